@@ -56,6 +56,13 @@ module pineappl
             character (c_char)  :: key(*), valju(*)
         end subroutine
 
+        logical (c_bool) function grid_set_remapper_from_str(grid, remapping) &
+                bind(c, name = 'pineappl_grid_set_remapper_from_str')
+            use iso_c_binding
+            type (c_ptr), value :: grid
+            character (c_char)  :: remapping(*)
+        end function
+
         subroutine grid_write(grid, filename) bind(c, name = 'pineappl_grid_write')
             use iso_c_binding
             type (c_ptr), value :: grid
@@ -150,6 +157,17 @@ contains
         call grid_set_key_value(grid%ptr, key // c_null_char, valju // c_null_char)
     end subroutine
 
+    logical function pineappl_grid_set_remapper_from_str(grid, remapping)
+        use iso_c_binding
+
+        implicit none
+
+        type (pineappl_grid), intent(in) :: grid
+        character (*), intent(in)        :: remapping
+
+        pineappl_grid_set_remapper_from_str = grid_set_remapper_from_str(grid%ptr, remapping // c_null_char)
+    end function
+
     subroutine pineappl_grid_write(grid, filename)
         use iso_c_binding
 
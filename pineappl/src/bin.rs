@@ -2,10 +2,12 @@
 
 use super::convert::{f64_from_usize, usize_from_f64};
 use float_cmp::approx_eq;
-use itertools::Itertools;
+use itertools::{izip, Itertools};
 use serde::{Deserialize, Serialize};
 use std::f64;
+use std::num::ParseFloatError;
 use std::ops::Range;
+use std::str::FromStr;
 use thiserror::Error;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -29,14 +31,16 @@ pub enum MergeBinError {
         rhs: f64,
     },
 
-    /// Returned by [`BinRemapper::merge_bins`] whenever it can not merge bins.
+    /// Returned by [`BinRemapper::merge_bins`] whenever the bins of the range are not
+    /// consecutive.
     #[error("can not merge bins with indices {0:?}")]
     NonConsecutiveRange(Range<usize>),
 
-    /// Returned by [`BinLimits::merge_bins`] whenever the range is outside the available bins.
+    /// Returned by [`BinLimits::merge_bins`] and [`BinRemapper::merge_bins`] whenever the range
+    /// is empty or outside the available bins.
     #[error("tried to merge bins with indices {range:?}, but there are only {bins} bins")]
     InvalidRange {
-        /// Range given to [`BinLimits::merge_bins`].
+        /// Range given to [`BinLimits::merge_bins`] or [`BinRemapper::merge_bins`].
         range: Range<usize>,
         /// Number of bins.
         bins: usize,
@@ -71,6 +75,41 @@ pub enum BinRemapperNewError {
     },
 }
 
+/// Error type that is returned by [`BinRemapper::parse`] and the implementation of [`FromStr`] for
+/// `BinRemapper`.
+#[derive(Debug, Error)]
+pub enum ParseBinRemapperError {
+    /// Returned if a bin limit is not a valid number.
+    #[error("unable to parse limit '{limit}'")]
+    InvalidLimit {
+        /// The string that was tried to be parsed as a limit.
+        limit: String,
+        /// The error returned when parsing the limit.
+        source: ParseFloatError,
+    },
+    /// Returned if the `|` syntax is used for the first dimension.
+    #[error("'|' syntax not meaningful for first dimension")]
+    PipeSyntaxFirstDimension,
+    /// Returned if the first entry of a `|` specification is empty.
+    #[error("empty repetition with '|'")]
+    EmptyRepetition,
+    /// Returned if an entry contains more than one `:`.
+    #[error("too many ':' found: '{0}'")]
+    TooManyColons(String),
+    /// Returned if the number of the `:` syntax can not be parsed.
+    #[error("unable to parse ':' syntax from: '{0}'")]
+    InvalidColonSyntax(String),
+    /// Returned if the `:` syntax removes all limits.
+    #[error("no limits due to ':' syntax")]
+    NoLimits,
+    /// Returned if a dimension has fewer `|` entries than required by the previous dimensions.
+    #[error("missing '|' specification: number of variants too small")]
+    MissingPipeSpecification,
+    /// Returned if the remapping string does not define any bin.
+    #[error("the remapping string does not define any bin")]
+    NoBins,
+}
+
 /// Structure for remapping bin limits.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct BinRemapper {
@@ -258,6 +297,174 @@ impl BinRemapper {
         }
     }
 
+    /// Parses the remapping string `remapping`, which defines the limits of each dimension
+    /// separated by `;`. Each dimension is given by a comma-separated list of bin edges; with `|`
+    /// different edges can be given for each bin of the previous dimensions, where an empty entry
+    /// repeats the previous one, and with `N:` and `:N` the first and last `N` bins of an entry are
+    /// removed. The normalization of each bin is the product of its widths, except for the
+    /// dimensions (counted starting from one) given in `ignore_obs_norm`, multiplied by `norm`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `remapping` can not be parsed.
+    ///
+    /// # Panics
+    ///
+    /// TODO
+    pub fn parse(
+        remapping: &str,
+        ignore_obs_norm: &[usize],
+        norm: f64,
+    ) -> Result<Self, ParseBinRemapperError> {
+        let remaps = parse_remaps(remapping)?;
+
+        let dimensions = remaps.len();
+        let mut normalizations = Vec::new();
+        let mut limits = Vec::new();
+        let mut buffer = Vec::with_capacity(dimensions);
+        let mut pipe_indices = vec![0; dimensions];
+        let mut last_indices = vec![0; dimensions];
+
+        'looop: for indices in remaps
+            .iter()
+            .map(|vec| {
+                0..vec
+                    .iter()
+                    .map(|vec| vec.len().saturating_sub(1))
+                    .max()
+                    .unwrap()
+            })
+            .multi_cartesian_product()
+        {
+            // calculate `pipe_indices`, which stores the indices for the second dimension of `remaps`
+            for d in 0..dimensions - 1 {
+                if indices[d] > last_indices[d] {
+                    for dp in d + 1..dimensions {
+                        if remaps[dp].len() != 1 {
+                            pipe_indices[dp] += 1;
+                        }
+                    }
+                }
+            }
+
+            last_indices.clone_from(&indices);
+
+            let mut normalization = 1.0;
+
+            for (d, (remap, &pipe_index, &i)) in izip!(&remaps, &pipe_indices, &indices).enumerate()
+            {
+                if let Some(r) = remap.get(pipe_index) {
+                    if r.len() <= (i + 1) {
+                        buffer.clear();
+
+                        // this index doesn't exist
+                        continue 'looop;
+                    }
+
+                    let left = r[i];
+                    let right = r[i + 1];
+
+                    buffer.push((left, right));
+
+                    if !ignore_obs_norm.iter().any(|dim| *dim == (d + 1)) {
+                        normalization *= right - left;
+                    }
+                } else {
+                    return Err(ParseBinRemapperError::MissingPipeSpecification);
+                }
+            }
+
+            limits.append(&mut buffer);
+            normalizations.push(norm * normalization);
+        }
+
+        if normalizations.is_empty() {
+            return Err(ParseBinRemapperError::NoBins);
+        }
+
+        Ok(Self {
+            normalizations,
+            limits,
+        })
+    }
+
+    /// Returns the limits of this remapper in the compact form understood by
+    /// [`BinRemapper::parse`]. The normalizations are not part of the string. If the bins can not
+    /// be represented in this form, for instance because they overlap or are not ordered, `None`
+    /// is returned.
+    #[must_use]
+    pub fn to_remapping_string(&self) -> Option<String> {
+        let dim = self.dimensions();
+        let bins: Vec<_> = self.limits.chunks_exact(dim).collect();
+        // for each bin the position of its limits in the edges of each dimension
+        let mut indices = vec![Vec::with_capacity(dim); bins.len()];
+        // for each dimension the largest number of bins of all variants
+        let mut sizes: Vec<usize> = Vec::with_capacity(dim);
+        let mut dimensions = Vec::with_capacity(dim);
+
+        for d in 0..dim {
+            // each variant stores the index that the `|` syntax assigns to it and its edges
+            let mut variants: Vec<(usize, Vec<f64>)> = Vec::new();
+
+            for (bin, limits) in bins.iter().enumerate() {
+                let (left, right) = limits[d];
+
+                if (bin == 0) || (bins[bin - 1][..d] != limits[..d]) {
+                    let variant = indices[bin]
+                        .iter()
+                        .zip(&sizes)
+                        .fold(0, |acc, (&index, &size)| acc * size + index);
+                    variants.push((variant, vec![left, right]));
+                    indices[bin].push(0);
+                } else {
+                    let edges = &mut variants.last_mut().unwrap_or_else(|| unreachable!()).1;
+                    let last = edges.len() - 1;
+
+                    // the same limits are repeated if only higher dimensions change
+                    if !approx_eq!(f64, edges[last - 1], left, ulps = 8)
+                        || !approx_eq!(f64, edges[last], right, ulps = 8)
+                    {
+                        if !approx_eq!(f64, edges[last], left, ulps = 8) {
+                            return None;
+                        }
+
+                        edges.push(right);
+                    }
+
+                    indices[bin].push(edges.len() - 2);
+                }
+            }
+
+            sizes.push(variants.iter().map(|(_, edges)| edges.len() - 1).max()?);
+            dimensions.push(variants);
+        }
+
+        let format_edges = |edges: &[f64]| edges.iter().map(ToString::to_string).join(",");
+        let string = dimensions
+            .iter()
+            .map(|variants| {
+                if variants.iter().all(|(_, edges)| *edges == variants[0].1) {
+                    format_edges(&variants[0].1)
+                } else {
+                    let mut strings = vec![String::new(); variants.last().map_or(0, |v| v.0 + 1)];
+
+                    for (variant, edges) in variants {
+                        strings[*variant] = format_edges(edges);
+                    }
+
+                    strings.join("|")
+                }
+            })
+            .join(";");
+
+        // make sure the string reproduces the limits, which isn't the case if the bins are not
+        // ordered as assumed above
+        Self::parse(&string, &[], 1.0)
+            .ok()
+            .filter(|remapper| remapper.limits == self.limits)
+            .map(|_| string)
+    }
+
     /// Return the number of bins.
     #[must_use]
     pub fn bins(&self) -> usize {
@@ -276,31 +483,92 @@ impl BinRemapper {
         &self.limits
     }
 
-    /// Merges the bins for the corresponding range together in a single one.
+    /// Returns the index of the first bin that contains the point given by `observables`, which
+    /// must have one entry for each dimension. Each bin includes its left limits and excludes its
+    /// right limits. If no bin contains the point, `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `observables` is not the same as the number of dimensions.
+    #[must_use]
+    pub fn index(&self, observables: &[f64]) -> Option<usize> {
+        self.indices(observables).into_iter().next()
+    }
+
+    /// Returns the indices of all bins that contain the point given by `observables`, which is
+    /// useful if bins overlap. See [`BinRemapper::index`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `observables` is not the same as the number of dimensions.
+    #[must_use]
+    pub fn indices(&self, observables: &[f64]) -> Vec<usize> {
+        let dim = self.dimensions();
+
+        assert_eq!(observables.len(), dim);
+
+        self.limits
+            .chunks_exact(dim)
+            .enumerate()
+            .filter(|(_, limits)| {
+                limits
+                    .iter()
+                    .zip(observables)
+                    .all(|(&(left, right), &observable)| {
+                        (left <= observable) && (observable < right)
+                    })
+            })
+            .map(|(bin, _)| bin)
+            .collect()
+    }
+
+    /// Merges the bins for the corresponding range together in a single one. The limits of the new
+    /// bin are the smallest ones that contain all merged bins and its normalization is the sum of
+    /// their normalizations. The bins must be consecutive, which means that they have the same
+    /// limits in all but the last dimension and that in the last dimension each bin starts where
+    /// the previous one ends.
     ///
     /// # Errors
     ///
-    /// When `range` refers to non-consecutive bins, an error is returned.
+    /// When `range` is empty or contains indices that do not correspond to bins, or when the bins
+    /// are not consecutive, an error is returned.
     pub fn merge_bins(&mut self, range: Range<usize>) -> Result<(), MergeBinError> {
-        if self
-            .slices()
-            .iter()
-            .any(|&(start, end)| (start <= range.start) && (range.end <= end))
-        {
-            for bin in range.start + 1..range.end {
-                self.normalizations[range.start] += self.normalizations[bin];
-            }
+        if range.is_empty() || (range.end > self.bins()) {
+            return Err(MergeBinError::InvalidRange {
+                range,
+                bins: self.bins(),
+            });
+        }
 
-            let dim = self.dimensions();
+        let dim = self.dimensions();
+        let consecutive = (range.start + 1..range.end).all(|bin| {
+            let lhs = &self.limits[dim * (bin - 1)..dim * bin];
+            let rhs = &self.limits[dim * bin..dim * (bin + 1)];
 
-            self.normalizations.drain(range.start + 1..range.end);
-            self.limits[dim * (range.start + 1) - 1].1 = self.limits[dim * range.end - 1].1;
-            self.limits.drain(dim * (range.start + 1)..dim * range.end);
+            (lhs[..dim - 1] == rhs[..dim - 1])
+                && approx_eq!(f64, lhs[dim - 1].1, rhs[dim - 1].0, ulps = 8)
+        });
 
-            Ok(())
-        } else {
-            Err(MergeBinError::NonConsecutiveRange(range))
+        if !consecutive {
+            return Err(MergeBinError::NonConsecutiveRange(range));
+        }
+
+        for bin in range.start + 1..range.end {
+            self.normalizations[range.start] += self.normalizations[bin];
+
+            for d in 0..dim {
+                let (left, right) = self.limits[dim * bin + d];
+                let limits = &mut self.limits[dim * range.start + d];
+
+                limits.0 = limits.0.min(left);
+                limits.1 = limits.1.max(right);
+            }
         }
+
+        self.normalizations.drain(range.start + 1..range.end);
+        self.limits.drain(dim * (range.start + 1)..dim * range.end);
+
+        Ok(())
     }
 
     /// Merge the `BinRemapper` of `other` into `self` on the right-hand-side.
@@ -319,7 +587,6 @@ impl BinRemapper {
             });
         }
 
-        // TODO: we shouldn't allow overlapping bins
         self.normalizations.extend_from_slice(&other.normalizations);
         self.limits.extend_from_slice(&other.limits);
 
@@ -366,6 +633,136 @@ impl BinRemapper {
             self.limits.drain((range.start * dim)..(range.end * dim));
         }
     }
+
+    /// Replaces the bins with the ones given by `bin_indices`, in that order. Indices may appear
+    /// more than once and not every bin must be selected; limits and normalizations are taken from
+    /// the corresponding bins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the `bin_indices` is larger or equal the number of bins.
+    pub fn select_bins(&mut self, bin_indices: &[usize]) {
+        let dim = self.dimensions();
+
+        self.normalizations = bin_indices
+            .iter()
+            .map(|&bin| self.normalizations[bin])
+            .collect();
+        self.limits = bin_indices
+            .iter()
+            .flat_map(|&bin| self.limits[(bin * dim)..((bin + 1) * dim)].iter().copied())
+            .collect();
+    }
+}
+
+/// Splits the remapping string into the edges of each dimension and each `|` entry, resolving
+/// the repetitions and `:` syntax.
+fn parse_remaps(remapping: &str) -> Result<Vec<Vec<Vec<f64>>>, ParseBinRemapperError> {
+    let remaps: Result<Vec<Vec<Vec<_>>>, _> = remapping
+        .split(';')
+        .map(|string| {
+            string
+                .split('|')
+                .map(|string| {
+                    string
+                        .find(':')
+                        .map_or(string, |index| {
+                            let (lhs, rhs) = string.split_at(index);
+                            let rhs = &rhs[1..]; // remove ':' which is contained with `split_at`
+
+                            // extract the part that doesn't belong to the ':' specification
+                            match (lhs.trim().parse::<usize>(), rhs.trim().parse::<usize>()) {
+                                (Err(_), Ok(_)) => lhs,
+                                (Ok(_), Err(_)) => rhs,
+                                _ => "",
+                            }
+                        })
+                        .split(',')
+                        .filter_map(|string| {
+                            let string = string.trim();
+                            if string.is_empty() {
+                                None
+                            } else {
+                                Some(string.parse::<f64>().map_err(|source| {
+                                    ParseBinRemapperError::InvalidLimit {
+                                        limit: string.to_string(),
+                                        source,
+                                    }
+                                }))
+                            }
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+    let mut remaps = remaps?;
+
+    if remaps[0].len() != 1 {
+        return Err(ParseBinRemapperError::PipeSyntaxFirstDimension);
+    }
+
+    // go over `remaps` again, and repeat previous entries as requested with the `|` syntax
+    for vec in &mut remaps {
+        for i in 1..vec.len() {
+            if vec[i].is_empty() {
+                if vec[i - 1].is_empty() {
+                    return Err(ParseBinRemapperError::EmptyRepetition);
+                }
+
+                let previous = vec[i - 1].clone();
+                vec[i] = previous;
+            }
+        }
+    }
+
+    // go over `remaps` again, this time remove bin as requested with the `:N` or `N:` syntax
+    for (vec, string) in remaps.iter_mut().zip(remapping.split(';')) {
+        for (vec, string) in vec.iter_mut().zip(string.split('|')) {
+            let (lhs, rhs) = {
+                let split: Vec<_> = string.split(':').collect();
+
+                if split.len() == 1 {
+                    // there's no colon
+                    continue;
+                }
+
+                if split.len() != 2 {
+                    return Err(ParseBinRemapperError::TooManyColons(string.to_string()));
+                }
+
+                (split[0].parse::<usize>(), split[1].parse::<usize>())
+            };
+
+            if let Ok(num) = rhs {
+                vec.truncate(vec.len().saturating_sub(num));
+            }
+
+            if let Ok(num) = lhs {
+                vec.drain(0..num.min(vec.len()));
+            }
+
+            if lhs.is_err() && rhs.is_err() {
+                return Err(ParseBinRemapperError::InvalidColonSyntax(
+                    string.to_string(),
+                ));
+            }
+
+            if vec.len() <= 1 {
+                return Err(ParseBinRemapperError::NoLimits);
+            }
+        }
+    }
+
+    Ok(remaps)
+}
+
+impl FromStr for BinRemapper {
+    type Err = ParseBinRemapperError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, &[], 1.0)
+    }
 }
 
 impl PartialEq<Self> for BinRemapper {
@@ -483,10 +880,10 @@ impl BinLimits {
     ///
     /// # Errors
     ///
-    /// When `bins` contains any indices that do not correspond to bins this method returns an
-    /// error.
+    /// When `range` is empty or contains any indices that do not correspond to bins this method
+    /// returns an error.
     pub fn merge_bins(&mut self, range: Range<usize>) -> Result<(), MergeBinError> {
-        if range.end > self.bins() {
+        if range.is_empty() || (range.end > self.bins()) {
             return Err(MergeBinError::InvalidRange {
                 range,
                 bins: self.bins(),
@@ -854,6 +1251,7 @@ mod test {
     fn merge_bins_error() {
         let mut limits = BinLimits::new(vec![0.0, 0.4, 0.7, 0.9, 1.0]);
         assert!(limits.merge_bins(0..5).is_err());
+        assert!(limits.merge_bins(2..2).is_err());
     }
 
     #[test]
@@ -906,6 +1304,40 @@ mod test {
         assert_eq!(remapper.normalizations(), vec![1.0; 4]);
     }
 
+    #[test]
+    fn bin_remapper_index() {
+        let remapper = BinRemapper::new(
+            vec![1.0; 4],
+            vec![
+                (0.0, 1.0),
+                (0.0, 2.0),
+                (0.0, 1.0),
+                (2.0, 5.0),
+                (1.0, 3.0),
+                (0.0, 1.0),
+                (1.0, 3.0),
+                (1.0, 5.0),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(remapper.index(&[0.5, 0.0]), Some(0));
+        assert_eq!(remapper.index(&[0.5, 2.0]), Some(1));
+        assert_eq!(remapper.index(&[0.5, 4.999]), Some(1));
+        assert_eq!(remapper.index(&[1.0, 0.5]), Some(2));
+        assert_eq!(remapper.index(&[2.5, 3.0]), Some(3));
+        assert_eq!(remapper.index(&[0.5, 5.0]), None);
+        assert_eq!(remapper.index(&[3.0, 0.5]), None);
+        assert_eq!(remapper.index(&[-1.0, 0.5]), None);
+        assert!(remapper.indices(&[3.0, 0.5]).is_empty());
+
+        let overlapping = BinRemapper::new(vec![1.0; 2], vec![(0.0, 2.0), (1.0, 3.0)]).unwrap();
+
+        assert_eq!(overlapping.index(&[1.5]), Some(0));
+        assert_eq!(overlapping.indices(&[1.5]), [0, 1]);
+        assert_eq!(overlapping.indices(&[2.5]), [1]);
+    }
+
     #[test]
     fn bin_remapper_merge_bins() {
         let mut remapper = BinRemapper::new(
@@ -922,14 +1354,238 @@ mod test {
         assert_eq!(remapper.slices(), [(0, 1)]);
     }
 
-    //#[test]
-    //#[ignore] // FIXME: there's a bug in the `slices` method
-    //#[should_panic]
-    //fn bin_remapper_merge_bins_panic() {
-    //    let mut remapper =
-    //        BinRemapper::new(vec![1.0; 3], vec![(0.0, 0.25), (0.5, 0.75), (0.75, 1.0)]).unwrap();
+    #[test]
+    fn bin_remapper_merge_non_consecutive_bins() {
+        let mut remapper = BinRemapper::new(
+            vec![1.0; 4],
+            vec![
+                (0.0, 1.0),
+                (0.0, 0.25),
+                (0.0, 1.0),
+                (0.25, 0.5),
+                (0.0, 1.0),
+                (0.75, 1.0),
+                (1.0, 2.0),
+                (0.75, 1.0),
+            ],
+        )
+        .unwrap();
+
+        // the bins have a gap in the last dimension
+        assert!(matches!(
+            remapper.merge_bins(1..3),
+            Err(MergeBinError::NonConsecutiveRange(range)) if range == (1..3)
+        ));
+        // the bins have different limits in the first dimension
+        assert!(matches!(
+            remapper.merge_bins(2..4),
+            Err(MergeBinError::NonConsecutiveRange(range)) if range == (2..4)
+        ));
+
+        remapper.merge_bins(0..2).unwrap();
+        assert_eq!(remapper.bins(), 3);
+        assert_eq!(
+            remapper.limits(),
+            [
+                (0.0, 1.0),
+                (0.0, 0.5),
+                (0.0, 1.0),
+                (0.75, 1.0),
+                (1.0, 2.0),
+                (0.75, 1.0)
+            ]
+        );
+        assert_eq!(remapper.normalizations(), [2.0, 1.0, 1.0]);
+
+        // an empty range is rejected instead of panicking
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 2..1;
+        assert!(matches!(
+            remapper.merge_bins(reversed.clone()),
+            Err(MergeBinError::InvalidRange { range, bins }) if (range == reversed) && (bins == 3)
+        ));
+        assert!(matches!(
+            remapper.merge_bins(1..1),
+            Err(MergeBinError::InvalidRange { range, bins }) if (range == (1..1)) && (bins == 3)
+        ));
+        assert!(matches!(
+            remapper.merge_bins(0..4),
+            Err(MergeBinError::InvalidRange { range, bins }) if (range == (0..4)) && (bins == 3)
+        ));
+    }
+
+    #[test]
+    fn bin_remapper_parse() {
+        let remapper =
+            BinRemapper::parse("0,1,2;0,2,4;1,2,3,4,5|:3|5:1,2,3,4,5,8,9|2:2", &[2], 5.0).unwrap();
+
+        assert_eq!(remapper.dimensions(), 3);
+        assert_eq!(
+            remapper.limits(),
+            [
+                (0.0, 1.0),
+                (0.0, 2.0),
+                (1.0, 2.0),
+                (0.0, 1.0),
+                (0.0, 2.0),
+                (2.0, 3.0),
+                (0.0, 1.0),
+                (0.0, 2.0),
+                (3.0, 4.0),
+                (0.0, 1.0),
+                (0.0, 2.0),
+                (4.0, 5.0),
+                (0.0, 1.0),
+                (2.0, 4.0),
+                (1.0, 2.0),
+                (1.0, 2.0),
+                (0.0, 2.0),
+                (8.0, 9.0),
+                (1.0, 2.0),
+                (2.0, 4.0),
+                (3.0, 4.0),
+                (1.0, 2.0),
+                (2.0, 4.0),
+                (4.0, 5.0),
+            ]
+        );
+        assert_eq!(remapper.normalizations(), [5.0; 8]);
+
+        assert_eq!(
+            remapper.to_remapping_string().unwrap(),
+            "0,1,2;0,2,4;1,2,3,4,5|1,2|8,9|3,4,5"
+        );
+        assert_eq!(
+            "0,1,2;0,2,4;1,2,3,4,5|1,2|8,9|3,4,5"
+                .parse::<BinRemapper>()
+                .unwrap()
+                .limits(),
+            remapper.limits()
+        );
+    }
 
-    //    //assert_eq!(remapper.slices(), [(0, 1), (1, 3)]);
-    //    remapper.merge_bins(0..3).unwrap();
-    //}
+    #[test]
+    fn bin_remapper_parse_errors() {
+        assert!(matches!(
+            "0,1,a".parse::<BinRemapper>(),
+            Err(ParseBinRemapperError::InvalidLimit { limit, .. }) if limit == "a"
+        ));
+        assert!(matches!(
+            "|0,1,2".parse::<BinRemapper>(),
+            Err(ParseBinRemapperError::PipeSyntaxFirstDimension)
+        ));
+        assert!(matches!(
+            "0,1,2;0,2,4;||".parse::<BinRemapper>(),
+            Err(ParseBinRemapperError::EmptyRepetition)
+        ));
+        assert!(matches!(
+            "0,1,2;0,2,4;1,2,3,4,5|::".parse::<BinRemapper>(),
+            Err(ParseBinRemapperError::TooManyColons(string)) if string == "::"
+        ));
+        assert!(matches!(
+            "0,1,2;0,2,4;1,2,3,4,5|2.5:|:3|:3".parse::<BinRemapper>(),
+            Err(ParseBinRemapperError::InvalidColonSyntax(string)) if string == "2.5:"
+        ));
+        assert!(matches!(
+            "0,1,2;0,2,4;1,2,3,4,5|:2.5|:3|:3".parse::<BinRemapper>(),
+            Err(ParseBinRemapperError::InvalidColonSyntax(string)) if string == ":2.5"
+        ));
+        assert!(matches!(
+            "0,1,2;0,2,4;1,2,3,4,5|:4|:3|:3".parse::<BinRemapper>(),
+            Err(ParseBinRemapperError::NoLimits)
+        ));
+        assert!(matches!(
+            "0,1,2;0,2,4;1,2,3|4,5,6|7,8,9".parse::<BinRemapper>(),
+            Err(ParseBinRemapperError::MissingPipeSpecification)
+        ));
+        assert!(matches!(
+            "".parse::<BinRemapper>(),
+            Err(ParseBinRemapperError::NoBins)
+        ));
+    }
+
+    #[test]
+    fn bin_remapper_to_remapping_string() {
+        let remapper = BinRemapper::new(
+            vec![1.0; 3],
+            vec![
+                (0.0, 1.0),
+                (0.0, 1.0),
+                (0.0, 1.0),
+                (1.0, 3.0),
+                (1.0, 2.0),
+                (0.0, 2.0),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(remapper.to_remapping_string().unwrap(), "0,1,2;0,1,3|0,2");
+
+        // the second bin of the first dimension has more bins in the second dimension, which
+        // requires an empty `|` entry for a combination of bins that doesn't exist
+        let remapper = BinRemapper::new(
+            vec![1.0; 3],
+            vec![
+                (0.0, 1.0),
+                (0.0, 1.0),
+                (0.0, 1.0),
+                (1.0, 2.0),
+                (0.0, 1.0),
+                (1.0, 2.0),
+                (1.0, 2.0),
+                (1.0, 2.0),
+                (2.0, 3.0),
+            ],
+        )
+        .unwrap();
+        let string = remapper.to_remapping_string().unwrap();
+
+        assert_eq!(string, "0,1,2;0,1|0,1,2;0,1||1,2|2,3");
+        assert_eq!(
+            string.parse::<BinRemapper>().unwrap().limits(),
+            remapper.limits()
+        );
+
+        // overlapping bins can not be represented
+        let remapper = BinRemapper::new(vec![1.0; 2], vec![(0.0, 2.0), (1.0, 3.0)]).unwrap();
+
+        assert_eq!(remapper.to_remapping_string(), None);
+
+        // neither can unordered bins
+        let remapper = BinRemapper::new(vec![1.0; 2], vec![(1.0, 2.0), (0.0, 1.0)]).unwrap();
+
+        assert_eq!(remapper.to_remapping_string(), None);
+    }
+
+    #[test]
+    fn bin_remapper_select_bins() {
+        let mut remapper = BinRemapper::new(
+            vec![1.0, 2.0, 3.0],
+            vec![
+                (0.0, 1.0),
+                (0.0, 0.5),
+                (1.0, 2.0),
+                (0.5, 1.0),
+                (2.0, 3.0),
+                (0.0, 1.0),
+            ],
+        )
+        .unwrap();
+
+        remapper.select_bins(&[2, 0, 2]);
+        assert_eq!(remapper.bins(), 3);
+        assert_eq!(remapper.dimensions(), 2);
+        assert_eq!(
+            remapper.limits(),
+            [
+                (2.0, 3.0),
+                (0.0, 1.0),
+                (0.0, 1.0),
+                (0.0, 0.5),
+                (2.0, 3.0),
+                (0.0, 1.0)
+            ]
+        );
+        assert_eq!(remapper.normalizations(), [3.0, 1.0, 3.0]);
+    }
 }
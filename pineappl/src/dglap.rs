@@ -0,0 +1,1230 @@
+//! Built-in generator of evolution kernel operators (EKOs) for [`Grid::evolve`], which solves the
+//! DGLAP equations of QCD at leading (LO) or next-to-leading order (NLO) directly in `x` space.
+//!
+//! The momentum densities `x f(x)` are interpolated with piecewise cubic polynomials in `ln(x)`,
+//! whose nodes are the `x`-grid coordinates of the [`Grid`], and vanish at `x = 1`. The evolution
+//! uses a variable flavour number scheme: the number of active quark flavours changes at the
+//! squared quark masses given in [`Theory::thresholds`], and heavy quarks vanish below their
+//! threshold. At the orders considered here the parton distribution functions are continuous
+//! across the thresholds.
+//!
+//! The same splitting functions are available for [`Grid::add_scale_logs`] with
+//! [`splitting_kernel`].
+//!
+//! [`Grid`]: super::grid::Grid
+//! [`Grid::add_scale_logs`]: super::grid::Grid::add_scale_logs
+//! [`Grid::evolve`]: super::grid::Grid::evolve
+
+// the splitting functions are much easier to compare with the literature without `mul_add`
+#![allow(clippy::suboptimal_flops)]
+
+use super::evolution::{EvolveInfo, OperatorInfo};
+use super::grid::SplittingKernel;
+use ndarray::{s, Array2, Array5, ArrayView2};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+const CF: f64 = 4.0 / 3.0;
+const CA: f64 = 3.0;
+const TR: f64 = 0.5;
+const ZETA3: f64 = 1.202_056_903_159_594_3;
+
+/// Particle identifiers of the operators, the gluon is in the middle at index `6`.
+const PIDS: [i32; 13] = [-6, -5, -4, -3, -2, -1, 21, 1, 2, 3, 4, 5, 6];
+const GLUON: usize = 6;
+
+/// Degree of the interpolation polynomials.
+const DEGREE: usize = 3;
+/// Number of Gauss-Legendre points used for each interval between two `x`-grid nodes.
+const GAUSS_POINTS: usize = 16;
+/// Largest step in `ln(mu2)` of the Runge-Kutta solver.
+const STEP: f64 = 0.2;
+
+/// Perturbative order of the DGLAP evolution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PerturbativeOrder {
+    /// Leading order.
+    Lo,
+    /// Next-to-leading order.
+    Nlo,
+}
+
+/// Parameters of the DGLAP evolution.
+#[derive(Clone, Debug)]
+pub struct Theory {
+    /// Perturbative order of the splitting functions.
+    pub order: PerturbativeOrder,
+    /// Squared factorization scale of the `FkTable`, which is the starting scale of the evolution.
+    pub fac0: f64,
+    /// Squared masses of the charm, bottom and top quark. Above each threshold the number of
+    /// active flavours increases by one, starting from three.
+    pub thresholds: [f64; 3],
+}
+
+impl Theory {
+    fn nf(&self, mu2: f64) -> usize {
+        3 + self.thresholds.iter().filter(|&&m2| m2 < mu2).count()
+    }
+}
+
+/// Computes the evolution kernel operator for the `x`-grid coordinates, particle identifiers and
+/// squared factorization scales given in `info`.
+///
+/// The parameter `info` can be obtained from [`Grid::evolve_info`], and the returned operator can
+/// be passed to [`Grid::evolve`] together with the returned [`OperatorInfo`].
+///
+/// Like the operators expected by [`Grid::evolve`], the operator evolves parton densities `f(x)`,
+/// not momentum densities `x f(x)`. The squared factorization scales are multiplied with
+/// `xif * xif`, and the squared renormalization scales in the returned [`OperatorInfo`] with
+/// `xir * xir`. The function `alphas` must return the strong coupling for a given squared scale;
+/// it is used both for the evolution and for the strong couplings in the returned
+/// [`OperatorInfo`].
+///
+/// [`Grid::evolve`]: super::grid::Grid::evolve
+/// [`Grid::evolve_info`]: super::grid::Grid::evolve_info
+pub fn evolution_operator(
+    info: &EvolveInfo,
+    theory: &Theory,
+    xir: f64,
+    xif: f64,
+    alphas: &mut dyn FnMut(f64) -> f64,
+) -> (OperatorInfo, Array5<f64>) {
+    let fac1: Vec<_> = info.fac1.iter().map(|&fac| xif * xif * fac).collect();
+    let ren1: Vec<_> = info.ren1.iter().map(|&ren| xir * xir * ren).collect();
+    let nf0 = theory.nf(theory.fac0);
+    let pids0: Vec<_> = PIDS
+        .iter()
+        .copied()
+        .filter(|&pid| (pid == 21) || (pid.unsigned_abs() as usize <= nf0))
+        .collect();
+    let pids1: Vec<_> = PIDS
+        .iter()
+        .copied()
+        .filter(|&pid| {
+            info.pids1
+                .iter()
+                .any(|&other| (other == pid) || ((pid == 21) && (other == 0)))
+        })
+        .collect();
+    let x = info.x1.clone();
+    let n = x.len();
+
+    let mut evolution = Evolution::new(&x, theory);
+    let mut operator = Array5::zeros((fac1.len(), pids1.len(), n, pids0.len(), n));
+
+    // evolve upwards and downwards from the starting scale, in both cases step by step from the
+    // closest scale to the farthest one
+    let mut indices: Vec<_> = (0..fac1.len()).collect();
+    indices.sort_by(|&a, &b| fac1[a].total_cmp(&fac1[b]));
+    let split = indices.partition_point(|&index| fac1[index] < theory.fac0);
+    let (down, up) = indices.split_at(split);
+
+    for path in [down.iter().rev().copied().collect::<Vec<_>>(), up.to_vec()] {
+        let mut flavour = evolution.start(nf0);
+        let mut mu2 = theory.fac0;
+
+        for index in path {
+            flavour = evolution.evolve(flavour, mu2, fac1[index], alphas);
+            mu2 = fac1[index];
+
+            for (pid1_idx, pid1) in pids1.iter().enumerate() {
+                let row = pid_index(*pid1) * n;
+
+                for (pid0_idx, pid0) in pids0.iter().enumerate() {
+                    let col = pid_index(*pid0) * n;
+
+                    // the evolution acts on `x f(x)`, but the operator must act on `f(x)`
+                    for ((i, j), value) in operator
+                        .slice_mut(s![index, pid1_idx, .., pid0_idx, ..])
+                        .indexed_iter_mut()
+                    {
+                        *value = flavour[[row + i, col + j]] * x[j] / x[i];
+                    }
+                }
+            }
+        }
+    }
+
+    let alphas = ren1.iter().map(|&mur2| alphas(mur2)).collect();
+
+    (
+        OperatorInfo {
+            fac0: theory.fac0,
+            pids0,
+            x0: x.clone(),
+            fac1,
+            pids1,
+            x1: x,
+            ren1,
+            alphas,
+            xir,
+            xif,
+            lumi_id_types: "pdg_mc_ids".to_string(),
+        },
+        operator,
+    )
+}
+
+/// Returns the splitting functions of `order` for `nf` active flavours.
+///
+/// The splitting functions multiply `alphas^1` for [`PerturbativeOrder::Lo`] and `alphas^2` for
+/// [`PerturbativeOrder::Nlo`] and have the form expected by [`Grid::add_scale_logs`]. The parton
+/// ids are PDG Monte Carlo ids, where the gluon may also be given as `0`. The PDFs are
+/// interpolated like in [`evolution_operator`], using the `x` values given to the kernel as nodes.
+///
+/// [`Grid::add_scale_logs`]: super::grid::Grid::add_scale_logs
+#[must_use]
+pub fn splitting_kernel(order: PerturbativeOrder, nf: usize) -> Box<SplittingKernel> {
+    let (kernels, power) = match order {
+        PerturbativeOrder::Lo => (&LO, 1),
+        PerturbativeOrder::Nlo => (&NLO, 2),
+    };
+    // the matrices are given in the normalization of `alphas / (2 pi)`
+    let normalization = (2.0 * PI).powi(-power);
+    // the generators of the evolution for each `x` grid, which usually repeat for every subgrid
+    let cache: RefCell<Vec<(Vec<f64>, Array2<f64>)>> = RefCell::new(Vec::new());
+
+    Box::new(move |pid, x| {
+        let pid = if pid == 0 { 21 } else { pid };
+
+        if !PIDS.contains(&pid) {
+            return Vec::new();
+        }
+
+        let mut indices: Vec<_> = (0..x.len()).collect();
+        indices.sort_by(|&a, &b| x[a].total_cmp(&x[b]));
+        let sorted: Vec<_> = indices.iter().map(|&index| x[index]).collect();
+        let n = sorted.len();
+
+        let mut cache = cache.borrow_mut();
+        let position = cache
+            .iter()
+            .position(|(nodes, _)| *nodes == sorted)
+            .unwrap_or_else(|| {
+                let interpolation = Interpolation::new(&sorted);
+                #[allow(clippy::cast_precision_loss)]
+                let sectors = Sectors::new(&interpolation, nf as f64, kernels);
+                let identity = Array2::eye(PIDS.len() * n);
+
+                cache.push((sorted.clone(), apply(&sectors, nf, identity.view(), n)));
+                cache.len() - 1
+            });
+        let generator = &cache[position].1;
+        let row = pid_index(pid) * n;
+
+        PIDS.iter()
+            .enumerate()
+            .filter_map(|(index, &other)| {
+                let col = index * n;
+                let mut matrix = Array2::zeros((n, n));
+
+                // the generator acts on `x f(x)`, but the kernel must act on `f(x)`
+                for i in 0..n {
+                    for k in 0..n {
+                        matrix[[indices[i], indices[k]]] =
+                            normalization * generator[[row + i, col + k]] * sorted[k] / sorted[i];
+                    }
+                }
+
+                matrix
+                    .iter()
+                    .any(|&value| value != 0.0)
+                    .then_some((other, matrix))
+            })
+            .collect()
+    })
+}
+
+fn pid_index(pid: i32) -> usize {
+    PIDS.iter().position(|&other| other == pid).unwrap()
+}
+
+/// Convolutions of the splitting functions with the interpolation polynomials for a fixed number
+/// of active flavours, given in the normalization of the strong coupling `alphas / (2 pi)`.
+struct Matrices {
+    /// Leading-order matrices of the non-singlet and singlet sectors.
+    lo: Sectors,
+    /// Next-to-leading order matrices, if needed.
+    nlo: Option<Sectors>,
+}
+
+/// Matrices of the non-singlet (plus and minus) and singlet sectors. The singlet matrix is a
+/// block matrix acting on the quark singlet and the gluon.
+struct Sectors {
+    ns_plus: Array2<f64>,
+    ns_minus: Array2<f64>,
+    singlet: Array2<f64>,
+}
+
+struct Evolution<'a> {
+    theory: &'a Theory,
+    interpolation: Interpolation,
+    matrices: HashMap<usize, Matrices>,
+}
+
+impl<'a> Evolution<'a> {
+    fn new(x: &[f64], theory: &'a Theory) -> Self {
+        Self {
+            theory,
+            interpolation: Interpolation::new(x),
+            matrices: HashMap::new(),
+        }
+    }
+
+    /// Returns the operator in flavour space at the starting scale, which is the identity for all
+    /// active partons.
+    fn start(&self, nf: usize) -> Array2<f64> {
+        let n = self.interpolation.len();
+        let mut flavour = Array2::zeros((PIDS.len() * n, PIDS.len() * n));
+
+        for (index, &pid) in PIDS.iter().enumerate() {
+            if (pid == 21) || (pid.unsigned_abs() as usize <= nf) {
+                for i in 0..n {
+                    flavour[[index * n + i, index * n + i]] = 1.0;
+                }
+            }
+        }
+
+        flavour
+    }
+
+    /// Evolves the operator in flavour space `flavour` from `from` to `to`, stopping at each
+    /// threshold in between to change the number of active flavours.
+    fn evolve(
+        &mut self,
+        mut flavour: Array2<f64>,
+        from: f64,
+        to: f64,
+        alphas: &mut dyn FnMut(f64) -> f64,
+    ) -> Array2<f64> {
+        let mut scales = vec![from];
+        let (lower, upper) = if from < to { (from, to) } else { (to, from) };
+        let mut thresholds: Vec<_> = self
+            .theory
+            .thresholds
+            .iter()
+            .copied()
+            .filter(|&m2| (lower < m2) && (m2 < upper))
+            .collect();
+
+        if from > to {
+            thresholds.reverse();
+        }
+
+        scales.extend(thresholds);
+        scales.push(to);
+
+        for window in scales.windows(2) {
+            let (mu2a, mu2b) = (window[0], window[1]);
+            let nf = self.theory.nf((mu2a * mu2b).sqrt());
+            let sectors = self.solve(nf, mu2a, mu2b, alphas);
+
+            flavour = apply(&sectors, nf, flavour.view(), self.interpolation.len());
+        }
+
+        flavour
+    }
+
+    /// Solves the DGLAP equations for `nf` active flavours from `mu2a` to `mu2b` with a
+    /// fourth-order Runge-Kutta method.
+    fn solve(
+        &mut self,
+        nf: usize,
+        mu2a: f64,
+        mu2b: f64,
+        alphas: &mut dyn FnMut(f64) -> f64,
+    ) -> Sectors {
+        let interpolation = &self.interpolation;
+        let order = self.theory.order;
+        let matrices = self
+            .matrices
+            .entry(nf)
+            .or_insert_with(|| Matrices::new(interpolation, order, nf));
+
+        let n = interpolation.len();
+        let mut result = Sectors {
+            ns_plus: Array2::eye(n),
+            ns_minus: Array2::eye(n),
+            singlet: Array2::eye(2 * n),
+        };
+
+        let (ta, tb) = (mu2a.ln(), mu2b.ln());
+        // the casts are fine, because the number of steps is small and positive
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let steps = ((tb - ta).abs() / STEP).ceil() as usize;
+        #[allow(clippy::cast_precision_loss)]
+        let h = (tb - ta) / steps as f64;
+        let mut coupling = |t: f64| alphas(t.exp()) / (2.0 * PI);
+
+        for step in 0..steps {
+            #[allow(clippy::cast_precision_loss)]
+            let t = h.mul_add(step as f64, ta);
+            let a = [coupling(t), coupling(0.5 * h + t), coupling(h + t)];
+
+            result.ns_plus = rk4(&result.ns_plus, h, &a, |a| {
+                matrices.generator(a, |s| &s.ns_plus)
+            });
+            result.ns_minus = rk4(&result.ns_minus, h, &a, |a| {
+                matrices.generator(a, |s| &s.ns_minus)
+            });
+            result.singlet = rk4(&result.singlet, h, &a, |a| {
+                matrices.generator(a, |s| &s.singlet)
+            });
+        }
+
+        result
+    }
+}
+
+impl Matrices {
+    fn new(interpolation: &Interpolation, order: PerturbativeOrder, nf: usize) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let nf = nf as f64;
+        let lo = Sectors::new(interpolation, nf, &LO);
+        let nlo = (order == PerturbativeOrder::Nlo).then(|| Sectors::new(interpolation, nf, &NLO));
+
+        Self { lo, nlo }
+    }
+
+    /// Returns the matrix on the right-hand side of the DGLAP equation for the coupling `a`.
+    fn generator(&self, a: f64, sector: impl Fn(&Sectors) -> &Array2<f64>) -> Array2<f64> {
+        let mut result = a * sector(&self.lo);
+
+        if let Some(nlo) = &self.nlo {
+            result.scaled_add(a * a, sector(nlo));
+        }
+
+        result
+    }
+}
+
+/// Performs a single step of size `h` of the fourth-order Runge-Kutta method for the equation
+/// `dE/dt = A(t) E`, where `a` contains the couplings at the beginning, the middle and the end of
+/// the step and `generator` returns `A` for a given coupling.
+fn rk4(
+    e: &Array2<f64>,
+    h: f64,
+    a: &[f64; 3],
+    generator: impl Fn(f64) -> Array2<f64>,
+) -> Array2<f64> {
+    let (m0, m1, m2) = (generator(a[0]), generator(a[1]), generator(a[2]));
+    let k1 = m0.dot(e);
+    let k2 = m1.dot(&(e + &(0.5 * h * &k1)));
+    let k3 = m1.dot(&(e + &(0.5 * h * &k2)));
+    let k4 = m2.dot(&(e + &(h * &k3)));
+
+    e + &((h / 6.0) * &(k1 + 2.0 * k2 + 2.0 * k3 + k4))
+}
+
+/// Applies the evolution given by `sectors` for `nf` active flavours to the operator in flavour
+/// space `flavour`. All partons that are not active afterwards vanish.
+fn apply(sectors: &Sectors, nf: usize, flavour: ArrayView2<f64>, n: usize) -> Array2<f64> {
+    let rows = |index: usize| flavour.slice(s![index * n..(index + 1) * n, ..]);
+    #[allow(clippy::cast_precision_loss)]
+    let nf_f64 = nf as f64;
+
+    let plus: Vec<_> = (1..=nf)
+        .map(|q| &rows(GLUON + q) + &rows(GLUON - q))
+        .collect();
+    let minus: Vec<_> = (1..=nf)
+        .map(|q| &rows(GLUON + q) - &rows(GLUON - q))
+        .collect();
+    let mut sigma = Array2::zeros(plus[0].raw_dim());
+
+    for plus in &plus {
+        sigma += plus;
+    }
+
+    let gluon = rows(GLUON);
+    let (qq, qg) = (
+        sectors.singlet.slice(s![..n, ..n]),
+        sectors.singlet.slice(s![..n, n..]),
+    );
+    let (gq, gg) = (
+        sectors.singlet.slice(s![n.., ..n]),
+        sectors.singlet.slice(s![n.., n..]),
+    );
+    let new_sigma = qq.dot(&sigma) + qg.dot(&gluon);
+    let new_gluon = gq.dot(&sigma) + gg.dot(&gluon);
+
+    let mut result = Array2::zeros(flavour.raw_dim());
+    result
+        .slice_mut(s![GLUON * n..(GLUON + 1) * n, ..])
+        .assign(&new_gluon);
+
+    for (q, (plus, minus)) in (1..=nf).zip(plus.iter().zip(&minus)) {
+        // the non-singlet combination `q+ - sigma / nf` evolves with the plus operator
+        let new_plus = sectors.ns_plus.dot(&(plus - &(&sigma / nf_f64))) + &new_sigma / nf_f64;
+        let new_minus = sectors.ns_minus.dot(minus);
+
+        result
+            .slice_mut(s![(GLUON + q) * n..(GLUON + q + 1) * n, ..])
+            .assign(&(0.5 * (&new_plus + &new_minus)));
+        result
+            .slice_mut(s![(GLUON - q) * n..(GLUON - q + 1) * n, ..])
+            .assign(&(0.5 * (new_plus - new_minus)));
+    }
+
+    result
+}
+
+impl Sectors {
+    fn new(interpolation: &Interpolation, nf: f64, kernels: &Kernels) -> Self {
+        let mut matrices = interpolation
+            .convolutions(
+                &[
+                    kernels.ns_plus,
+                    kernels.ns_minus,
+                    kernels.qq,
+                    kernels.qg,
+                    kernels.gq,
+                    kernels.gg,
+                ],
+                nf,
+            )
+            .into_iter();
+        let ns_plus = matrices.next().unwrap();
+        let ns_minus = matrices.next().unwrap();
+        let (qq, qg, gq, gg) = (
+            matrices.next().unwrap(),
+            matrices.next().unwrap(),
+            matrices.next().unwrap(),
+            matrices.next().unwrap(),
+        );
+
+        let n = interpolation.len();
+        let mut singlet = Array2::zeros((2 * n, 2 * n));
+        singlet.slice_mut(s![..n, ..n]).assign(&qq);
+        singlet.slice_mut(s![..n, n..]).assign(&qg);
+        singlet.slice_mut(s![n.., ..n]).assign(&gq);
+        singlet.slice_mut(s![n.., n..]).assign(&gg);
+
+        Self {
+            ns_plus,
+            ns_minus,
+            singlet,
+        }
+    }
+}
+
+/// Piecewise polynomial interpolation in `ln(x)`.
+struct Interpolation {
+    /// Nodes of the interpolation, the last one is always `x = 1`.
+    x: Vec<f64>,
+    /// Logarithms of `x`.
+    y: Vec<f64>,
+    /// Number of nodes that are not fixed to zero.
+    len: usize,
+    /// Gauss-Legendre points and weights on the interval `[0, 1]`.
+    gauss: Vec<(f64, f64)>,
+}
+
+impl Interpolation {
+    fn new(x: &[f64]) -> Self {
+        let mut nodes = x.to_vec();
+
+        if nodes.last().map_or(true, |&last| last < 1.0) {
+            nodes.push(1.0);
+        }
+
+        Self {
+            y: nodes.iter().map(|x| x.ln()).collect(),
+            x: nodes,
+            len: x.len(),
+            gauss: gauss_legendre(GAUSS_POINTS),
+        }
+    }
+
+    const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the indices of the nodes whose polynomials are non-zero between the nodes `k` and
+    /// `k + 1`.
+    fn window(&self, k: usize) -> std::ops::Range<usize> {
+        let nodes = self.x.len();
+        let start = k
+            .saturating_sub((DEGREE - 1) / 2)
+            .min(nodes.saturating_sub(DEGREE + 1));
+
+        start..(start + DEGREE + 1).min(nodes)
+    }
+
+    /// Evaluates the polynomial of node `j` in the interpolation window `window` at `y`.
+    fn polynomial(&self, window: &std::ops::Range<usize>, j: usize, y: f64) -> f64 {
+        window
+            .clone()
+            .filter(|&m| m != j)
+            .map(|m| (y - self.y[m]) / (self.y[j] - self.y[m]))
+            .product()
+    }
+
+    /// Computes the Mellin convolution of each splitting function in `kernels`, multiplied with
+    /// `z`, with the interpolation polynomials of `x f(x)`, evaluated at the nodes. The element
+    /// `(i, j)` of each matrix is the convolution with the polynomial of node `j` at the node `i`.
+    fn convolutions(&self, kernels: &[Kernel], nf: f64) -> Vec<Array2<f64>> {
+        let n = self.len;
+        let mut matrices = vec![Array2::zeros((n, n)); kernels.len()];
+
+        for i in 0..n {
+            let xi = self.x[i];
+
+            // the convolution vanishes at `x = 1`
+            if xi >= 1.0 {
+                continue;
+            }
+
+            for (matrix, kernel) in matrices.iter_mut().zip(kernels) {
+                matrix[[i, i]] += (kernel.plus)(nf).mul_add((-xi).ln_1p(), (kernel.delta)(nf));
+            }
+
+            for k in i..(self.x.len() - 1) {
+                let window = self.window(k);
+                let delta = self.y[k + 1] - self.y[k];
+
+                for &(t, weight) in &self.gauss {
+                    // in the first interval the integrand has integrable singularities at `z = 1`,
+                    // which are smoothed out by the substitution `t -> t^2`
+                    let (y, dy) = if k == i {
+                        (delta.mul_add(t * t, self.y[k]), 2.0 * delta * t * weight)
+                    } else {
+                        (delta.mul_add(t, self.y[k]), delta * weight)
+                    };
+                    let w = y.exp();
+                    let z = xi / w;
+                    let polynomials: Vec<_> = window
+                        .clone()
+                        .filter(|&j| j < n)
+                        .map(|j| (j, self.polynomial(&window, j, y)))
+                        .collect();
+
+                    for (matrix, kernel) in matrices.iter_mut().zip(kernels) {
+                        let plus = (kernel.plus)(nf);
+                        // the kernel for `x f(x)` is `z P(z)`, and `z / (1 - z)_+` is
+                        // `1 / (1 - z)_+ - 1`
+                        let regular = z.mul_add((kernel.regular)(z, nf), -plus);
+
+                        for &(j, polynomial) in &polynomials {
+                            matrix[[i, j]] += dy * polynomial * plus.mul_add(w / (w - xi), regular);
+                        }
+
+                        // subtraction term of the plus distribution
+                        matrix[[i, i]] -= dy * plus * xi / (w - xi);
+                    }
+                }
+            }
+        }
+
+        matrices
+    }
+}
+
+/// Returns the Gauss-Legendre points and weights for `n` points on the interval `[0, 1]`.
+fn gauss_legendre(n: usize) -> Vec<(f64, f64)> {
+    #[allow(clippy::cast_precision_loss)]
+    let nf64 = n as f64;
+
+    (0..n)
+        .map(|i| {
+            #[allow(clippy::cast_precision_loss)]
+            let mut x = (PI * (i as f64 + 0.75) / (nf64 + 0.5)).cos();
+            let mut derivative = 0.0;
+
+            for _ in 0..100 {
+                // evaluate the Legendre polynomial and its derivative with the recurrence relation
+                let (mut p0, mut p1) = (1.0, x);
+
+                for k in 2..=n {
+                    #[allow(clippy::cast_precision_loss)]
+                    let k = k as f64;
+                    let p2 = ((2.0 * k - 1.0) * x * p1 - (k - 1.0) * p0) / k;
+                    p0 = p1;
+                    p1 = p2;
+                }
+
+                derivative = nf64 * x.mul_add(p1, -p0) / x.mul_add(x, -1.0);
+                let dx = p1 / derivative;
+                x -= dx;
+
+                if dx.abs() < 1e-15 {
+                    break;
+                }
+            }
+
+            let weight = 2.0 / ((1.0 - x * x) * derivative * derivative);
+
+            (0.5 * (1.0 - x), 0.5 * weight)
+        })
+        .collect()
+}
+
+/// Splitting function `P(z) = R(z) + S / (1 - z)_+ + D delta(1 - z)`, where the regular part `R`,
+/// the coefficient `S` and the coefficient `D` may depend on the number of active flavours.
+#[derive(Clone, Copy)]
+struct Kernel {
+    regular: fn(f64, f64) -> f64,
+    plus: fn(f64) -> f64,
+    delta: fn(f64) -> f64,
+}
+
+/// Splitting functions of the non-singlet and singlet sectors.
+struct Kernels {
+    ns_plus: Kernel,
+    ns_minus: Kernel,
+    qq: Kernel,
+    qg: Kernel,
+    gq: Kernel,
+    gg: Kernel,
+}
+
+const fn zero(_: f64) -> f64 {
+    0.0
+}
+
+const LO_NS: Kernel = Kernel {
+    regular: |z, _| -CF * (1.0 + z),
+    plus: |_| 2.0 * CF,
+    delta: |_| 1.5 * CF,
+};
+
+const LO: Kernels = Kernels {
+    ns_plus: LO_NS,
+    ns_minus: LO_NS,
+    qq: LO_NS,
+    qg: Kernel {
+        regular: |z, nf| 2.0 * nf * TR * p_qg(z),
+        plus: zero,
+        delta: zero,
+    },
+    gq: Kernel {
+        regular: |z, _| CF * (1.0 + (1.0 - z) * (1.0 - z)) / z,
+        plus: zero,
+        delta: zero,
+    },
+    gg: Kernel {
+        regular: |z, _| 2.0 * CA * ((1.0 - z) / z + z * (1.0 - z) - 1.0),
+        plus: |_| 2.0 * CA,
+        delta: |nf| (11.0 * CA - 4.0 * nf * TR) / 6.0,
+    },
+};
+
+// the next-to-leading order splitting functions are taken from R. K. Ellis, W. J. Stirling and
+// B. R. Webber, "QCD and collider physics", in the normalization `(alphas / (2 pi))^2`
+
+const NLO: Kernels = Kernels {
+    ns_plus: Kernel {
+        regular: |z, nf| p1_qq_v(z, nf) + p1_qqbar_v(z),
+        plus: p1_ns_plus,
+        delta: p1_ns_delta,
+    },
+    ns_minus: Kernel {
+        regular: |z, nf| p1_qq_v(z, nf) - p1_qqbar_v(z),
+        plus: p1_ns_plus,
+        delta: p1_ns_delta,
+    },
+    qq: Kernel {
+        regular: |z, nf| p1_qq_v(z, nf) + p1_qqbar_v(z) + 2.0 * nf * p1_ps(z),
+        plus: p1_ns_plus,
+        delta: p1_ns_delta,
+    },
+    qg: Kernel {
+        regular: p1_qg,
+        plus: zero,
+        delta: zero,
+    },
+    gq: Kernel {
+        regular: p1_gq,
+        plus: zero,
+        delta: zero,
+    },
+    gg: Kernel {
+        regular: p1_gg,
+        plus: p1_gg_plus,
+        delta: p1_gg_delta,
+    },
+};
+
+/// Real part of the dilogarithm for arguments `y` between `-1` and `0`.
+fn li2_negative(y: f64) -> f64 {
+    // Landen's identity maps the argument to `u` between `0` and `1/2`
+    let u = y / (y - 1.0);
+    let mut sum = 0.0;
+    let mut power = 1.0;
+
+    for k in 1..=60 {
+        power *= u;
+        let k = f64::from(k);
+        sum += power / (k * k);
+    }
+
+    let log = (-y).ln_1p();
+
+    -sum - 0.5 * log * log
+}
+
+fn s2(z: f64) -> f64 {
+    let lz = z.ln();
+
+    -2.0 * li2_negative(-z) + 0.5 * lz * lz - 2.0 * lz * z.ln_1p() - PI * PI / 6.0
+}
+
+fn p_qq(z: f64) -> f64 {
+    2.0 / (1.0 - z) - 1.0 - z
+}
+
+fn p_qg(z: f64) -> f64 {
+    z * z + (1.0 - z) * (1.0 - z)
+}
+
+fn p_gq(z: f64) -> f64 {
+    (1.0 + (1.0 - z) * (1.0 - z)) / z
+}
+
+fn p_gg(z: f64) -> f64 {
+    1.0 / (1.0 - z) + 1.0 / z - 2.0 + z - z * z
+}
+
+fn p1_qq_v(z: f64, nf: f64) -> f64 {
+    let lz = z.ln();
+    let l1z = (-z).ln_1p();
+
+    CF * CF
+        * (-(2.0 * lz * l1z + 1.5 * lz) * p_qq(z)
+            - (1.5 + 3.5 * z) * lz
+            - 0.5 * (1.0 + z) * lz * lz
+            - 5.0 * (1.0 - z))
+        + CF * CA
+            * ((0.5 * lz * lz + 11.0 / 6.0 * lz) * p_qq(z)
+                - (67.0 / 18.0 - PI * PI / 6.0) * (1.0 + z)
+                + (1.0 + z) * lz
+                + 20.0 / 3.0 * (1.0 - z))
+        + CF * TR
+            * nf
+            * (-2.0 / 3.0 * lz * p_qq(z) + 10.0 / 9.0 * (1.0 + z) - 4.0 / 3.0 * (1.0 - z))
+}
+
+fn p1_qqbar_v(z: f64) -> f64 {
+    CF * (CF - 0.5 * CA) * (2.0 * p_qq(-z) * s2(z) + 2.0 * (1.0 + z) * z.ln() + 4.0 * (1.0 - z))
+}
+
+fn p1_ns_plus(nf: f64) -> f64 {
+    2.0 * (CF * CA * (67.0 / 18.0 - PI * PI / 6.0) - CF * TR * nf * 10.0 / 9.0)
+}
+
+fn p1_ns_delta(nf: f64) -> f64 {
+    CF * CF * (0.375 - PI * PI / 2.0 + 6.0 * ZETA3)
+        + CF * CA * (17.0 / 24.0 + 11.0 * PI * PI / 18.0 - 3.0 * ZETA3)
+        - CF * TR * nf * (1.0 / 6.0 + 2.0 * PI * PI / 9.0)
+}
+
+fn p1_ps(z: f64) -> f64 {
+    let lz = z.ln();
+
+    CF * TR
+        * (20.0 / (9.0 * z) - 2.0 + 6.0 * z - 56.0 / 9.0 * z * z
+            + (1.0 + 5.0 * z + 8.0 / 3.0 * z * z) * lz
+            - (1.0 + z) * lz * lz)
+}
+
+fn p1_qg(z: f64, nf: f64) -> f64 {
+    let lz = z.ln();
+    let l1z = (-z).ln_1p();
+    let lr = l1z - lz;
+
+    nf * (CF
+        * TR
+        * (4.0 - 9.0 * z - (1.0 - 4.0 * z) * lz - (1.0 - 2.0 * z) * lz * lz
+            + 4.0 * l1z
+            + (2.0 * lr * lr - 4.0 * lr - 2.0 * PI * PI / 3.0 + 10.0) * p_qg(z))
+        + CA * TR
+            * (182.0 / 9.0
+                + 14.0 / 9.0 * z
+                + 40.0 / (9.0 * z)
+                + (136.0 / 3.0 * z - 38.0 / 3.0) * lz
+                - 4.0 * l1z
+                - (2.0 + 8.0 * z) * lz * lz
+                + 2.0 * p_qg(-z) * s2(z)
+                + (-lz * lz + 44.0 / 3.0 * lz - 2.0 * l1z * l1z + 4.0 * l1z + PI * PI / 3.0
+                    - 218.0 / 9.0)
+                    * p_qg(z)))
+}
+
+fn p1_gq(z: f64, nf: f64) -> f64 {
+    let lz = z.ln();
+    let l1z = (-z).ln_1p();
+
+    CF * CF
+        * (-2.5 - 3.5 * z + (2.0 + 3.5 * z) * lz
+            - (1.0 - 0.5 * z) * lz * lz
+            - 2.0 * z * l1z
+            - (3.0 * l1z + l1z * l1z) * p_gq(z))
+        + CF * CA
+            * (28.0 / 9.0 + 65.0 / 18.0 * z + 44.0 / 9.0 * z * z
+                - (12.0 + 5.0 * z + 8.0 / 3.0 * z * z) * lz
+                + (4.0 + z) * lz * lz
+                + 2.0 * z * l1z
+                + s2(z) * p_gq(-z)
+                + (0.5 - 2.0 * lz * l1z + 0.5 * lz * lz + 11.0 / 3.0 * l1z + l1z * l1z
+                    - PI * PI / 6.0)
+                    * p_gq(z))
+        + CF * TR * nf * (-4.0 / 3.0 * z - (20.0 / 9.0 + 4.0 / 3.0 * l1z) * p_gq(z))
+}
+
+fn p1_gg(z: f64, nf: f64) -> f64 {
+    let lz = z.ln();
+    let l1z = (-z).ln_1p();
+    // `p_gg` without the term `1 / (1 - z)`, which is part of the plus distribution
+    let p_gg_regular = 1.0 / z - 2.0 + z - z * z;
+
+    CF * TR
+        * nf
+        * (-16.0 + 8.0 * z + 20.0 / 3.0 * z * z + 4.0 / (3.0 * z)
+            - (6.0 + 10.0 * z) * lz
+            - (2.0 + 2.0 * z) * lz * lz)
+        + CA * TR
+            * nf
+            * (2.0 - 2.0 * z + 26.0 / 9.0 * (z * z - 1.0 / z)
+                - 4.0 / 3.0 * (1.0 + z) * lz
+                - 20.0 / 9.0 * p_gg_regular)
+        + CA * CA
+            * (13.5 * (1.0 - z) + 67.0 / 9.0 * (z * z - 1.0 / z)
+                - (25.0 / 3.0 - 11.0 / 3.0 * z + 44.0 / 3.0 * z * z) * lz
+                + 4.0 * (1.0 + z) * lz * lz
+                + 2.0 * p_gg(-z) * s2(z)
+                + (lz * lz - 4.0 * lz * l1z) * p_gg(z)
+                + (67.0 / 9.0 - PI * PI / 3.0) * p_gg_regular)
+}
+
+fn p1_gg_plus(nf: f64) -> f64 {
+    CA * CA * (67.0 / 9.0 - PI * PI / 3.0) - CA * TR * nf * 20.0 / 9.0
+}
+
+fn p1_gg_delta(nf: f64) -> f64 {
+    CA * CA * (8.0 / 3.0 + 3.0 * ZETA3) - CF * TR * nf - 4.0 / 3.0 * CA * TR * nf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::assert_approx_eq;
+
+    const THRESHOLDS: [f64; 3] = [1.51 * 1.51, 4.92 * 4.92, 172.5 * 172.5];
+
+    /// Returns an `x` grid whose nodes are equally spaced in `ln(1/x) + 5 (1 - x)`.
+    fn x_grid(n: u32, x_min: f64) -> Vec<f64> {
+        let y = |x: f64| -x.ln() + 5.0 * (1.0 - x);
+        let y_max = y(x_min);
+
+        (0..n)
+            .map(|k| {
+                let target = y_max * (1.0 - f64::from(k) / f64::from(n));
+                let mut x: f64 = x_min;
+
+                for _ in 0..100 {
+                    x -= (y(x) - target) / (-1.0 / x - 5.0);
+                }
+
+                x
+            })
+            .collect()
+    }
+
+    fn info(x1: Vec<f64>, fac1: Vec<f64>) -> EvolveInfo {
+        EvolveInfo {
+            fac1,
+            pids1: PIDS.to_vec(),
+            x1,
+            ren1: vec![],
+        }
+    }
+
+    /// Strong coupling with one-loop running for five flavours.
+    fn alphas(q2: f64) -> f64 {
+        0.118 / (1.0 + 0.118 * 23.0 / (12.0 * PI) * (q2 / (91.1876 * 91.1876)).ln())
+    }
+
+    /// Toy parton densities `f(x)` at the starting scale.
+    fn pdf(pid: i32, x: f64) -> f64 {
+        let sea = 0.2 * x.powf(-1.1) * (1.0 - x).powi(7);
+
+        match pid {
+            21 => 1.7 * x.powf(-1.1) * (1.0 - x).powi(5),
+            2 => 5.1 * x.powf(-0.2) * (1.0 - x).powi(3) + sea,
+            1 => 3.1 * x.powf(-0.2) * (1.0 - x).powi(4) + sea,
+            -3..=3 => sea,
+            _ => 0.0,
+        }
+    }
+
+    /// Evolves [`pdf`] with `operator` and returns `f(x)` for each squared factorization scale,
+    /// flavour in [`PIDS`] and `x`.
+    fn evolve_pdf(info: &OperatorInfo, operator: &Array5<f64>) -> Vec<Vec<Vec<f64>>> {
+        (0..info.fac1.len())
+            .map(|fac1| {
+                PIDS.iter()
+                    .map(|pid1| {
+                        (0..info.x1.len())
+                            .map(|x1| {
+                                info.pids1
+                                    .iter()
+                                    .position(|pid| pid == pid1)
+                                    .map_or(0.0, |pid1| {
+                                        info.pids0
+                                            .iter()
+                                            .enumerate()
+                                            .flat_map(|(pid0_idx, &pid0)| {
+                                                info.x0.iter().enumerate().map(
+                                                    move |(x0_idx, &x0)| {
+                                                        operator[[fac1, pid1, x1, pid0_idx, x0_idx]]
+                                                            * pdf(pid0, x0)
+                                                    },
+                                                )
+                                            })
+                                            .sum()
+                                    })
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the weights of the values of `f(x)` at the nodes `x` for the Mellin moment `n`.
+    fn moment_weights(x: &[f64], n: i32) -> Vec<f64> {
+        let interpolation = Interpolation::new(x);
+
+        (0..x.len())
+            .map(|j| {
+                (0..(interpolation.x.len() - 1))
+                    .filter(|&k| interpolation.window(k).contains(&j))
+                    .map(|k| {
+                        let window = interpolation.window(k);
+                        let delta = interpolation.y[k + 1] - interpolation.y[k];
+
+                        interpolation
+                            .gauss
+                            .iter()
+                            .map(|&(t, weight)| {
+                                let y = delta.mul_add(t, interpolation.y[k]);
+                                // the interpolation is for `x f(x)`
+                                delta
+                                    * weight
+                                    * (f64::from(n - 1) * y).exp()
+                                    * interpolation.polynomial(&window, j, y)
+                            })
+                            .sum::<f64>()
+                    })
+                    .sum::<f64>()
+                    * x[j]
+            })
+            .collect()
+    }
+
+    fn moment(weights: &[f64], pdf: &[f64]) -> f64 {
+        weights.iter().zip(pdf).map(|(w, f)| w * f).sum()
+    }
+
+    #[test]
+    fn identity_at_starting_scale() {
+        let theory = Theory {
+            order: PerturbativeOrder::Nlo,
+            fac0: 10.0,
+            thresholds: THRESHOLDS,
+        };
+        let x = x_grid(20, 1e-4);
+        let (info, operator) =
+            evolution_operator(&info(x.clone(), vec![10.0]), &theory, 1.0, 1.0, &mut alphas);
+
+        assert_eq!(info.pids0, [-4, -3, -2, -1, 21, 1, 2, 3, 4]);
+        assert_eq!(info.pids1, PIDS);
+        assert_eq!(info.x0, x);
+        assert_eq!(info.x1, x);
+        assert_eq!(info.lumi_id_types, "pdg_mc_ids");
+
+        for ((_, pid1, x1, pid0, x0), &value) in operator.indexed_iter() {
+            let expected = if (info.pids1[pid1] == info.pids0[pid0]) && (x1 == x0) {
+                1.0
+            } else {
+                0.0
+            };
+
+            assert_approx_eq!(f64, value, expected, ulps = 4);
+        }
+    }
+
+    #[test]
+    fn sum_rules() {
+        // a very small `x_min` is needed, because the evolution moves momentum to smaller `x`
+        let x = x_grid(60, 1e-9);
+        let fac1 = vec![1000.0, 1.5, 10.0];
+        let number = moment_weights(&x, 1);
+        let momentum = moment_weights(&x, 2);
+
+        for order in [PerturbativeOrder::Lo, PerturbativeOrder::Nlo] {
+            let theory = Theory {
+                order,
+                fac0: 2.0,
+                thresholds: THRESHOLDS,
+            };
+            let (info, operator) = evolution_operator(
+                &info(x.clone(), fac1.clone()),
+                &theory,
+                1.0,
+                1.0,
+                &mut alphas,
+            );
+            let evolved = evolve_pdf(&info, &operator);
+            let starting: Vec<_> = x.iter().map(|&x| pdf(2, x) - pdf(-2, x)).collect();
+            let total_momentum: f64 = [-3, -2, -1, 21, 1, 2, 3]
+                .iter()
+                .map(|&pid| {
+                    let f: Vec<_> = x.iter().map(|&x| pdf(pid, x)).collect();
+                    moment(&momentum, &f)
+                })
+                .sum();
+
+            for pdfs in &evolved {
+                let flavour = |pid| &pdfs[pid_index(pid)];
+                let valence = |q: i32| -> Vec<_> {
+                    flavour(q)
+                        .iter()
+                        .zip(flavour(-q))
+                        .map(|(q, qbar)| q - qbar)
+                        .collect()
+                };
+
+                assert_approx_eq!(
+                    f64,
+                    moment(&number, &valence(2)),
+                    moment(&number, &starting),
+                    epsilon = 2e-4
+                );
+                assert_approx_eq!(f64, moment(&number, &valence(3)), 0.0, epsilon = 1e-12);
+
+                let evolved_momentum: f64 = PIDS
+                    .iter()
+                    .map(|&pid| moment(&momentum, flavour(pid)))
+                    .sum();
+
+                assert_approx_eq!(f64, evolved_momentum, total_momentum, epsilon = 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn splitting_kernel_moments() {
+        let x = x_grid(60, 1e-9);
+        // the kernels are evaluated on a descending grid, which they must handle
+        let reversed: Vec<_> = x.iter().rev().copied().collect();
+        let nf = 5;
+        let number = moment_weights(&x, 1);
+        let momentum = moment_weights(&x, 2);
+        let derivative = |kernel: &SplittingKernel, pid| -> Vec<f64> {
+            let mut result = vec![0.0; x.len()];
+
+            for (other, matrix) in kernel(pid, &reversed) {
+                let f: Vec<_> = reversed.iter().map(|&x| pdf(other, x)).collect();
+
+                for (value, row) in result.iter_mut().zip(matrix.rows()) {
+                    *value += row.iter().zip(&f).map(|(m, f)| m * f).sum::<f64>();
+                }
+            }
+
+            result.reverse();
+            result
+        };
+
+        for order in [PerturbativeOrder::Lo, PerturbativeOrder::Nlo] {
+            let kernel = splitting_kernel(order, nf);
+
+            // the gluon given as `0` is the same as `21`
+            assert_eq!(kernel(0, &x).len(), kernel(21, &x).len());
+            assert!(kernel(22, &x).is_empty());
+            assert_eq!(
+                kernel(21, &x),
+                kernel(21, &reversed)
+                    .into_iter()
+                    .map(|(pid, matrix)| (pid, matrix.slice(s![..;-1, ..;-1]).to_owned()))
+                    .collect::<Vec<_>>()
+            );
+
+            // the splitting functions conserve the momentum and the number of valence quarks
+            let momentum_derivative: f64 = PIDS
+                .iter()
+                .map(|&pid| moment(&momentum, &derivative(&*kernel, pid)))
+                .sum();
+            let valence_derivative: Vec<_> = derivative(&*kernel, 2)
+                .iter()
+                .zip(derivative(&*kernel, -2))
+                .map(|(q, qbar)| q - qbar)
+                .collect();
+
+            assert_approx_eq!(f64, momentum_derivative, 0.0, epsilon = 1e-4);
+            assert_approx_eq!(
+                f64,
+                moment(&number, &valence_derivative),
+                0.0,
+                epsilon = 1e-3
+            );
+        }
+
+        // at leading order the moments of non-singlet distributions change with the anomalous
+        // dimension
+        let kernel = splitting_kernel(PerturbativeOrder::Lo, nf);
+        let gamma = CF * (-0.5 + 1.0 / 6.0 - 1.0);
+        let non_singlet: Vec<_> = derivative(&*kernel, 2)
+            .iter()
+            .zip(derivative(&*kernel, 1))
+            .map(|(u, d)| u - d)
+            .collect();
+        let starting: Vec<_> = x.iter().map(|&x| pdf(2, x) - pdf(1, x)).collect();
+
+        assert_approx_eq!(
+            f64,
+            moment(&momentum, &non_singlet),
+            gamma / (2.0 * PI) * moment(&momentum, &starting),
+            epsilon = 1e-4
+        );
+    }
+
+    #[test]
+    fn lo_non_singlet_moments() {
+        // with a coupling that runs with the one-loop beta function for three flavours, the
+        // moments of the non-singlet distributions evolve with a power of the coupling ratio
+        let beta0 = 9.0 / (12.0 * PI);
+        let mut alphas = |q2: f64| 1.0 / (beta0 * (q2 / 0.1).ln());
+        let theory = Theory {
+            order: PerturbativeOrder::Lo,
+            fac0: 2.0,
+            thresholds: [f64::INFINITY; 3],
+        };
+        let x = x_grid(60, 1e-7);
+        let (info, operator) = evolution_operator(
+            &info(x.clone(), vec![100.0]),
+            &theory,
+            1.0,
+            1.0,
+            &mut alphas,
+        );
+        let evolved = evolve_pdf(&info, &operator);
+
+        let gamma = CF * (-0.5 + 1.0 / 6.0 - 1.0);
+        let ratio = (alphas(100.0) / alphas(2.0)).powf(-gamma / (2.0 * PI * beta0));
+        let weights = moment_weights(&x, 2);
+        let starting: Vec<_> = x.iter().map(|&x| pdf(2, x) - pdf(1, x)).collect();
+        let non_singlet: Vec<_> = evolved[0][pid_index(2)]
+            .iter()
+            .zip(&evolved[0][pid_index(1)])
+            .map(|(u, d)| u - d)
+            .collect();
+
+        assert_approx_eq!(
+            f64,
+            moment(&weights, &non_singlet),
+            ratio * moment(&weights, &starting),
+            epsilon = 1e-4
+        );
+    }
+}
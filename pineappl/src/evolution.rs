@@ -1,14 +1,16 @@
-//! Supporting classes and functions for [`Grid::evolve`].
+//! Supporting classes and functions for [`Grid::evolve`] and [`Grid::evolve_with_slice_iter`].
 
 use super::grid::{Grid, GridError, Order};
 use super::import_only_subgrid::ImportOnlySubgridV2;
 use super::lumi::LumiEntry;
 use super::lumi_entry;
+use super::pids;
 use super::sparse_array3::SparseArray3;
 use super::subgrid::{Mu2, Subgrid, SubgridEnum};
 use float_cmp::approx_eq;
 use itertools::Itertools;
-use ndarray::{s, Array1, Array2, Array3, ArrayView1, ArrayView5, Axis};
+use ndarray::{s, Array1, Array2, Array3, Array4, ArrayView1, ArrayView4, Axis};
+use std::collections::HashMap;
 use std::iter;
 
 /// This structure captures the information needed to create an evolution kernel operator (EKO) for
@@ -93,20 +95,547 @@ fn gluon_has_pid_zero(grid: &Grid) -> bool {
         .unwrap_or(false)
 }
 
-pub(crate) fn pids(
-    operator: &ArrayView5<f64>,
-    info: &OperatorInfo,
+/// Information about a single slice of an evolution kernel operator (EKO), which is the operator
+/// for a single squared factorization scale [`fac1`], passed to [`Grid::evolve_with_slice_iter`].
+///
+/// The dimensions of the operator slice must correspond to the values given in [`pids1`], [`x1`],
+/// [`pids0`] and [`x0`], exactly in this order. The remaining members have the same meaning as in
+/// [`OperatorInfo`].
+///
+/// [`fac1`]: Self::fac1
+/// [`pids0`]: Self::pids0
+/// [`pids1`]: Self::pids1
+/// [`x0`]: Self::x0
+/// [`x1`]: Self::x1
+#[derive(Clone)]
+pub struct OperatorSliceInfo {
+    /// Squared factorization scale of the `FkTable`.
+    pub fac0: f64,
+    /// Particle identifiers of the `FkTable`.
+    pub pids0: Vec<i32>,
+    /// `x`-grid coordinates of the `FkTable`
+    pub x0: Vec<f64>,
+    /// Squared factorization scale of this slice of the operator.
+    pub fac1: f64,
+    /// Particle identifiers of the `Grid`. If the `Grid` contains more particle identifiers than
+    /// given here, the contributions of them are silently ignored.
+    pub pids1: Vec<i32>,
+    /// `x`-grid coordinates of the `Grid`.
+    pub x1: Vec<f64>,
+    /// Identifier of the particle basis for the `FkTable`.
+    pub lumi_id_types: String,
+}
+
+/// Strong couplings [`alphas`](Self::alphas) for the squared renormalization scales given in
+/// [`ren1`](Self::ren1).
+pub struct AlphasTable {
+    /// Squared renormalization scales of the `Grid`.
+    pub ren1: Vec<f64>,
+    /// Strong couplings corresponding to the order given in [`ren1`](Self::ren1).
+    pub alphas: Vec<f64>,
+}
+
+impl AlphasTable {
+    /// Creates a table of the strong couplings calculated with `alphas` for each squared
+    /// renormalization scale of `grid`, multiplied with the square of `xir`.
+    pub fn from_grid(grid: &Grid, xir: f64, alphas: &mut dyn FnMut(f64) -> f64) -> Self {
+        let ren1: Vec<_> = grid
+            .evolve_info(&[])
+            .ren1
+            .into_iter()
+            .map(|ren| xir * xir * ren)
+            .collect();
+        let alphas = ren1.iter().map(|&mur2| alphas(mur2)).collect();
+
+        Self { ren1, alphas }
+    }
+}
+
+/// Rotation of the particle basis of an [`FkTable`] into the basis given by [`pids`].
+///
+/// The rotation is applied to the operator slices passed to [`Grid::evolve_with_slice_iter`] and
+/// is defined by a matrix, whose rows express each particle of the new basis as a linear
+/// combination of PDG Monte Carlo IDs.
+///
+/// [`FkTable`]: super::fk_table::FkTable
+/// [`pids`]: Self::pids
+pub struct BasisRotation {
+    lumi_id_types: String,
+    pids: Vec<i32>,
+    pdg_mc_ids: Vec<i32>,
+    /// Expresses each of the `pids` as linear combinations of `pdg_mc_ids`.
+    rotation: Array2<f64>,
+    /// Expresses each of the `pdg_mc_ids` as linear combinations of `pids`.
+    inverse: Array2<f64>,
+}
+
+impl BasisRotation {
+    /// Constructor. Row `i` of `rotation` expresses the particle `pids[i]` of the new basis, which
+    /// is identified by `lumi_id_types`, as a linear combination of the PDG Monte Carlo IDs given
+    /// in `pdg_mc_ids`.
+    ///
+    /// # Errors
+    ///
+    /// If `rotation` isn't a square matrix matching the lengths of `pids` and `pdg_mc_ids` or if
+    /// it isn't invertible, an error is returned.
+    pub fn new(
+        lumi_id_types: &str,
+        pids: Vec<i32>,
+        pdg_mc_ids: Vec<i32>,
+        rotation: Array2<f64>,
+    ) -> Result<Self, GridError> {
+        if rotation.dim() != (pids.len(), pdg_mc_ids.len()) || pids.len() != pdg_mc_ids.len() {
+            return Err(GridError::EvolutionFailure(format!(
+                "the basis rotation must be a {0}x{0} matrix, but it has the shape {1}x{2}",
+                pids.len(),
+                rotation.nrows(),
+                rotation.ncols()
+            )));
+        }
+
+        let inverse = invert(rotation.clone()).ok_or_else(|| {
+            GridError::EvolutionFailure("the basis rotation is not invertible".to_string())
+        })?;
+
+        Ok(Self {
+            lumi_id_types: lumi_id_types.to_string(),
+            pids,
+            pdg_mc_ids,
+            rotation,
+            inverse,
+        })
+    }
+
+    /// Rotation into the basis of PDG Monte Carlo IDs, identified by `pdg_mc_ids`.
+    #[must_use]
+    pub fn pdg_mc_ids() -> Self {
+        let pids = PDG_MC_IDS.to_vec();
+
+        Self {
+            lumi_id_types: "pdg_mc_ids".to_string(),
+            rotation: Array2::eye(pids.len()),
+            inverse: Array2::eye(pids.len()),
+            pdg_mc_ids: pids.clone(),
+            pids,
+        }
+    }
+
+    /// Rotation into the evolution basis, identified by `evol`; see [`pids::evol_to_pdg_mc_ids`].
+    #[must_use]
+    pub fn evol() -> Self {
+        let pids = vec![
+            22, 100, 103, 108, 115, 124, 135, 21, 200, 203, 208, 215, 224, 235,
+        ];
+        let rotation = Array2::from_shape_fn((pids.len(), PDG_MC_IDS.len()), |(i, j)| {
+            pids::evol_to_pdg_mc_ids(pids[i])
+                .into_iter()
+                .find_map(|(pid, factor)| (pid == PDG_MC_IDS[j]).then_some(factor))
+                .unwrap_or(0.0)
+        });
+        let inverse = Array2::from_shape_fn((PDG_MC_IDS.len(), pids.len()), |(i, j)| {
+            pids::pdg_mc_ids_to_evol(PDG_MC_IDS[i])
+                .into_iter()
+                .find_map(|(pid, factor)| (pid == pids[j]).then_some(factor))
+                .unwrap_or(0.0)
+        });
+
+        Self {
+            lumi_id_types: "evol".to_string(),
+            pids,
+            pdg_mc_ids: PDG_MC_IDS.to_vec(),
+            rotation,
+            inverse,
+        }
+    }
+
+    /// Returns the identifier of the particle basis after the rotation.
+    #[must_use]
+    pub fn lumi_id_types(&self) -> &str {
+        &self.lumi_id_types
+    }
+
+    /// Returns the particle identifiers of the basis after the rotation.
+    #[must_use]
+    pub fn pids(&self) -> &[i32] {
+        &self.pids
+    }
+
+    /// Expresses the particle `pid` of the basis after the rotation as a linear combination of
+    /// PDG Monte Carlo IDs, which is the format expected by [`LumiEntry::translate`]. Particles
+    /// that are not part of the basis are returned unchanged.
+    #[must_use]
+    pub fn to_pdg_mc_ids(&self, pid: i32) -> Vec<(i32, f64)> {
+        self.pids.iter().position(|&p| p == pid).map_or_else(
+            || vec![(pid, 1.0)],
+            |index| {
+                self.pdg_mc_ids
+                    .iter()
+                    .zip(self.rotation.row(index))
+                    .filter(|(_, &factor)| factor != 0.0)
+                    .map(|(&pdg_mc_id, &factor)| (pdg_mc_id, factor))
+                    .collect()
+            },
+        )
+    }
+
+    /// Expresses the particle `pid` of the basis `lumi_id_types` as a linear combination of the
+    /// particles of the basis after the rotation. PDG Monte Carlo IDs that are not part of the
+    /// rotation, for instance those of leptons, are left unchanged.
+    pub(crate) fn translate(
+        &self,
+        lumi_id_types: &str,
+        pid: i32,
+    ) -> Result<Vec<(i32, f64)>, GridError> {
+        let pdg_mc_ids = match lumi_id_types {
+            _ if lumi_id_types == self.lumi_id_types => return Ok(vec![(pid, 1.0)]),
+            // some grids use `0` to denote the gluon
+            "pdg_mc_ids" => vec![(if pid == 0 { 21 } else { pid }, 1.0)],
+            "evol" => pids::evol_to_pdg_mc_ids(pid),
+            _ => return Err(GridError::UnknownLumiIdTypes(lumi_id_types.to_string())),
+        };
+
+        let mut result: Vec<(i32, f64)> = Vec::new();
+
+        for (pdg_mc_id, factor) in pdg_mc_ids {
+            let terms: Vec<_> = self
+                .pdg_mc_ids
+                .iter()
+                .position(|&id| id == pdg_mc_id)
+                .map_or_else(
+                    || vec![(pdg_mc_id, factor)],
+                    |index| {
+                        self.pids
+                            .iter()
+                            .zip(self.inverse.row(index))
+                            .filter(|(_, &value)| value != 0.0)
+                            .map(|(&pid, &value)| (pid, factor * value))
+                            .collect()
+                    },
+                );
+
+            for (pid, factor) in terms {
+                if let Some(term) = result.iter_mut().find(|(id, _)| *id == pid) {
+                    term.1 += factor;
+                } else {
+                    result.push((pid, factor));
+                }
+            }
+        }
+
+        // remove coefficients that vanish up to rounding errors
+        let max = result
+            .iter()
+            .map(|(_, factor)| factor.abs())
+            .fold(0.0, f64::max);
+        result.retain(|(_, factor)| factor.abs() > 1e-14 * max);
+
+        Ok(result)
+    }
+
+    /// Rotates the particle basis of the `FkTable` of the operator slice given by `info` and
+    /// `operator`, i.e. the particle identifiers [`OperatorSliceInfo::pids0`], into the basis of
+    /// this rotation. The particle basis of the slice must either be `pdg_mc_ids`, `evol` or the
+    /// basis of this rotation. Particles that are not part of the rotation are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the particle basis of the slice isn't supported, an error is returned.
+    pub fn rotate(
+        &self,
+        info: &OperatorSliceInfo,
+        operator: ArrayView4<f64>,
+    ) -> Result<(OperatorSliceInfo, Array4<f64>), GridError> {
+        let translations = info
+            .pids0
+            .iter()
+            .map(|&pid0| self.translate(&info.lumi_id_types, pid0))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut pids0 = self.pids.clone();
+
+        // particles that are not part of the rotation are appended
+        for &(pid, _) in translations.iter().flatten() {
+            if !pids0.contains(&pid) {
+                pids0.push(pid);
+            }
+        }
+
+        let indices: HashMap<_, _> = pids0.iter().enumerate().map(|(i, &pid)| (pid, i)).collect();
+        let (pids1, x1, _, x0) = operator.dim();
+        let mut result = Array4::zeros((pids1, x1, pids0.len(), x0));
+
+        for (index0, translation) in translations.iter().enumerate() {
+            for &(pid, factor) in translation {
+                result
+                    .slice_mut(s![.., .., indices[&pid], ..])
+                    .scaled_add(factor, &operator.slice(s![.., .., index0, ..]));
+            }
+        }
+
+        Ok((
+            OperatorSliceInfo {
+                fac0: info.fac0,
+                pids0,
+                x0: info.x0.clone(),
+                fac1: info.fac1,
+                pids1: info.pids1.clone(),
+                x1: info.x1.clone(),
+                lumi_id_types: self.lumi_id_types.clone(),
+            },
+            result,
+        ))
+    }
+}
+
+const PDG_MC_IDS: [i32; 14] = [22, -6, -5, -4, -3, -2, -1, 21, 1, 2, 3, 4, 5, 6];
+
+/// Inverts the square matrix `matrix` using Gauss-Jordan elimination with partial pivoting.
+/// Returns `None` if the matrix is singular.
+fn invert(mut matrix: Array2<f64>) -> Option<Array2<f64>> {
+    let n = matrix.nrows();
+    let mut inverse = Array2::eye(n);
+
+    for column in 0..n {
+        let pivot = (column..n).max_by(|&a, &b| {
+            matrix[[a, column]]
+                .abs()
+                .total_cmp(&matrix[[b, column]].abs())
+        })?;
+
+        if matrix[[pivot, column]].abs() < 1e-12 {
+            return None;
+        }
+
+        for array in [&mut matrix, &mut inverse] {
+            for index in 0..n {
+                array.swap([column, index], [pivot, index]);
+            }
+        }
+
+        let factor = matrix[[column, column]].recip();
+        matrix.row_mut(column).mapv_inplace(|value| value * factor);
+        inverse.row_mut(column).mapv_inplace(|value| value * factor);
+
+        for row in (0..n).filter(|&row| row != column) {
+            let factor = matrix[[row, column]];
+
+            if factor != 0.0 {
+                let pivot_row = matrix.row(column).to_owned();
+                matrix.row_mut(row).scaled_add(-factor, &pivot_row);
+                let pivot_row = inverse.row(column).to_owned();
+                inverse.row_mut(row).scaled_add(-factor, &pivot_row);
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+/// The subgrids, luminosity function, particle basis and the squared factorization scales of both
+/// initial states of an FK table.
+type FkTableParts = (Array3<SubgridEnum>, Vec<LumiEntry>, String, [f64; 2]);
+
+/// Accumulates the contributions of the operator slices to the FK table.
+pub(crate) struct FkTableAccumulator {
+    pids0_a: Vec<i32>,
+    pids0_b: Vec<i32>,
+    tables: HashMap<(i32, i32), Vec<Array2<f64>>>,
+    fac1: Vec<f64>,
+    /// Squared factorization scales and `x`-grid coordinates of the `FkTable` for both initial
+    /// states.
+    x0: Option<[(f64, Vec<f64>); 2]>,
+    /// Particle basis of the `FkTable`.
+    lumi_id_types: String,
+}
+
+impl FkTableAccumulator {
+    pub(crate) fn new() -> Self {
+        Self {
+            pids0_a: Vec::new(),
+            pids0_b: Vec::new(),
+            tables: HashMap::new(),
+            fac1: Vec::new(),
+            x0: None,
+            lumi_id_types: String::new(),
+        }
+    }
+
+    fn table(&mut self, grid: &Grid, pid0_a: i32, pid0_b: i32) -> &mut Vec<Array2<f64>> {
+        for (pids0, pid0) in [(&mut self.pids0_a, pid0_a), (&mut self.pids0_b, pid0_b)] {
+            if let Err(index) = pids0.binary_search(&pid0) {
+                pids0.insert(index, pid0);
+            }
+        }
+
+        // `add_slice` must have been called before
+        let [(_, x0_a), (_, x0_b)] = self.x0.as_ref().unwrap();
+        let shape = (
+            if grid.has_pdf1() { x0_a.len() } else { 1 },
+            if grid.has_pdf2() { x0_b.len() } else { 1 },
+        );
+
+        self.tables
+            .entry((pid0_a, pid0_b))
+            .or_insert_with(|| vec![Array2::zeros(shape); grid.bin_info().bins()])
+    }
+
+    /// Checks that the slices described by `infos`, one for each initial state, are compatible
+    /// with each other and with the ones added before.
+    fn add_slice(&mut self, infos: [&OperatorSliceInfo; 2]) -> Result<(), GridError> {
+        let [info, info_b] = infos;
+
+        if !approx_eq!(f64, info.fac1, info_b.fac1, ulps = 64) {
+            return Err(GridError::EvolutionFailure(format!(
+                "the operators for both initial states must be given for the same muf2, but they are given for muf2 = {} and muf2 = {}",
+                info.fac1, info_b.fac1
+            )));
+        }
+
+        if info.lumi_id_types != info_b.lumi_id_types {
+            return Err(GridError::EvolutionFailure(format!(
+                "the operators for both initial states must use the same particle basis, but they use `{}` and `{}`",
+                info.lumi_id_types, info_b.lumi_id_types
+            )));
+        }
+
+        if let Some(x0) = &self.x0 {
+            if x0.iter().zip(infos).any(|((fac0, x0), info)| {
+                !approx_eq!(f64, *fac0, info.fac0, ulps = 64) || !same_grid(x0, &info.x0)
+            }) || (self.lumi_id_types != info.lumi_id_types)
+            {
+                return Err(GridError::EvolutionFailure(format!(
+                    "the operator for muf2 = {} is defined for a different FK table than the previous ones",
+                    info.fac1
+                )));
+            }
+        }
+
+        if self
+            .fac1
+            .iter()
+            .any(|&fac1| approx_eq!(f64, fac1, info.fac1, ulps = 64))
+        {
+            return Err(GridError::EvolutionFailure(format!(
+                "the operator for muf2 = {} was given more than once",
+                info.fac1
+            )));
+        }
+
+        self.fac1.push(info.fac1);
+
+        if self.x0.is_none() {
+            self.x0 = Some(infos.map(|info| (info.fac0, info.x0.clone())));
+            self.lumi_id_types.clone_from(&info.lumi_id_types);
+        }
+
+        Ok(())
+    }
+
+    /// Converts the accumulated tables into the subgrids and luminosity function of an FK table,
+    /// which are returned together with its particle basis and the squared factorization scales
+    /// of both initial states. Returns an error if `fac1`, the squared factorization scales needed by `grid`, are not all
+    /// covered by the slices that were added.
+    pub(crate) fn into_subgrids(
+        self,
+        grid: &Grid,
+        fac1: &[f64],
+    ) -> Result<FkTableParts, GridError> {
+        if let Some(fac) = fac1.iter().find(|&&fac| {
+            !self
+                .fac1
+                .iter()
+                .any(|&fac1| approx_eq!(f64, fac1, fac, ulps = 64))
+        }) {
+            return Err(GridError::EvolutionFailure(format!(
+                "no operator for muf2 = {fac} found"
+            )));
+        }
+
+        let [(fac0_a, x0_a), (fac0_b, x0_b)] = self.x0.ok_or_else(|| {
+            GridError::EvolutionFailure("no operator slices were given".to_string())
+        })?;
+
+        if self.tables.is_empty() {
+            return Err(GridError::EvolutionFailure(
+                "no non-zero operator found; result would be an empty FkTable".to_string(),
+            ));
+        }
+
+        let has_pdf1 = grid.has_pdf1();
+        let has_pdf2 = grid.has_pdf2();
+        // with different fitting scales the one of the first convolved initial state is stored
+        let fac0 = if has_pdf1 { fac0_a } else { fac0_b };
+        let lumi0: Vec<_> = self
+            .pids0_a
+            .iter()
+            .copied()
+            .cartesian_product(self.pids0_b.iter().copied())
+            .collect();
+        let bins = grid.bin_info().bins();
+        let mut tables = self.tables;
+        let mut sub_fk_tables = Vec::with_capacity(bins * lumi0.len());
+        let mut lumi_tables: Vec<_> = lumi0
+            .iter()
+            .map(|pids| tables.remove(pids).map(Vec::into_iter))
+            .collect();
+
+        for _ in 0..bins {
+            for lumi_table in &mut lumi_tables {
+                let table = lumi_table
+                    .as_mut()
+                    .and_then(Iterator::next)
+                    .unwrap_or_else(|| {
+                        Array2::zeros((
+                            if has_pdf1 { x0_a.len() } else { 1 },
+                            if has_pdf2 { x0_b.len() } else { 1 },
+                        ))
+                    });
+
+                sub_fk_tables.push(
+                    ImportOnlySubgridV2::new(
+                        SparseArray3::from_ndarray(table.insert_axis(Axis(0)).view(), 0, 1),
+                        vec![Mu2 {
+                            // TODO: FK tables don't depend on the renormalization scale
+                            //ren: -1.0,
+                            ren: fac0,
+                            fac: fac0,
+                        }],
+                        if has_pdf1 { x0_a.clone() } else { vec![1.0] },
+                        if has_pdf2 { x0_b.clone() } else { vec![1.0] },
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        Ok((
+            Array1::from_iter(sub_fk_tables)
+                .into_shape((1, bins, lumi0.len()))
+                .unwrap(),
+            lumi0.iter().map(|&(a, b)| lumi_entry![a, b, 1.0]).collect(),
+            self.lumi_id_types,
+            [fac0_a, fac0_b],
+        ))
+    }
+}
+
+/// The index pairs of the non-zero `(pid0, pid1)` combinations of an operator and the
+/// combinations themselves.
+type PidIndices = (Vec<(usize, usize)>, Vec<(i32, i32)>);
+
+fn pids(
+    operator: &ArrayView4<f64>,
+    info: &OperatorSliceInfo,
     gluon_has_pid_zero: bool,
     pid1_nonzero: &dyn Fn(i32) -> bool,
-) -> Result<(Vec<(usize, usize)>, Vec<(i32, i32)>), GridError> {
+) -> PidIndices {
     // list of all non-zero PID indices
-    let pid_indices: Vec<_> = (0..operator.dim().3)
-        .cartesian_product(0..operator.dim().1)
+    let pid_indices: Vec<_> = (0..operator.dim().2)
+        .cartesian_product(0..operator.dim().0)
         .filter(|&(pid0_idx, pid1_idx)| {
             // 1) at least one element of the operator must be non-zero, and 2) the pid must be
             // contained in the lumi somewhere
             operator
-                .slice(s![.., pid1_idx, .., pid0_idx, ..])
+                .slice(s![pid1_idx, .., pid0_idx, ..])
                 .iter()
                 .any(|&value| value != 0.0)
                 && pid1_nonzero(if gluon_has_pid_zero && info.pids1[pid1_idx] == 21 {
@@ -117,12 +646,6 @@ pub(crate) fn pids(
         })
         .collect();
 
-    if pid_indices.is_empty() {
-        return Err(GridError::EvolutionFailure(
-            "no non-zero operator found; result would be an empty FkTable".to_string(),
-        ));
-    }
-
     // list of all non-zero (pid0, pid1) combinations
     let pids = pid_indices
         .iter()
@@ -138,56 +661,15 @@ pub(crate) fn pids(
         })
         .collect();
 
-    Ok((pid_indices, pids))
-}
-
-pub(crate) fn lumi0_with_one(pids: &[(i32, i32)]) -> Vec<i32> {
-    let mut pids0: Vec<_> = pids.iter().map(|&(pid0, _)| pid0).collect();
-    pids0.sort_unstable();
-    pids0.dedup();
-
-    pids0
+    (pid_indices, pids)
 }
 
-pub(crate) fn lumi0_with_two(pids_a: &[(i32, i32)], pids_b: &[(i32, i32)]) -> Vec<(i32, i32)> {
-    let mut pids0_a: Vec<_> = pids_a.iter().map(|&(pid0, _)| pid0).collect();
-    pids0_a.sort_unstable();
-    pids0_a.dedup();
-    let mut pids0_b: Vec<_> = pids_b.iter().map(|&(pid0, _)| pid0).collect();
-    pids0_b.sort_unstable();
-    pids0_b.dedup();
-
-    pids0_a
-        .iter()
-        .copied()
-        .cartesian_product(pids0_b.iter().copied())
-        .collect()
-}
-
-pub(crate) fn operators(
-    operator: &ArrayView5<f64>,
-    info: &OperatorInfo,
-    fac1: &[f64],
+fn operators(
+    operator: &ArrayView4<f64>,
+    info: &OperatorSliceInfo,
     pid_indices: &[(usize, usize)],
     x1: &[f64],
-) -> Result<Vec<Array3<f64>>, GridError> {
-    // permutation between the grid fac1 values and the operator fac1 values
-    let fac1_indices: Vec<_> = if let Some(fac1_indices) = fac1
-        .iter()
-        .map(|&fac1p| {
-            info.fac1
-                .iter()
-                .position(|&fac1| approx_eq!(f64, fac1p, fac1, ulps = 64))
-        })
-        .collect()
-    {
-        fac1_indices
-    } else {
-        return Err(GridError::EvolutionFailure(
-            "operator information does not match grid's factorization scale values".to_string(),
-        ));
-    };
-
+) -> Result<Vec<Array2<f64>>, GridError> {
     // permutation between the grid x values and the operator x1 values
     let x1_indices: Vec<_> = if let Some(x1_indices) = x1
         .iter()
@@ -205,15 +687,14 @@ pub(crate) fn operators(
         ));
     };
 
-    // create the corresponding operators accessible in the form [muf2, x0, x1]
+    // create the corresponding operators accessible in the form [x0, x1]
     let operators: Vec<_> = pid_indices
         .iter()
         .map(|&(pid0_idx, pid1_idx)| {
             operator
-                .slice(s![.., pid1_idx, .., pid0_idx, ..])
-                .select(Axis(0), &fac1_indices)
-                .select(Axis(1), &x1_indices)
-                .permuted_axes([0, 2, 1])
+                .slice(s![pid1_idx, .., pid0_idx, ..])
+                .select(Axis(0), &x1_indices)
+                .reversed_axes()
                 .as_standard_layout()
                 .into_owned()
         })
@@ -222,25 +703,89 @@ pub(crate) fn operators(
     Ok(operators)
 }
 
-pub(crate) fn ndarray_from_subgrid_orders(
-    info: &OperatorInfo,
-    subgrids: &ArrayView1<SubgridEnum>,
-    orders: &[Order],
-    order_mask: &[bool],
-) -> Result<(Vec<f64>, Vec<f64>, Vec<f64>, Array3<f64>), GridError> {
-    // TODO: skip empty subgrids
-
-    let mut fac1: Vec<_> = subgrids
+/// Returns the subgrids in `subgrids` that are enabled by `order_mask`, together with their
+/// perturbative order and the factor coming from the scale logarithms.
+fn subgrid_orders<'a>(
+    subgrids: &'a ArrayView1<SubgridEnum>,
+    orders: &'a [Order],
+    order_mask: &'a [bool],
+    (xir, xif): (f64, f64),
+) -> impl Iterator<Item = (&'a SubgridEnum, &'a Order, f64)> + 'a {
+    subgrids
         .iter()
-        .flat_map(|subgrid| {
-            subgrid
-                .mu2_grid()
-                .iter()
-                .cloned()
-                .map(|mu2| info.xif * info.xif * mu2.fac)
+        .zip(orders.iter())
+        .zip(order_mask.iter().chain(iter::repeat(&true)))
+        .filter_map(move |((subgrid, order), &enabled)| {
+            if !enabled || subgrid.is_empty() {
+                return None;
+            }
+
+            let mut logs = 1.0;
+
+            // the scale belonging to `logxia` isn't varied, so these logarithms vanish
+            if order.logxia > 0 {
+                return None;
+            }
+
+            if order.logxir > 0 {
+                if approx_eq!(f64, xir, 1.0, ulps = 4) {
+                    return None;
+                }
+
+                logs *= (xir * xir).ln();
+            }
+
+            if order.logxif > 0 {
+                if approx_eq!(f64, xif, 1.0, ulps = 4) {
+                    return None;
+                }
+
+                logs *= (xif * xif).ln();
+            }
+
+            Some((subgrid, order, logs))
+        })
+}
+
+/// Returns the squared factorization scales, multiplied with the square of `xif`, for which
+/// [`Grid::evolve_with_slice_iter`] needs an operator.
+pub(crate) fn fac1(grid: &Grid, order_mask: &[bool], xi: (f64, f64)) -> Vec<f64> {
+    let mut fac1: Vec<_> = grid
+        .subgrids()
+        .lanes(Axis(0))
+        .into_iter()
+        .flat_map(|subgrids| {
+            subgrid_orders(&subgrids, grid.orders(), order_mask, xi)
+                .flat_map(|(subgrid, _, _)| {
+                    subgrid
+                        .mu2_grid()
+                        .iter()
+                        .map(|mu2| xi.1 * xi.1 * mu2.fac)
+                        .collect::<Vec<_>>()
+                })
                 .collect::<Vec<_>>()
         })
         .collect();
+
+    fac1.sort_by(f64::total_cmp);
+    fac1.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = 64));
+
+    fac1
+}
+
+/// The `x` grids of both initial states and the subgrid values tabulated on them.
+type SubgridArray = (Vec<f64>, Vec<f64>, Array2<f64>);
+
+/// Sums the subgrids for all orders, but the same bin and lumi, using the right couplings for the
+/// squared factorization scale `fac1`. Returns `None` if none of the subgrids has this scale.
+fn ndarray_from_subgrid_orders(
+    fac1: f64,
+    subgrids: &ArrayView1<SubgridEnum>,
+    orders: &[Order],
+    order_mask: &[bool],
+    (xir, xif): (f64, f64),
+    alphas_table: &AlphasTable,
+) -> Result<Option<SubgridArray>, GridError> {
     let mut x1_a: Vec<_> = subgrids
         .iter()
         .flat_map(|subgrid| subgrid.x1_grid().into_owned())
@@ -250,55 +795,27 @@ pub(crate) fn ndarray_from_subgrid_orders(
         .flat_map(|subgrid| subgrid.x2_grid().into_owned())
         .collect();
 
-    fac1.sort_by(f64::total_cmp);
-    fac1.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = 64));
     x1_a.sort_by(f64::total_cmp);
     x1_a.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = 64));
     x1_b.sort_by(f64::total_cmp);
     x1_b.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = 64));
 
-    let mut array = Array3::<f64>::zeros((fac1.len(), x1_a.len(), x1_b.len()));
-
-    // add subgrids for different orders, but the same bin and lumi, using the right
-    // couplings
-    for (subgrid, order) in subgrids
-        .iter()
-        .zip(orders.iter())
-        .zip(order_mask.iter().chain(iter::repeat(&true)))
-        .filter_map(|((subgrid, order), &enabled)| {
-            (enabled && !subgrid.is_empty()).then_some((subgrid, order))
-        })
-    {
-        let mut logs = 1.0;
+    let mut array = Array2::<f64>::zeros((x1_a.len(), x1_b.len()));
+    let mut found = false;
 
-        if order.logxir > 0 {
-            if approx_eq!(f64, info.xir, 1.0, ulps = 4) {
-                continue;
-            }
+    for (subgrid, order, logs) in subgrid_orders(subgrids, orders, order_mask, (xir, xif)) {
+        let fac1_indices: Vec<_> = subgrid
+            .mu2_grid()
+            .iter()
+            .map(|&Mu2 { fac, .. }| approx_eq!(f64, xif * xif * fac, fac1, ulps = 64))
+            .collect();
 
-            logs *= (info.xir * info.xir).ln();
+        if !fac1_indices.contains(&true) {
+            continue;
         }
 
-        if order.logxif > 0 {
-            if approx_eq!(f64, info.xif, 1.0, ulps = 4) {
-                continue;
-            }
-
-            logs *= (info.xif * info.xif).ln();
-        }
+        found = true;
 
-        // TODO: use `try_collect` once stabilized
-        let fac1_indices: Vec<_> = subgrid
-            .mu2_grid()
-            .iter()
-            .map(|&Mu2 { fac, .. }| {
-                fac1.iter()
-                    .position(|&scale| approx_eq!(f64, info.xif * info.xif * fac, scale, ulps = 64))
-                    .ok_or_else(|| {
-                        GridError::EvolutionFailure(format!("no operator for muf2 = {fac} found"))
-                    })
-            })
-            .collect::<Result<_, _>>()?;
         let xa_indices: Vec<_> = subgrid
             .x1_grid()
             .iter()
@@ -323,14 +840,18 @@ pub(crate) fn ndarray_from_subgrid_orders(
             .collect::<Result<_, _>>()?;
 
         for ((ifac1, ix1, ix2), value) in subgrid.indexed_iter() {
-            let mur2 = info.xir * info.xir * subgrid.mu2_grid()[ifac1].ren;
+            if !fac1_indices[ifac1] {
+                continue;
+            }
+
+            let mur2 = xir * xir * subgrid.mu2_grid()[ifac1].ren;
 
             let als = if order.alphas == 0 {
                 1.0
-            } else if let Some(alphas) = info
+            } else if let Some(alphas) = alphas_table
                 .ren1
                 .iter()
-                .zip(info.alphas.iter())
+                .zip(alphas_table.alphas.iter())
                 .find_map(|(&ren1, &alphas)| approx_eq!(f64, ren1, mur2, ulps = 64).then(|| alphas))
             {
                 alphas.powi(order.alphas.try_into().unwrap())
@@ -340,43 +861,75 @@ pub(crate) fn ndarray_from_subgrid_orders(
                 )));
             };
 
-            array[[fac1_indices[ifac1], xa_indices[ix1], xb_indices[ix2]]] += als * logs * value;
+            array[[xa_indices[ix1], xb_indices[ix2]]] += als * logs * value;
         }
     }
 
-    Ok((fac1, x1_a, x1_b, array))
+    Ok(found.then_some((x1_a, x1_b, array)))
+}
+
+fn same_grid(lhs: &[f64], rhs: &[f64]) -> bool {
+    (lhs.len() == rhs.len())
+        && lhs
+            .iter()
+            .zip(rhs)
+            .all(|(&lhs, &rhs)| approx_eq!(f64, lhs, rhs, ulps = 64))
 }
 
-pub(crate) fn evolve_with_one(
+/// Adds the contribution of the operator slices in `slices`, one for each initial state and
+/// described by their information, to `accumulator` for grids with a single convolution. Only the
+/// slice of the convolved initial state is used.
+pub(crate) fn evolve_slice_with_one(
     grid: &Grid,
-    operator: &ArrayView5<f64>,
-    info: &OperatorInfo,
+    slices: [(&OperatorSliceInfo, &ArrayView4<f64>); 2],
     order_mask: &[bool],
-) -> Result<(Array3<SubgridEnum>, Vec<LumiEntry>), GridError> {
+    xi: (f64, f64),
+    alphas_table: &AlphasTable,
+    accumulator: &mut FkTableAccumulator,
+) -> Result<(), GridError> {
+    accumulator.add_slice(slices.map(|(info, _)| info))?;
+
     let gluon_has_pid_zero = gluon_has_pid_zero(grid);
     let has_pdf1 = grid.has_pdf1();
+    let (info, operator) = slices[usize::from(!has_pdf1)];
 
     let (pid_indices, pids) = pids(operator, info, gluon_has_pid_zero, &|pid| {
         grid.lumi()
             .iter()
             .flat_map(LumiEntry::entry)
             .any(|&(a, b, _)| if has_pdf1 { a } else { b } == pid)
-    })?;
+    });
 
-    let lumi0 = lumi0_with_one(&pids);
-    let mut sub_fk_tables = Vec::with_capacity(grid.bin_info().bins() * lumi0.len());
-    let new_axis = if has_pdf1 { 2 } else { 1 };
+    // the initial state that isn't convoluted keeps its particle identifier
+    let pid = if has_pdf1 {
+        grid.initial_state_2()
+    } else {
+        grid.initial_state_1()
+    };
+    let lumi0 = |pid0| if has_pdf1 { (pid0, pid) } else { (pid, pid0) };
+
+    for &(pid0, _) in &pids {
+        let (a, b) = lumi0(pid0);
+        accumulator.table(grid, a, b);
+    }
 
     let mut last_x1 = Vec::new();
-    let mut last_fac1 = Vec::new();
     let mut ops = Vec::new();
 
-    for subgrids_ol in grid.subgrids().axis_iter(Axis(1)) {
-        let mut tables = vec![Array1::zeros(info.x0.len()); lumi0.len()];
-
+    for (bin, subgrids_ol) in grid.subgrids().axis_iter(Axis(1)).enumerate() {
         for (lumi1, subgrids_o) in subgrids_ol.axis_iter(Axis(1)).enumerate() {
-            let (fac1, x1_a, x1_b, array) =
-                ndarray_from_subgrid_orders(info, &subgrids_o, grid.orders(), order_mask)?;
+            let (x1_a, x1_b, array) = if let Some(result) = ndarray_from_subgrid_orders(
+                info.fac1,
+                &subgrids_o,
+                grid.orders(),
+                order_mask,
+                xi,
+                alphas_table,
+            )? {
+                result
+            } else {
+                continue;
+            };
 
             let x1 = if has_pdf1 { x1_a } else { x1_b };
 
@@ -384,232 +937,121 @@ pub(crate) fn evolve_with_one(
                 continue;
             }
 
-            if (last_fac1.len() != fac1.len())
-                || last_fac1
-                    .iter()
-                    .zip(fac1.iter())
-                    .any(|(&lhs, &rhs)| !approx_eq!(f64, lhs, rhs, ulps = 64))
-                || (last_x1.len() != x1.len())
-                || last_x1
-                    .iter()
-                    .zip(x1.iter())
-                    .any(|(&lhs, &rhs)| !approx_eq!(f64, lhs, rhs, ulps = 64))
-            {
-                ops = operators(operator, info, &fac1, &pid_indices, &x1)?;
-                last_fac1 = fac1;
+            if !same_grid(&last_x1, &x1) {
+                ops = operators(operator, info, &pid_indices, &x1)?;
                 last_x1 = x1;
             }
 
+            let array = array.index_axis(Axis(usize::from(has_pdf1)), 0);
+
             // TODO: get rid of array-index access
-            for (&pid1, &factor) in
-                grid.lumi()[lumi1].entry().iter().map(
-                    |(a, b, f)| {
-                        if has_pdf1 {
-                            (a, f)
-                        } else {
-                            (b, f)
-                        }
-                    },
-                )
-            {
-                for (fk_table, op) in
-                    lumi0
-                        .iter()
-                        .zip(tables.iter_mut())
-                        .filter_map(|(&pid0, fk_table)| {
-                            pids.iter()
-                                .zip(ops.iter())
-                                .find_map(|(&(p0, p1), op)| {
-                                    (p0 == pid0 && p1 == pid1).then_some(op)
-                                })
-                                .map(|op| (fk_table, op))
-                        })
-                {
-                    let mut result = Array1::zeros(info.x0.len());
+            for &(a, b, factor) in grid.lumi()[lumi1].entry() {
+                let pid1 = if has_pdf1 { a } else { b };
 
-                    for imu2 in 0..array.dim().0 {
-                        let op = op.index_axis(Axis(0), imu2);
+                for (&(pid0, _), op) in pids
+                    .iter()
+                    .zip(ops.iter())
+                    .filter(|((_, p1), _)| *p1 == pid1)
+                {
+                    let (a, b) = lumi0(pid0);
+                    let table = &mut accumulator.table(grid, a, b)[bin];
+                    let result = op.dot(&array);
 
-                        result += &op.dot(
-                            &array
-                                .index_axis(Axis(0), imu2)
-                                .index_axis(Axis(new_axis - 1), 0),
-                        );
+                    if has_pdf1 {
+                        table.index_axis_mut(Axis(1), 0).scaled_add(factor, &result);
+                    } else {
+                        table.index_axis_mut(Axis(0), 0).scaled_add(factor, &result);
                     }
-
-                    fk_table.scaled_add(factor, &result);
                 }
             }
         }
-
-        sub_fk_tables.extend(tables.into_iter().map(|table| {
-            ImportOnlySubgridV2::new(
-                SparseArray3::from_ndarray(
-                    table
-                        .insert_axis(Axis(0))
-                        .insert_axis(Axis(new_axis))
-                        .view(),
-                    0,
-                    1,
-                ),
-                vec![Mu2 {
-                    // TODO: FK tables don't depend on the renormalization scale
-                    //ren: -1.0,
-                    ren: info.fac0,
-                    fac: info.fac0,
-                }],
-                if has_pdf1 { info.x0.clone() } else { vec![1.0] },
-                if has_pdf1 { vec![1.0] } else { info.x0.clone() },
-            )
-            .into()
-        }));
     }
 
-    let pid = if has_pdf1 {
-        grid.initial_state_2()
-    } else {
-        grid.initial_state_1()
-    };
-
-    Ok((
-        Array1::from_iter(sub_fk_tables.into_iter())
-            .into_shape((1, grid.bin_info().bins(), lumi0.len()))
-            .unwrap(),
-        lumi0
-            .iter()
-            .map(|&a| {
-                lumi_entry![
-                    if has_pdf1 { a } else { pid },
-                    if has_pdf1 { pid } else { a },
-                    1.0
-                ]
-            })
-            .collect(),
-    ))
+    Ok(())
 }
 
-pub(crate) fn evolve_with_two(
+/// Adds the contribution of the operator slices in `slices`, one for each initial state and
+/// described by their information, to `accumulator` for grids with two convolutions.
+pub(crate) fn evolve_slice_with_two(
     grid: &Grid,
-    operator: &ArrayView5<f64>,
-    info: &OperatorInfo,
+    slices: [(&OperatorSliceInfo, &ArrayView4<f64>); 2],
     order_mask: &[bool],
-) -> Result<(Array3<SubgridEnum>, Vec<LumiEntry>), GridError> {
+    xi: (f64, f64),
+    alphas_table: &AlphasTable,
+    accumulator: &mut FkTableAccumulator,
+) -> Result<(), GridError> {
+    accumulator.add_slice(slices.map(|(info, _)| info))?;
+
     let gluon_has_pid_zero = gluon_has_pid_zero(grid);
+    let [(info_a, operator_a), (info_b, operator_b)] = slices;
 
-    let (pid_indices_a, pids_a) = pids(operator, info, gluon_has_pid_zero, &|pid1| {
+    let (pid_indices_a, pids_a) = pids(operator_a, info_a, gluon_has_pid_zero, &|pid1| {
         grid.lumi()
             .iter()
             .flat_map(LumiEntry::entry)
             .any(|&(a, _, _)| a == pid1)
-    })?;
-    let (pid_indices_b, pids_b) = pids(operator, info, gluon_has_pid_zero, &|pid1| {
+    });
+    let (pid_indices_b, pids_b) = pids(operator_b, info_b, gluon_has_pid_zero, &|pid1| {
         grid.lumi()
             .iter()
             .flat_map(LumiEntry::entry)
             .any(|&(_, b, _)| b == pid1)
-    })?;
+    });
 
-    let lumi0 = lumi0_with_two(&pids_a, &pids_b);
-    let mut sub_fk_tables = Vec::with_capacity(grid.bin_info().bins() * lumi0.len());
+    for (&(pida0, _), &(pidb0, _)) in pids_a.iter().cartesian_product(pids_b.iter()) {
+        accumulator.table(grid, pida0, pidb0);
+    }
 
-    let mut last_fac1 = Vec::new();
     let mut last_x1a = Vec::new();
     let mut last_x1b = Vec::new();
     let mut operators_a = Vec::new();
     let mut operators_b = Vec::new();
 
-    for subgrids_ol in grid.subgrids().axis_iter(Axis(1)) {
-        let mut tables = vec![Array2::zeros((info.x0.len(), info.x0.len())); lumi0.len()];
-
+    for (bin, subgrids_ol) in grid.subgrids().axis_iter(Axis(1)).enumerate() {
         for (lumi1, subgrids_o) in subgrids_ol.axis_iter(Axis(1)).enumerate() {
-            let (fac1, x1_a, x1_b, array) =
-                ndarray_from_subgrid_orders(info, &subgrids_o, grid.orders(), order_mask)?;
-
-            let fac1_diff = (last_fac1.len() != fac1.len())
-                || last_fac1
-                    .iter()
-                    .zip(fac1.iter())
-                    .any(|(&lhs, &rhs)| !approx_eq!(f64, lhs, rhs, ulps = 64));
+            let (x1_a, x1_b, array) = if let Some(result) = ndarray_from_subgrid_orders(
+                info_a.fac1,
+                &subgrids_o,
+                grid.orders(),
+                order_mask,
+                xi,
+                alphas_table,
+            )? {
+                result
+            } else {
+                continue;
+            };
 
-            if fac1_diff
-                || (last_x1a.len() != x1_a.len())
-                || last_x1a
-                    .iter()
-                    .zip(x1_a.iter())
-                    .any(|(&lhs, &rhs)| !approx_eq!(f64, lhs, rhs, ulps = 64))
-            {
-                operators_a = operators(operator, info, &fac1, &pid_indices_a, &x1_a)?;
+            if !same_grid(&last_x1a, &x1_a) {
+                operators_a = operators(operator_a, info_a, &pid_indices_a, &x1_a)?;
                 last_x1a = x1_a;
             }
 
-            if fac1_diff
-                || (last_x1b.len() != x1_b.len())
-                || last_x1b
-                    .iter()
-                    .zip(x1_b.iter())
-                    .any(|(&lhs, &rhs)| !approx_eq!(f64, lhs, rhs, ulps = 64))
-            {
-                operators_b = operators(operator, info, &fac1, &pid_indices_b, &x1_b)?;
+            if !same_grid(&last_x1b, &x1_b) {
+                operators_b = operators(operator_b, info_b, &pid_indices_b, &x1_b)?;
                 last_x1b = x1_b;
             }
 
-            if fac1_diff {
-                last_fac1 = fac1;
-            };
-
             // TODO: get rid of array-index access
             for &(pida1, pidb1, factor) in grid.lumi()[lumi1].entry() {
-                for (fk_table, opa, opb) in
-                    lumi0
-                        .iter()
-                        .zip(tables.iter_mut())
-                        .filter_map(|(&(pida0, pidb0), fk_table)| {
-                            pids_a
-                                .iter()
-                                .zip(operators_a.iter())
-                                .cartesian_product(pids_b.iter().zip(operators_b.iter()))
-                                .find_map(|((&(pa0, pa1), opa), (&(pb0, pb1), opb))| {
-                                    (pa0 == pida0 && pa1 == pida1 && pb0 == pidb0 && pb1 == pidb1)
-                                        .then_some((opa, opb))
-                                })
-                                .map(|(opa, opb)| (fk_table, opa, opb))
-                        })
+                for ((&(pida0, _), opa), (&(pidb0, _), opb)) in pids_a
+                    .iter()
+                    .zip(operators_a.iter())
+                    .filter(|((_, pa1), _)| *pa1 == pida1)
+                    .cartesian_product(
+                        pids_b
+                            .iter()
+                            .zip(operators_b.iter())
+                            .filter(|((_, pb1), _)| *pb1 == pidb1),
+                    )
                 {
-                    let mut result = Array2::zeros((info.x0.len(), info.x0.len()));
-
-                    for imu2 in 0..array.dim().0 {
-                        let opa = opa.index_axis(Axis(0), imu2);
-                        let opb = opb.index_axis(Axis(0), imu2);
-                        let arr = array.index_axis(Axis(0), imu2);
+                    let table = &mut accumulator.table(grid, pida0, pidb0)[bin];
 
-                        result += &opa.dot(&arr.dot(&opb.t()));
-                    }
-
-                    fk_table.scaled_add(factor, &result);
+                    table.scaled_add(factor, &opa.dot(&array.dot(&opb.t())));
                 }
             }
         }
-
-        sub_fk_tables.extend(tables.into_iter().map(|table| {
-            ImportOnlySubgridV2::new(
-                SparseArray3::from_ndarray(table.insert_axis(Axis(0)).view(), 0, 1),
-                vec![Mu2 {
-                    // TODO: FK tables don't depend on the renormalization scale
-                    //ren: -1.0,
-                    ren: info.fac0,
-                    fac: info.fac0,
-                }],
-                info.x0.clone(),
-                info.x0.clone(),
-            )
-            .into()
-        }));
     }
 
-    Ok((
-        Array1::from_iter(sub_fk_tables.into_iter())
-            .into_shape((1, grid.bin_info().bins(), lumi0.len()))
-            .unwrap(),
-        lumi0.iter().map(|&(a, b)| lumi_entry![a, b, 1.0]).collect(),
-    ))
+    Ok(())
 }
@@ -1,10 +1,14 @@
 //! Provides the [`FkTable`] type.
 
 use super::grid::{Grid, GridError, Order};
+use super::import_only_subgrid::ImportOnlySubgridV2;
 use super::lumi::LumiCache;
+use super::pids;
+use super::sparse_array3::SparseArray3;
 use super::subgrid::Subgrid;
 use float_cmp::approx_eq;
-use ndarray::Array4;
+use itertools::Itertools;
+use ndarray::{Array4, ArrayView2};
 use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
 use std::io::Write;
@@ -16,8 +20,9 @@ use thiserror::Error;
 ///
 /// - all subgrids of the grid evaluate the PDFs at a single factorization scale given by
 ///   [`FkTable::muf2`].
-/// - all subgrids, for both hadronic initial states (if both initial states are hadronic), share
-///   the same `x` grid. See [`FkTable::x_grid`].
+/// - all subgrids share the same `x` grid for each hadronic initial state. The `x` grids of both
+///   initial states may be different, for instance if the FK table was created with
+///   [`Grid::evolve_with_slice_iter2`]. See [`FkTable::x_grid`].
 /// - the luminosity function is *simple*, meaning that every entry consists of a single pair of
 ///   partons with trivial factor `1.0`, and all tuples are distinct from each other. See
 ///   [`Grid::lumi`].
@@ -44,6 +49,27 @@ pub enum TryFromGridError {
     MetadataMissing(String),
 }
 
+/// The error type returned by [`FkTable::merge`].
+#[derive(Debug, Error)]
+pub enum MergeFkTableError {
+    /// Error if the FK tables have different factorization scales.
+    #[error("the FK tables have different factorization scales: {0} and {1}")]
+    DifferentMuf2(f64, f64),
+    /// Error if the FK tables have different values for the given metadata key, for instance for
+    /// different initial states or optimization assumptions.
+    #[error("the FK tables have different values for the metadata key `{0}`")]
+    DifferentMetadata(String),
+    /// Error if the `x` grids of the FK tables are different and interpolation is not allowed.
+    #[error("the FK tables have different x grids")]
+    DifferentXGrids,
+    /// Error if an `x` value can not be interpolated, because it lies outside the `x` grid.
+    #[error("x = {0} lies outside the x grid of the FK table")]
+    XOutsideGrid(f64),
+    /// Error if the bins of the FK tables can not be merged.
+    #[error(transparent)]
+    MergeFailure(#[from] GridError),
+}
+
 /// The optimization assumptions for an [`FkTable`], needed for [`FkTable::optimize`]. Since FK
 /// tables are typically stored at very small `Q2 = Q0`, the PDFs `f(x,Q0)` of heavy quarks are
 /// typically set to zero at this scale or set to the same value as their anti-quark PDF. This is
@@ -76,6 +102,43 @@ pub enum FkAssumptions {
     Nf3Sym,
 }
 
+/// A condition that [`FkAssumptions`] impose on the PDFs of a quark and its anti-quark at the FK
+/// table scale.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FkCondition {
+    /// The PDFs of the quark with the given PDG ID and of its anti-quark are zero.
+    Zero(i32),
+    /// The PDFs of the quark with the given PDG ID and of its anti-quark are the same.
+    Symmetric(i32),
+}
+
+/// The result of checking an [`FkCondition`] with a PDF, see [`FkTable::check_assumptions`].
+#[derive(Debug, Clone, Copy)]
+pub struct FkConditionCheck {
+    /// The condition that was checked.
+    pub condition: FkCondition,
+    /// The value of `x` for which the condition is violated the most.
+    pub x: f64,
+    /// The largest violation of the condition, which is `max(|xq|, |xqbar|)` for
+    /// [`FkCondition::Zero`] and `|xq - xqbar|` for [`FkCondition::Symmetric`], where `xq` and
+    /// `xqbar` are the PDFs of the quark and anti-quark multiplied with `x`, relative to the
+    /// largest absolute value of the PDFs of the gluon and all quarks at the same `x`. If the
+    /// condition is satisfied exactly this is zero.
+    pub violation: f64,
+}
+
+impl FkConditionCheck {
+    /// Relative violation up to which a condition is considered to be satisfied, which accounts
+    /// for the limited numerical precision of the PDFs.
+    pub const TOLERANCE: f64 = 1e-8;
+
+    /// Returns `true` if the condition is satisfied within [`FkConditionCheck::TOLERANCE`].
+    #[must_use]
+    pub fn is_satisfied(&self) -> bool {
+        self.violation <= Self::TOLERANCE
+    }
+}
+
 /// Error type when trying to construct [`FkAssumptions`] with a string.
 #[derive(Debug, Eq, Error, PartialEq)]
 #[error("unknown variant for FkAssumptions: {variant}")]
@@ -102,6 +165,68 @@ impl Display for FkAssumptions {
     }
 }
 
+impl Display for FkCondition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = |pid: i32| match pid {
+            1 => "d".to_string(),
+            2 => "u".to_string(),
+            3 => "s".to_string(),
+            4 => "c".to_string(),
+            5 => "b".to_string(),
+            6 => "t".to_string(),
+            _ => pid.to_string(),
+        };
+
+        match *self {
+            Self::Zero(pid) => write!(f, "{0} = {0}bar = 0", name(pid)),
+            Self::Symmetric(pid) => write!(f, "{0} = {0}bar", name(pid)),
+        }
+    }
+}
+
+impl FkAssumptions {
+    /// Returns the conditions these assumptions impose on the PDFs at the FK table scale.
+    #[must_use]
+    pub fn conditions(self) -> Vec<FkCondition> {
+        let (nf, symmetric) = match self {
+            Self::Nf6Ind => (6, false),
+            Self::Nf6Sym => (6, true),
+            Self::Nf5Ind => (5, false),
+            Self::Nf5Sym => (5, true),
+            Self::Nf4Ind => (4, false),
+            Self::Nf4Sym => (4, true),
+            Self::Nf3Ind => (3, false),
+            Self::Nf3Sym => (3, true),
+        };
+
+        (nf + 1..=6)
+            .map(FkCondition::Zero)
+            .chain(symmetric.then_some(FkCondition::Symmetric(nf)))
+            .collect()
+    }
+
+    /// Evaluates the PDF `xfx` for the parton `pid` at `x` and `q2`, modified such that it
+    /// satisfies these assumptions: PDFs that must be zero are set to zero, and PDFs that must be
+    /// the same as their anti-quark PDFs are replaced by the average of both.
+    pub fn apply(
+        self,
+        xfx: &mut dyn FnMut(i32, f64, f64) -> f64,
+        pid: i32,
+        x: f64,
+        q2: f64,
+    ) -> f64 {
+        match self
+            .conditions()
+            .into_iter()
+            .find(|&(FkCondition::Zero(quark) | FkCondition::Symmetric(quark))| quark == pid.abs())
+        {
+            Some(FkCondition::Zero(_)) => 0.0,
+            Some(FkCondition::Symmetric(_)) => 0.5 * (xfx(pid, x, q2) + xfx(-pid, x, q2)),
+            None => xfx(pid, x, q2),
+        }
+    }
+}
+
 impl FromStr for FkAssumptions {
     type Err = UnknownFkAssumption;
 
@@ -226,7 +351,9 @@ impl FkTable {
             .collect()
     }
 
-    /// Returns the single `muf2` scale of this `FkTable`.
+    /// Returns the single `muf2` scale of this `FkTable`. If the initial states were evolved from
+    /// different fitting scales, this is the one of the first convolved initial state; see
+    /// [`FkTable::muf2_per_initial_state`].
     #[must_use]
     pub fn muf2(&self) -> f64 {
         if let &[muf2] = &self.grid.evolve_info(&[true]).fac1[..] {
@@ -237,7 +364,124 @@ impl FkTable {
         }
     }
 
-    /// Returns the x grid that all subgrids for all hadronic initial states share.
+    /// Returns the squared factorization scales at which the PDFs of the first and second initial
+    /// state must be evaluated. They are different if the initial states were evolved with
+    /// separate operators from different fitting scales, see [`Grid::evolve_with_slice_iter2`].
+    /// In this case [`FkTable::convolute`], which evaluates all PDFs at [`FkTable::muf2`], gives
+    /// wrong results and [`FkTable::convolute_tabulated_with_two`] must be used instead. For
+    /// `FkTable`s that do not store separate scales both values are [`FkTable::muf2`].
+    #[must_use]
+    pub fn muf2_per_initial_state(&self) -> [f64; 2] {
+        ["fk_muf2_1", "fk_muf2_2"].map(|key| {
+            self.key_values()
+                .and_then(|key_values| key_values.get(key))
+                .and_then(|muf2| muf2.parse().ok())
+                .unwrap_or_else(|| self.muf2())
+        })
+    }
+
+    /// Returns the assumptions this `FkTable` was optimized with by [`FkTable::optimize`], or
+    /// `None` if it was not optimized.
+    #[must_use]
+    pub fn assumptions(&self) -> Option<FkAssumptions> {
+        self.key_values()
+            .and_then(|key_values| key_values.get("fk_assumptions"))
+            .and_then(|assumptions| assumptions.parse().ok())
+    }
+
+    /// Checks whether the PDF `xfx` satisfies the conditions of `assumptions` at the scale
+    /// [`FkTable::muf2`] and the points of [`FkTable::x_grid`]. For every condition the largest
+    /// violation is returned. To check the assumptions this `FkTable` was optimized with, pass
+    /// [`FkTable::assumptions`].
+    pub fn check_assumptions(
+        &self,
+        assumptions: FkAssumptions,
+        xfx: &mut dyn FnMut(i32, f64, f64) -> f64,
+    ) -> Vec<FkConditionCheck> {
+        let conditions = assumptions.conditions();
+
+        if conditions.is_empty() {
+            return Vec::new();
+        }
+
+        let muf2 = self.muf2();
+        let x_grid = self.x_grid();
+        // the largest PDF for each `x`, relative to which the violations are given
+        let norms: Vec<_> = x_grid
+            .iter()
+            .map(|&x| {
+                (-6..=6)
+                    .map(|pid| xfx(if pid == 0 { 21 } else { pid }, x, muf2).abs())
+                    .fold(0.0, f64::max)
+            })
+            .collect();
+
+        conditions
+            .into_iter()
+            .filter_map(|condition| {
+                x_grid
+                    .iter()
+                    .zip(&norms)
+                    .map(|(&x, &norm)| {
+                        let violation = match condition {
+                            FkCondition::Zero(pid) => {
+                                xfx(pid, x, muf2).abs().max(xfx(-pid, x, muf2).abs())
+                            }
+                            FkCondition::Symmetric(pid) => {
+                                (xfx(pid, x, muf2) - xfx(-pid, x, muf2)).abs()
+                            }
+                        };
+                        // if all PDFs vanish, so does the violation
+                        let violation = if violation == 0.0 {
+                            0.0
+                        } else {
+                            violation / norm
+                        };
+
+                        FkConditionCheck {
+                            condition,
+                            x,
+                            violation,
+                        }
+                    })
+                    .max_by(|lhs, rhs| lhs.violation.total_cmp(&rhs.violation))
+            })
+            .collect()
+    }
+
+    /// Returns the most restrictive [`FkAssumptions`] that are satisfied by all PDFs in `xfxs` at
+    /// the scale [`FkTable::muf2`] and the points of [`FkTable::x_grid`]. These are the strongest
+    /// assumptions that can be passed to [`FkTable::optimize`] without changing the predictions
+    /// of any of the PDFs. If `xfxs` is empty, the most restrictive assumptions are returned.
+    pub fn detect_assumptions(
+        &self,
+        xfxs: &mut [&mut dyn FnMut(i32, f64, f64) -> f64],
+    ) -> FkAssumptions {
+        // ordered from the most to the least restrictive assumptions, each one implying the next
+        let candidates = [
+            FkAssumptions::Nf3Sym,
+            FkAssumptions::Nf3Ind,
+            FkAssumptions::Nf4Sym,
+            FkAssumptions::Nf4Ind,
+            FkAssumptions::Nf5Sym,
+            FkAssumptions::Nf5Ind,
+            FkAssumptions::Nf6Sym,
+        ];
+
+        candidates
+            .into_iter()
+            .find(|assumptions| {
+                xfxs.iter_mut().all(|xfx| {
+                    self.check_assumptions(*assumptions, *xfx)
+                        .iter()
+                        .all(FkConditionCheck::is_satisfied)
+                })
+            })
+            .unwrap_or(FkAssumptions::Nf6Ind)
+    }
+
+    /// Returns the x grid that all subgrids for all hadronic initial states share. If the initial
+    /// states have different `x` grids, the union of them is returned.
     #[must_use]
     pub fn x_grid(&self) -> Vec<f64> {
         self.grid.evolve_info(&[true]).x1
@@ -262,16 +506,195 @@ impl FkTable {
     }
 
     /// Propagate convolute to grid
+    ///
+    /// # Errors
+    ///
+    /// See [`Grid::convolute`].
     pub fn convolute(
         &self,
         lumi_cache: &mut LumiCache,
         bin_indices: &[usize],
         lumi_mask: &[bool],
-    ) -> Vec<f64> {
+    ) -> Result<Vec<f64>, GridError> {
         self.grid
             .convolute(lumi_cache, &[], bin_indices, lumi_mask, &[(1.0, 1.0)])
     }
 
+    /// Convolutes this `FkTable` with the PDF of a single hadron with PDG id `pdg_id`, which is
+    /// tabulated in `xfx` for the scale [`FkTable::muf2`] and the points of [`FkTable::x_grid`]:
+    /// the value `xfx[[i, j]]` must be the PDF of the parton with PDG id `pids[i]` multiplied with
+    /// `x_grid[j]`. In contrast to [`FkTable::convolute`] no interpolation is performed. Partons
+    /// that are not contained in `pids` have a vanishing PDF. If an initial state of this
+    /// `FkTable` is the anti-particle of `pdg_id`, the PDF is charge conjugated. The parameters
+    /// `bin_indices` and `lumi_mask` select bins and channels as in [`Grid::convolute`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::TabulatedPdfShapeMismatch`] if `xfx` does not have a row for each
+    /// entry of `pids` and a column for each point of [`FkTable::x_grid`],
+    /// [`GridError::IncompatibleInitialState`] if an initial state of this `FkTable` is neither
+    /// `pdg_id` nor its anti-particle, and [`GridError::InvalidBinSelection`] if `bin_indices`
+    /// contains an index that is larger or equal the number of bins.
+    pub fn convolute_tabulated_with_one(
+        &self,
+        pdg_id: i32,
+        pids: &[i32],
+        xfx: ArrayView2<f64>,
+        bin_indices: &[usize],
+        lumi_mask: &[bool],
+    ) -> Result<Vec<f64>, GridError> {
+        self.convolute_tabulated(
+            [(Some(pdg_id), pids, xfx), (Some(pdg_id), pids, xfx)],
+            bin_indices,
+            lumi_mask,
+        )
+    }
+
+    /// Convolutes this `FkTable` with two PDFs tabulated in `xfx1` and `xfx2`, which are used for
+    /// the first and second initial state, respectively. Since the PDFs are given for the initial
+    /// states directly, they are never charge conjugated. See
+    /// [`FkTable::convolute_tabulated_with_one`] for the meaning of the remaining parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::TabulatedPdfShapeMismatch`] if the dimensions of `xfx1` or `xfx2` do
+    /// not match `pids1` or `pids2` and [`FkTable::x_grid`], respectively, and
+    /// [`GridError::InvalidBinSelection`] if `bin_indices` contains an index that is larger or
+    /// equal the number of bins.
+    pub fn convolute_tabulated_with_two(
+        &self,
+        pids1: &[i32],
+        xfx1: ArrayView2<f64>,
+        pids2: &[i32],
+        xfx2: ArrayView2<f64>,
+        bin_indices: &[usize],
+        lumi_mask: &[bool],
+    ) -> Result<Vec<f64>, GridError> {
+        self.convolute_tabulated(
+            [
+                (None, pids1, xfx1.reborrow()),
+                (None, pids2, xfx2.reborrow()),
+            ],
+            bin_indices,
+            lumi_mask,
+        )
+    }
+
+    fn convolute_tabulated(
+        &self,
+        pdfs: [(Option<i32>, &[i32], ArrayView2<f64>); 2],
+        bin_indices: &[usize],
+        lumi_mask: &[bool],
+    ) -> Result<Vec<f64>, GridError> {
+        let x_grid = self.x_grid();
+        let initial_states = [self.grid.initial_state_1(), self.grid.initial_state_2()];
+        let has_pdfs = [self.grid.has_pdf1(), self.grid.has_pdf2()];
+
+        // for each initial state: `None` if it is not a hadron, otherwise whether the PDF must be
+        // charge conjugated
+        let cc: Vec<_> = pdfs
+            .iter()
+            .zip(initial_states)
+            .zip(has_pdfs)
+            .map(|(((pdg_id, pids, xfx), initial_state), has_pdf)| {
+                if xfx.dim() != (pids.len(), x_grid.len()) {
+                    return Err(GridError::TabulatedPdfShapeMismatch {
+                        actual: xfx.dim(),
+                        expected: (pids.len(), x_grid.len()),
+                    });
+                }
+
+                if !has_pdf {
+                    return Ok(None);
+                }
+
+                match *pdg_id {
+                    None => Ok(Some(false)),
+                    Some(pdg_id) if pdg_id == initial_state => Ok(Some(false)),
+                    Some(pdg_id) if pdg_id == -initial_state => Ok(Some(true)),
+                    Some(pdg_id) => Err(GridError::IncompatibleInitialState {
+                        pdg_id,
+                        initial_state,
+                    }),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        // returns the PDF of `pid` for the initial state `index`, divided by `x`
+        let pdf = |index: usize, pid: i32, ix: usize| -> f64 {
+            cc[index].map_or(1.0, |cc| {
+                let (_, pids, xfx) = &pdfs[index];
+                let pid = if cc {
+                    pids::charge_conjugate_pdg_pid(pid)
+                } else {
+                    pid
+                };
+
+                pids.iter()
+                    .position(|&p| p == pid)
+                    .map_or(0.0, |row| xfx[[row, ix]] / x_grid[ix])
+            })
+        };
+
+        let bins = self.bins();
+
+        if bin_indices.iter().any(|&bin| bin >= bins) {
+            return Err(GridError::InvalidBinSelection {
+                bin_indices: bin_indices.to_vec(),
+                bins,
+            });
+        }
+
+        let bin_indices = if bin_indices.is_empty() {
+            (0..bins).collect()
+        } else {
+            bin_indices.to_vec()
+        };
+        let normalizations = self.bin_normalizations();
+        let x_index = |x: &f64| {
+            x_grid
+                .iter()
+                .position(|xi| approx_eq!(f64, *x, *xi, ulps = 2))
+                .unwrap()
+        };
+
+        Ok(bin_indices
+            .into_iter()
+            .map(|bin| {
+                let mut result = 0.0;
+
+                for (lumi, &(a, b)) in self.lumi().iter().enumerate() {
+                    if !lumi_mask.is_empty() && !lumi_mask[lumi] {
+                        continue;
+                    }
+
+                    let subgrid = self.grid.subgrid(0, bin, lumi);
+
+                    if subgrid.is_empty() {
+                        continue;
+                    }
+
+                    let indices1: Vec<_> = if cc[0].is_some() {
+                        subgrid.x1_grid().iter().map(x_index).collect()
+                    } else {
+                        vec![0]
+                    };
+                    let indices2: Vec<_> = if cc[1].is_some() {
+                        subgrid.x2_grid().iter().map(x_index).collect()
+                    } else {
+                        vec![0]
+                    };
+
+                    for ((_, ix1, ix2), value) in subgrid.indexed_iter() {
+                        result += value * pdf(0, a, indices1[ix1]) * pdf(1, b, indices2[ix2]);
+                    }
+                }
+
+                result / normalizations[bin]
+            })
+            .collect())
+    }
+
     /// Set a metadata key-value pair
     pub fn set_key_value(&mut self, key: &str, value: &str) {
         self.grid.set_key_value(key, value);
@@ -341,6 +764,140 @@ impl FkTable {
             .set_key_value("fk_assumptions", &assumptions.to_string());
         self.grid.optimize();
     }
+
+    /// Merges the bins of `other` into this `FkTable`, appending them to the bins of `self`. Both
+    /// FK tables must have the same factorization scale, initial states, particle basis and
+    /// [`FkAssumptions`]. The subgrids of `other` are converted to the `x` grids of `self`: `x`
+    /// values that agree up to numerical noise are identified with each other and, if
+    /// `interpolate` is `true`, all others are interpolated with Lagrange polynomials of third
+    /// order in `ln(x)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if both FK tables are incompatible, if their `x` grids are different and
+    /// `interpolate` is `false`, if an `x` value of `other` lies outside the `x` grid of `self` or
+    /// if the bins can not be merged.
+    pub fn merge(&mut self, other: Self, interpolate: bool) -> Result<(), MergeFkTableError> {
+        if self.grid.initial_state_1() != other.grid.initial_state_1() {
+            return Err(MergeFkTableError::DifferentMetadata(
+                "initial_state_1".to_string(),
+            ));
+        }
+
+        if self.grid.initial_state_2() != other.grid.initial_state_2() {
+            return Err(MergeFkTableError::DifferentMetadata(
+                "initial_state_2".to_string(),
+            ));
+        }
+
+        for key in ["lumi_id_types", "fk_assumptions"] {
+            let lhs = self.key_values().and_then(|key_values| key_values.get(key));
+            let rhs = other
+                .key_values()
+                .and_then(|key_values| key_values.get(key));
+
+            if lhs != rhs {
+                return Err(MergeFkTableError::DifferentMetadata(key.to_string()));
+            }
+        }
+
+        let mu2 = |fk_table: &Self| {
+            fk_table
+                .grid
+                .subgrids()
+                .iter()
+                .find(|subgrid| !subgrid.is_empty())
+                .map(|subgrid| subgrid.mu2_grid()[0].clone())
+        };
+        let lhs_mu2 = mu2(self);
+        let rhs_mu2 = mu2(&other);
+
+        if let (Some(lhs), Some(rhs)) = (&lhs_mu2, &rhs_mu2) {
+            if !approx_eq!(f64, lhs.fac, rhs.fac, ulps = 64) {
+                return Err(MergeFkTableError::DifferentMuf2(lhs.fac, rhs.fac));
+            }
+
+            // FK tables written before the scales were stored per initial state don't have the
+            // corresponding metadata, which is why we compare the values including defaults
+            for (lhs, rhs) in self
+                .muf2_per_initial_state()
+                .into_iter()
+                .zip(other.muf2_per_initial_state())
+            {
+                if !approx_eq!(f64, lhs, rhs, ulps = 64) {
+                    return Err(MergeFkTableError::DifferentMuf2(lhs, rhs));
+                }
+            }
+        }
+
+        // if `self` is empty, the subgrids of `other` keep their scale and `x` grids
+        let (mu2, [x_grid1, x_grid2]) = if let Some(mu2) = lhs_mu2 {
+            (mu2, self.x_grids())
+        } else if let Some(mu2) = rhs_mu2 {
+            (mu2, other.x_grids())
+        } else {
+            self.grid.append(other.grid)?;
+            return Ok(());
+        };
+
+        let mut grid = other.grid;
+
+        for bin in 0..grid.bin_info().bins() {
+            for lumi in 0..grid.lumi().len() {
+                let subgrid = grid.subgrid(0, bin, lumi);
+
+                if subgrid.is_empty() {
+                    continue;
+                }
+
+                let weights1 = interpolation_weights(&subgrid.x1_grid(), &x_grid1, interpolate)?;
+                let weights2 = interpolation_weights(&subgrid.x2_grid(), &x_grid2, interpolate)?;
+                let mut array = SparseArray3::new(1, x_grid1.len(), x_grid2.len());
+
+                for ((_, ix1, ix2), value) in subgrid.indexed_iter() {
+                    for (&(jx1, w1), &(jx2, w2)) in
+                        weights1[ix1].iter().cartesian_product(&weights2[ix2])
+                    {
+                        array[[0, jx1, jx2]] += w1 * w2 * value;
+                    }
+                }
+
+                grid.set_subgrid(
+                    0,
+                    bin,
+                    lumi,
+                    ImportOnlySubgridV2::new(
+                        array,
+                        vec![mu2.clone()],
+                        x_grid1.clone(),
+                        x_grid2.clone(),
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        self.grid.append(grid)?;
+
+        Ok(())
+    }
+
+    /// Returns the `x` grids of both initial states.
+    fn x_grids(&self) -> [Vec<f64>; 2] {
+        let mut x_grids = [Vec::new(), Vec::new()];
+
+        for subgrid in self.grid.subgrids().iter().filter(|s| !s.is_empty()) {
+            x_grids[0].extend_from_slice(&subgrid.x1_grid());
+            x_grids[1].extend_from_slice(&subgrid.x2_grid());
+        }
+
+        for x_grid in &mut x_grids {
+            x_grid.sort_by(f64::total_cmp);
+            x_grid.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = 64));
+        }
+
+        x_grids
+    }
 }
 
 impl TryFrom<Grid> for FkTable {
@@ -355,6 +912,7 @@ impl TryFrom<Grid> for FkTable {
                 alpha: 0,
                 logxir: 0,
                 logxif: 0,
+                logxia: 0,
             }]
         {
             return Err(TryFromGridError::NonTrivialOrder);
@@ -418,9 +976,430 @@ impl TryFrom<Grid> for FkTable {
     }
 }
 
+/// Returns for each value of `x` the indices of the points of `x_grid` and the weights with which
+/// the value is distributed to them. Values that are also in `x_grid` are mapped to their point,
+/// all others are interpolated in `ln(x)` using the (up to) four closest points of `x_grid`, if
+/// `interpolate` is `true`.
+fn interpolation_weights(
+    x: &[f64],
+    x_grid: &[f64],
+    interpolate: bool,
+) -> Result<Vec<Vec<(usize, f64)>>, MergeFkTableError> {
+    x.iter()
+        .map(|&x| {
+            if let Some(index) = x_grid
+                .iter()
+                .position(|&xg| approx_eq!(f64, xg, x, ulps = 64))
+            {
+                return Ok(vec![(index, 1.0)]);
+            }
+
+            if !interpolate {
+                return Err(MergeFkTableError::DifferentXGrids);
+            }
+
+            if x_grid.is_empty() || (x < x_grid[0]) || (x > x_grid[x_grid.len() - 1]) {
+                return Err(MergeFkTableError::XOutsideGrid(x));
+            }
+
+            let points = x_grid.len().min(4);
+            let start = x_grid
+                .partition_point(|&xg| xg < x)
+                .saturating_sub(points / 2)
+                .min(x_grid.len() - points);
+            let y: Vec<_> = x_grid[start..start + points]
+                .iter()
+                .map(|xg| xg.ln())
+                .collect();
+            let y0 = x.ln();
+
+            Ok((0..points)
+                .map(|i| {
+                    let weight = (0..points)
+                        .filter(|&j| j != i)
+                        .map(|j| (y0 - y[j]) / (y[i] - y[j]))
+                        .product();
+
+                    (start + i, weight)
+                })
+                .collect())
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::grid::{Grid, Order};
+    use crate::import_only_subgrid::ImportOnlySubgridV2;
+    use crate::lumi_entry;
+    use crate::sparse_array3::SparseArray3;
+    use crate::subgrid::{Mu2, SubgridParams};
+    use float_cmp::assert_approx_eq;
+    use ndarray::Array2;
+
+    const X_GRID: [f64; 3] = [0.1, 0.2, 0.5];
+    const PIDS: [i32; 6] = [-2, -1, 1, 2, 21, 22];
+
+    fn xfx(pid: i32, x: f64, _: f64) -> f64 {
+        x * (1.0 - x) * f64::from(pid.abs() + 1) * if pid < 0 { 0.5 } else { 1.0 }
+    }
+
+    fn xfx_other(pid: i32, x: f64, _: f64) -> f64 {
+        x.sqrt() * f64::from(pid + 30)
+    }
+
+    fn tabulate(xfx: fn(i32, f64, f64) -> f64) -> Array2<f64> {
+        Array2::from_shape_fn((PIDS.len(), X_GRID.len()), |(i, j)| {
+            xfx(PIDS[i], X_GRID[j], 100.0)
+        })
+    }
+
+    fn fk_table() -> FkTable {
+        fk_table_with(&X_GRID, 100.0)
+    }
+
+    fn fk_table_with(x_grid: &[f64], muf2: f64) -> FkTable {
+        let mut grid = Grid::new(
+            vec![
+                lumi_entry![2, 2, 1.0],
+                lumi_entry![21, -2, 1.0],
+                lumi_entry![1, 22, 1.0],
+            ],
+            vec![Order::new(0, 0, 0, 0, 0)],
+            vec![0.0, 1.0, 3.0],
+            SubgridParams::default(),
+        );
+        let mut values = (1..).map(f64::from);
+
+        for bin in 0..2 {
+            for lumi in 0..3 {
+                let mut array = SparseArray3::new(1, x_grid.len(), x_grid.len());
+
+                for ix1 in 0..x_grid.len() {
+                    for ix2 in 0..x_grid.len() {
+                        array[[0, ix1, ix2]] = values.next().unwrap();
+                    }
+                }
+
+                grid.set_subgrid(
+                    0,
+                    bin,
+                    lumi,
+                    ImportOnlySubgridV2::new(
+                        array,
+                        vec![Mu2 {
+                            ren: muf2,
+                            fac: muf2,
+                        }],
+                        x_grid.to_vec(),
+                        x_grid.to_vec(),
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        grid.set_key_value("lumi_id_types", "pdg_mc_ids");
+
+        FkTable::try_from(grid).unwrap()
+    }
+
+    #[test]
+    fn convolute_tabulated() {
+        let fk_table = fk_table();
+        let xfx1 = tabulate(xfx);
+        // `LumiCache::with_two` below charge conjugates the second PDF
+        let xfx2 = tabulate(|id, x, q2| xfx_other(pids::charge_conjugate_pdg_pid(id), x, q2));
+        let mut alphas = |_| 1.0;
+
+        assert_eq!(fk_table.x_grid(), X_GRID);
+
+        for pdg_id in [2212, -2212] {
+            let mut pdf = xfx;
+            let mut lumi_cache = LumiCache::with_one(pdg_id, &mut pdf, &mut alphas);
+            let reference = fk_table.convolute(&mut lumi_cache, &[], &[]).unwrap();
+            let results = fk_table
+                .convolute_tabulated_with_one(pdg_id, &PIDS, xfx1.view(), &[], &[])
+                .unwrap();
+
+            assert_eq!(results.len(), 2);
+
+            for (result, reference) in results.iter().zip(&reference) {
+                assert_approx_eq!(f64, *result, *reference, ulps = 16);
+            }
+        }
+
+        let mut pdf1 = xfx;
+        let mut pdf2 = xfx_other;
+        let mut lumi_cache = LumiCache::with_two(2212, &mut pdf1, -2212, &mut pdf2, &mut alphas);
+        let reference = fk_table
+            .convolute(&mut lumi_cache, &[1], &[false, true, true])
+            .unwrap();
+        let results = fk_table
+            .convolute_tabulated_with_two(
+                &PIDS,
+                xfx1.view(),
+                &PIDS,
+                xfx2.view(),
+                &[1],
+                &[false, true, true],
+            )
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_approx_eq!(f64, results[0], reference[0], ulps = 16);
+    }
+
+    #[test]
+    fn convolute_tabulated_errors() {
+        let fk_table = fk_table();
+        let xfx = tabulate(xfx);
+
+        assert_eq!(
+            fk_table
+                .convolute_tabulated_with_one(2212, &PIDS[1..], xfx.view(), &[], &[])
+                .unwrap_err()
+                .to_string(),
+            "the tabulated PDF has the shape (6, 3), but expected is (5, 3)"
+        );
+        assert_eq!(
+            fk_table
+                .convolute_tabulated_with_one(2112, &PIDS, xfx.view(), &[], &[])
+                .unwrap_err()
+                .to_string(),
+            "the PDF for the hadron 2112 can not be used for the initial state 2212"
+        );
+        assert_eq!(
+            fk_table
+                .convolute_tabulated_with_two(&PIDS, xfx.view(), &PIDS, xfx.view(), &[2], &[])
+                .unwrap_err()
+                .to_string(),
+            "the bin indices [2] are not a valid selection of the 2 bins"
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let mut fk_table = fk_table();
+        let xfx1 = tabulate(xfx);
+        let xfx2 = tabulate(xfx_other);
+        let convolute = |fk_table: &FkTable| {
+            fk_table
+                .convolute_tabulated_with_two(&PIDS, xfx1.view(), &PIDS, xfx2.view(), &[], &[])
+                .unwrap()
+        };
+        let reference = convolute(&fk_table);
+
+        // `x` values that differ by numerical noise are identified with each other
+        let x_grid: Vec<_> = X_GRID
+            .iter()
+            .map(|x| f64::from_bits(x.to_bits() + 4))
+            .collect();
+        fk_table
+            .merge(fk_table_with(&x_grid, 100.0), false)
+            .unwrap();
+
+        assert_eq!(fk_table.bins(), 4);
+        assert_eq!(fk_table.x_grid(), X_GRID);
+        assert_eq!(
+            convolute(&fk_table),
+            [&reference[..], &reference[..]].concat()
+        );
+    }
+
+    #[test]
+    fn merge_with_interpolation() {
+        const OTHER_X_GRID: [f64; 3] = [0.15, 0.3, 0.45];
+
+        // PDFs that are quadratic in `ln(x)` are interpolated exactly
+        fn xfx_quadratic(pid: i32, x: f64, _: f64) -> f64 {
+            let y = x.ln();
+
+            x * f64::from(pid + 30) * y.mul_add(0.01_f64.mul_add(y, 0.1), 1.0)
+        }
+
+        let mut fk_table = fk_table();
+        let other = fk_table_with(&OTHER_X_GRID, 100.0);
+        let xfx = Array2::from_shape_fn((PIDS.len(), OTHER_X_GRID.len()), |(i, j)| {
+            xfx_quadratic(PIDS[i], OTHER_X_GRID[j], 100.0)
+        });
+        let reference = other
+            .convolute_tabulated_with_one(2212, &PIDS, xfx.view(), &[], &[])
+            .unwrap();
+
+        assert!(matches!(
+            fk_table.merge(fk_table_with(&OTHER_X_GRID, 100.0), false),
+            Err(MergeFkTableError::DifferentXGrids)
+        ));
+
+        fk_table.merge(other, true).unwrap();
+
+        assert_eq!(fk_table.bins(), 4);
+        assert_eq!(fk_table.x_grid(), X_GRID);
+
+        let results = fk_table
+            .convolute_tabulated_with_one(2212, &PIDS, tabulate(xfx_quadratic).view(), &[2, 3], &[])
+            .unwrap();
+
+        for (result, reference) in results.iter().zip(&reference) {
+            assert_approx_eq!(f64, *result, *reference, epsilon = 1e-12 * reference.abs());
+        }
+
+        assert_eq!(
+            fk_table
+                .merge(fk_table_with(&[0.05, 0.2, 0.5], 100.0), true)
+                .unwrap_err()
+                .to_string(),
+            "x = 0.05 lies outside the x grid of the FK table"
+        );
+    }
+
+    #[test]
+    fn merge_incompatible() {
+        let mut fk_table = fk_table();
+
+        assert_eq!(
+            fk_table
+                .merge(fk_table_with(&X_GRID, 10.0), false)
+                .unwrap_err()
+                .to_string(),
+            "the FK tables have different factorization scales: 100 and 10"
+        );
+
+        let mut other = fk_table_with(&X_GRID, 100.0);
+        other.optimize(FkAssumptions::Nf6Sym);
+
+        assert!(matches!(
+            fk_table.merge(other, false),
+            Err(MergeFkTableError::DifferentMetadata(key)) if key == "fk_assumptions"
+        ));
+    }
+
+    #[test]
+    fn merge_muf2_per_initial_state() {
+        // FK tables without scales per initial state use `muf2` for both
+        let mut fk_table = fk_table();
+        let mut other = fk_table_with(&X_GRID, 100.0);
+        other.grid.set_key_value("fk_muf2_1", "100");
+        other.grid.set_key_value("fk_muf2_2", "100");
+
+        fk_table.merge(other, false).unwrap();
+
+        assert_eq!(fk_table.bins(), 4);
+
+        let mut other = fk_table_with(&X_GRID, 100.0);
+        other.grid.set_key_value("fk_muf2_1", "100");
+        other.grid.set_key_value("fk_muf2_2", "10");
+
+        assert_eq!(
+            fk_table.merge(other, false).unwrap_err().to_string(),
+            "the FK tables have different factorization scales: 100 and 10"
+        );
+    }
+
+    #[test]
+    fn check_assumptions() {
+        let mut fk_table = fk_table();
+        let mut pdf = xfx;
+
+        assert_eq!(fk_table.assumptions(), None);
+        assert!(fk_table
+            .check_assumptions(FkAssumptions::Nf6Ind, &mut pdf)
+            .is_empty());
+
+        // the check doesn't depend on whether the FK table was optimized
+        let unoptimized_checks = fk_table.check_assumptions(FkAssumptions::Nf4Sym, &mut pdf);
+
+        fk_table.optimize(FkAssumptions::Nf4Sym);
+
+        assert_eq!(fk_table.assumptions(), Some(FkAssumptions::Nf4Sym));
+
+        // `xfx` has non-zero heavy-quark PDFs, which are different from their anti-quark PDFs
+        let checks = fk_table.check_assumptions(FkAssumptions::Nf4Sym, &mut pdf);
+
+        assert_eq!(checks.len(), unoptimized_checks.len());
+
+        for (check, unoptimized) in checks.iter().zip(&unoptimized_checks) {
+            assert_eq!(check.condition, unoptimized.condition);
+            assert_approx_eq!(f64, check.violation, unoptimized.violation, ulps = 2);
+        }
+
+        assert_eq!(checks.len(), 3);
+        assert_eq!(checks[0].condition, FkCondition::Zero(5));
+        // the violations are relative to the gluon PDF, which is the largest one
+        assert_eq!(checks[0].condition.to_string(), "b = bbar = 0");
+        assert!(X_GRID.contains(&checks[0].x));
+        assert_approx_eq!(f64, checks[0].violation, 6.0 / 22.0, ulps = 4);
+        assert!(!checks[0].is_satisfied());
+        assert_eq!(checks[1].condition, FkCondition::Zero(6));
+        assert_approx_eq!(f64, checks[1].violation, 7.0 / 22.0, ulps = 4);
+        assert_eq!(checks[2].condition, FkCondition::Symmetric(4));
+        assert_eq!(checks[2].condition.to_string(), "c = cbar");
+        assert_approx_eq!(f64, checks[2].violation, 2.5 / 22.0, ulps = 4);
+
+        let mut symmetric = |pid: i32, x, q2| xfx(pid.abs(), x, q2) * f64::from(pid.abs() <= 4);
+
+        assert!(fk_table
+            .check_assumptions(FkAssumptions::Nf4Sym, &mut symmetric)
+            .iter()
+            .all(|check| check.violation == 0.0));
+
+        // tiny asymmetries and heavy-quark PDFs are attributed to the numerical precision
+        let mut noisy = |pid: i32, x, q2| {
+            symmetric(pid, x, q2) + if pid < 0 { 0.0 } else { 1e-12 * xfx(21, x, q2) }
+        };
+        let checks = fk_table.check_assumptions(FkAssumptions::Nf4Sym, &mut noisy);
+
+        assert!(checks.iter().all(|check| check.violation > 0.0));
+        assert!(checks.iter().all(FkConditionCheck::is_satisfied));
+
+        assert_approx_eq!(f64, FkAssumptions::Nf4Sym.apply(&mut pdf, 6, 0.5, 1.0), 0.0);
+        assert_approx_eq!(
+            f64,
+            FkAssumptions::Nf4Sym.apply(&mut pdf, -4, 0.5, 1.0),
+            0.9375,
+            ulps = 2
+        );
+        assert_approx_eq!(
+            f64,
+            FkAssumptions::Nf4Sym.apply(&mut pdf, 2, 0.5, 1.0),
+            0.75,
+            ulps = 2
+        );
+    }
+
+    #[test]
+    fn detect_assumptions() {
+        let fk_table = fk_table();
+        let mut pdf = xfx;
+        // symmetric up to charm, zero for bottom and top
+        let mut nf4sym = |pid: i32, x, q2| xfx(pid.abs(), x, q2) * f64::from(pid.abs() <= 4);
+        // symmetric up to strange, zero for all heavy quarks
+        let mut nf3sym = |pid: i32, x, q2| xfx(pid.abs(), x, q2) * f64::from(pid.abs() <= 3);
+
+        assert_eq!(
+            fk_table.detect_assumptions(&mut [&mut nf3sym]),
+            FkAssumptions::Nf3Sym
+        );
+        assert_eq!(
+            fk_table.detect_assumptions(&mut [&mut nf3sym, &mut nf4sym]),
+            FkAssumptions::Nf4Sym
+        );
+        assert_eq!(
+            fk_table.detect_assumptions(&mut [&mut nf4sym, &mut pdf]),
+            FkAssumptions::Nf6Ind
+        );
+        assert_eq!(fk_table.detect_assumptions(&mut []), FkAssumptions::Nf3Sym);
+
+        let mut noisy =
+            |pid: i32, x, q2| nf3sym(pid, x, q2) * 1e-12_f64.mul_add(f64::from(pid), 1.0);
+
+        assert_eq!(
+            fk_table.detect_assumptions(&mut [&mut noisy]),
+            FkAssumptions::Nf3Sym
+        );
+    }
 
     #[test]
     fn fk_assumptions_try_from() {
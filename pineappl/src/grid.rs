@@ -1,14 +1,19 @@
 //! Module containing all traits and supporting structures for grids.
 
-use super::bin::{BinInfo, BinLimits, BinRemapper};
+use super::bin::{BinInfo, BinLimits, BinRemapper, MergeBinError};
+use super::convert::f64_from_usize;
 use super::empty_subgrid::EmptySubgridV1;
-use super::evolution::{self, EvolveInfo, OperatorInfo};
+use super::evolution::{
+    self, AlphasTable, BasisRotation, EvolveInfo, FkTableAccumulator, OperatorInfo,
+    OperatorSliceInfo,
+};
 use super::fk_table::FkTable;
 use super::import_only_subgrid::ImportOnlySubgridV2;
 use super::lagrange_subgrid::{LagrangeSparseSubgridV1, LagrangeSubgridV1, LagrangeSubgridV2};
 use super::lumi::{LumiCache, LumiEntry};
 use super::lumi_entry;
 use super::ntuple_subgrid::NtupleSubgridV1;
+use super::observable::{Observables, ObservablesError};
 use super::pids;
 use super::sparse_array3::SparseArray3;
 use super::subgrid::{ExtraSubgridParams, Mu2, Subgrid, SubgridEnum, SubgridParams};
@@ -17,12 +22,13 @@ use git_version::git_version;
 use indicatif::{ProgressBar, ProgressStyle};
 use itertools::Itertools;
 use lz4_flex::frame::{FrameDecoder, FrameEncoder};
-use ndarray::{s, Array3, Array5, ArrayView5, Axis, Dimension};
+use ndarray::{s, Array2, Array3, Array5, ArrayView4, ArrayView5, Axis, CowArray, Dimension, Ix4};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::convert::{TryFrom, TryInto};
+use std::convert::{Infallible, TryFrom, TryInto};
 use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
 use std::iter;
 use std::mem;
@@ -31,6 +37,9 @@ use thiserror::Error;
 
 // TODO: when possible change the types from `u32` to `u8` to change `try_into` to `into`
 
+/// Type of the splitting-function kernels used by [`Grid::add_scale_logs`].
+pub type SplittingKernel = dyn Fn(i32, &[f64]) -> Vec<(i32, Array2<f64>)>;
+
 /// Coupling powers for each grid.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Order {
@@ -42,6 +51,9 @@ pub struct Order {
     pub logxir: u32,
     /// Exponent of the logarithm of the scale factor of the factorization scale.
     pub logxif: u32,
+    /// Exponent of the logarithm of the scale factor of an additional scale, for instance a
+    /// fragmentation, resummation or matching scale.
+    pub logxia: u32,
 }
 
 impl Ord for Order {
@@ -50,10 +62,11 @@ impl Ord for Order {
         // rest lexicographically
         (self.alphas + self.alpha)
             .cmp(&(other.alphas + other.alpha))
-            .then((self.alpha, self.logxir, self.logxif).cmp(&(
+            .then((self.alpha, self.logxir, self.logxif, self.logxia).cmp(&(
                 other.alpha,
                 other.logxir,
                 other.logxif,
+                other.logxia,
             )))
     }
 }
@@ -68,12 +81,13 @@ impl Order {
     /// Constructor. This function mainly exists to have a way of constructing `Order` that is less
     /// verbose.
     #[must_use]
-    pub const fn new(alphas: u32, alpha: u32, logxir: u32, logxif: u32) -> Self {
+    pub const fn new(alphas: u32, alpha: u32, logxir: u32, logxif: u32, logxia: u32) -> Self {
         Self {
             alphas,
             alpha,
             logxir,
             logxif,
+            logxia,
         }
     }
 
@@ -100,12 +114,12 @@ impl Order {
     /// use pineappl::grid::Order;
     ///
     /// let orders = [
-    ///     Order::new(0, 2, 0, 0), //   LO        :          alpha^2
-    ///     Order::new(1, 2, 0, 0), //  NLO QCD    : alphas   alpha^2
-    ///     Order::new(0, 3, 0, 0), //  NLO  EW    :          alpha^3
-    ///     Order::new(2, 2, 0, 0), // NNLO QCD    : alphas^2 alpha^2
-    ///     Order::new(1, 3, 0, 0), // NNLO QCD—EW : alphas   alpha^3
-    ///     Order::new(0, 4, 0, 0), // NNLO EW     :          alpha^4
+    ///     Order::new(0, 2, 0, 0, 0), //   LO        :          alpha^2
+    ///     Order::new(1, 2, 0, 0, 0), //  NLO QCD    : alphas   alpha^2
+    ///     Order::new(0, 3, 0, 0, 0), //  NLO  EW    :          alpha^3
+    ///     Order::new(2, 2, 0, 0, 0), // NNLO QCD    : alphas^2 alpha^2
+    ///     Order::new(1, 3, 0, 0, 0), // NNLO QCD—EW : alphas   alpha^3
+    ///     Order::new(0, 4, 0, 0, 0), // NNLO EW     :          alpha^4
     /// ];
     ///
     /// // LO EW
@@ -131,14 +145,15 @@ impl Order {
     /// use pineappl::grid::Order;
     ///
     /// let orders = [
-    ///     Order::new(0, 2, 0, 0), //  LO         :        alpha^2
-    ///     Order::new(1, 2, 0, 0), //  NLO QCD    : alphas alpha^2
-    ///     Order::new(1, 2, 1, 0), //  NLO QCD    : alphas alpha^2 logxif
-    ///     Order::new(0, 3, 0, 0), //  NLO  EW    :        alpha^3
-    ///     Order::new(0, 3, 1, 0), //  NLO  EW    :        alpha^3 logxif
+    ///     Order::new(0, 2, 0, 0, 0), //  LO         :        alpha^2
+    ///     Order::new(1, 2, 0, 0, 0), //  NLO QCD    : alphas alpha^2
+    ///     Order::new(1, 2, 1, 0, 0), //  NLO QCD    : alphas alpha^2 logxif
+    ///     Order::new(1, 2, 0, 0, 1), //  NLO QCD    : alphas alpha^2 logxia
+    ///     Order::new(0, 3, 0, 0, 0), //  NLO  EW    :        alpha^3
+    ///     Order::new(0, 3, 1, 0, 0), //  NLO  EW    :        alpha^3 logxif
     /// ];
     ///
-    /// assert_eq!(Order::create_mask(&orders, 0, 2), [true, false, false, true, true]);
+    /// assert_eq!(Order::create_mask(&orders, 0, 2), [true, false, false, false, true, true]);
     /// ```
     ///
     /// For the more complicated example of top-pair production one can see the difference between
@@ -148,13 +163,13 @@ impl Order {
     /// use pineappl::grid::Order;
     ///
     /// let orders = [
-    ///     Order::new(2, 0, 0, 0), //   LO QCD    : alphas^2
-    ///     Order::new(1, 1, 0, 0), //   LO QCD—EW : alphas   alpha
-    ///     Order::new(0, 2, 0, 0), //   LO  EW    :          alpha^2
-    ///     Order::new(3, 0, 0, 0), //  NLO QCD    : alphas^3
-    ///     Order::new(2, 1, 0, 0), //  NLO QCD—EW : alphas^2 alpha
-    ///     Order::new(1, 2, 0, 0), //  NLO QCD—EW : alphas   alpha^2
-    ///     Order::new(0, 3, 0, 0), //  NLO EW     :          alpha^3
+    ///     Order::new(2, 0, 0, 0, 0), //   LO QCD    : alphas^2
+    ///     Order::new(1, 1, 0, 0, 0), //   LO QCD—EW : alphas   alpha
+    ///     Order::new(0, 2, 0, 0, 0), //   LO  EW    :          alpha^2
+    ///     Order::new(3, 0, 0, 0, 0), //  NLO QCD    : alphas^3
+    ///     Order::new(2, 1, 0, 0, 0), //  NLO QCD—EW : alphas^2 alpha
+    ///     Order::new(1, 2, 0, 0, 0), //  NLO QCD—EW : alphas   alpha^2
+    ///     Order::new(0, 3, 0, 0, 0), //  NLO EW     :          alpha^3
     /// ];
     ///
     /// // LO EW
@@ -267,6 +282,63 @@ pub enum GridError {
     /// Returned from [`Grid::evolve`] if the evolution failed.
     #[error("failed to evolve grid: {0}")]
     EvolutionFailure(String),
+    /// Returned from [`Grid::add_scale_logs`] if the grid already contains the scale logarithms
+    /// that should be generated.
+    #[error(
+        "the grid already contains scale logarithms for the order O(as^{} a^{} lr^{} lf^{})",
+        .0.alphas,
+        .0.alpha,
+        .0.logxir,
+        .0.logxif
+    )]
+    ScaleLogsPresent(Order),
+    /// Returned from [`Grid::observables`] and [`Grid::set_observables`] if the observables can
+    /// not be parsed or reference bins that do not exist.
+    #[error(transparent)]
+    InvalidObservables(ObservablesError),
+    /// Returned from [`Grid::select_bins`], [`Grid::reorder_bins`] and the tabulated convolutions
+    /// of [`FkTable`] if the bin indices are not valid for this grid.
+    #[error("the bin indices {bin_indices:?} are not a valid selection of the {bins} bins")]
+    InvalidBinSelection {
+        /// Bin indices given.
+        bin_indices: Vec<usize>,
+        /// Number of bins in the grid.
+        bins: usize,
+    },
+    /// Returned from [`Grid::rebin`] if the new bin edges do not coincide with the limits of the
+    /// existing bins.
+    #[error("the bin edges {edges:?} do not align with the bins of the grid")]
+    MisalignedBinEdges {
+        /// Bin edges given.
+        edges: Vec<f64>,
+    },
+    /// Returned from [`Grid::evolve_with_slice_iter`] if an operator slice could not be provided.
+    #[error("failed to obtain the evolution operator: {0}")]
+    OperatorSliceFailure(Box<dyn std::error::Error + Send + Sync>),
+    /// Returned if the particle basis, given by the metadata `lumi_id_types`, is not known.
+    #[error("the particle basis `{0}` is not supported")]
+    UnknownLumiIdTypes(String),
+    /// Returned from [`FkTable::convolute_tabulated_with_one`] and
+    /// [`FkTable::convolute_tabulated_with_two`] if a tabulated PDF doesn't have the expected
+    /// shape.
+    #[error("the tabulated PDF has the shape {actual:?}, but expected is {expected:?}")]
+    TabulatedPdfShapeMismatch {
+        /// Number of rows and columns of the tabulated PDF.
+        actual: (usize, usize),
+        /// Number of PDG ids and `x` values.
+        expected: (usize, usize),
+    },
+    /// Returned from [`FkTable::convolute_tabulated_with_one`] if the PDF of the hadron can not
+    /// be used for an initial state of the [`FkTable`].
+    #[error(
+        "the PDF for the hadron {pdg_id} can not be used for the initial state {initial_state}"
+    )]
+    IncompatibleInitialState {
+        /// PDG id of the hadron the PDF was given for.
+        pdg_id: i32,
+        /// PDG id of the initial state.
+        initial_state: i32,
+    },
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -354,6 +426,54 @@ impl MoreMembers {
     }
 }
 
+/// [`Order`] as stored in files with file format version 0, which lack [`Order::logxia`].
+#[derive(Deserialize, Serialize)]
+struct OrderV0 {
+    alphas: u32,
+    alpha: u32,
+    logxir: u32,
+    logxif: u32,
+}
+
+/// [`Grid`] as stored in files with file format version 0.
+#[derive(Deserialize)]
+struct GridV0 {
+    subgrids: Array3<SubgridEnum>,
+    lumi: Vec<LumiEntry>,
+    bin_limits: BinLimits,
+    orders: Vec<OrderV0>,
+    subgrid_params: SubgridParams,
+    more_members: MoreMembers,
+}
+
+/// Borrowed version of [`GridV0`] used to write files with file format version 0.
+#[derive(Serialize)]
+struct GridV0Ref<'a> {
+    subgrids: &'a Array3<SubgridEnum>,
+    lumi: &'a [LumiEntry],
+    bin_limits: &'a BinLimits,
+    orders: Vec<OrderV0>,
+    subgrid_params: &'a SubgridParams,
+    more_members: &'a MoreMembers,
+}
+
+impl From<GridV0> for Grid {
+    fn from(grid: GridV0) -> Self {
+        Self {
+            subgrids: grid.subgrids,
+            lumi: grid.lumi,
+            bin_limits: grid.bin_limits,
+            orders: grid
+                .orders
+                .into_iter()
+                .map(|order| Order::new(order.alphas, order.alpha, order.logxir, order.logxif, 0))
+                .collect(),
+            subgrid_params: grid.subgrid_params,
+            more_members: grid.more_members,
+        }
+    }
+}
+
 /// Information required to calculate the evolution kernel operators (EKO) to perform a conversion
 /// of a [`Grid`] using [`Grid::convolute_eko`] to an [`FkTable`].
 #[deprecated(since = "0.6.0", note = "use EvolveInfo instead")]
@@ -469,24 +589,26 @@ impl Grid {
         })
     }
 
-    fn pdg_lumi(&self) -> Cow<[LumiEntry]> {
-        if let Some(key_values) = self.key_values() {
-            if let Some(lumi_id_types) = key_values.get("lumi_id_types") {
-                match lumi_id_types.as_str() {
-                    "pdg_mc_ids" => {}
-                    "evol" => {
-                        return self
-                            .lumi
-                            .iter()
-                            .map(|entry| LumiEntry::translate(entry, &pids::evol_to_pdg_mc_ids))
-                            .collect();
-                    }
-                    _ => unimplemented!(),
-                }
-            }
+    fn pdg_lumi(&self) -> Result<Cow<[LumiEntry]>, GridError> {
+        match self.lumi_id_types() {
+            "pdg_mc_ids" => Ok(Cow::Borrowed(self.lumi())),
+            "evol" => Ok(self
+                .lumi
+                .iter()
+                .map(|entry| LumiEntry::translate(entry, &pids::evol_to_pdg_mc_ids))
+                .collect()),
+            lumi_id_types => Err(GridError::UnknownLumiIdTypes(lumi_id_types.to_string())),
         }
+    }
 
-        Cow::Borrowed(self.lumi())
+    /// Returns the particle basis of the luminosity function, which is given by the metadata
+    /// `lumi_id_types`. If it is not set, the basis of PDG Monte Carlo IDs, `pdg_mc_ids`, is
+    /// assumed.
+    #[must_use]
+    pub fn lumi_id_types(&self) -> &str {
+        self.key_values()
+            .and_then(|key_values| key_values.get("lumi_id_types"))
+            .map_or("pdg_mc_ids", String::as_str)
     }
 
     /// Perform a convolution using the PDFs and strong coupling in `lumi_cache`, and only
@@ -496,9 +618,14 @@ impl Grid {
     /// the second the factorization scale. Note that for the variation to be trusted all non-zero
     /// log-grids must be contained.
     ///
+    /// # Errors
+    ///
+    /// Returns an error if the particle basis of this grid, see [`Grid::lumi_id_types`], isn't
+    /// `pdg_mc_ids` or `evol`.
+    ///
     /// # Panics
     ///
-    /// TODO
+    /// Panics if the hadrons of `lumi_cache` are not compatible with this grid.
     pub fn convolute(
         &self,
         lumi_cache: &mut LumiCache,
@@ -506,8 +633,34 @@ impl Grid {
         bin_indices: &[usize],
         lumi_mask: &[bool],
         xi: &[(f64, f64)],
-    ) -> Vec<f64> {
-        lumi_cache.setup(self, xi).unwrap();
+    ) -> Result<Vec<f64>, GridError> {
+        let xi: Vec<_> = xi.iter().map(|&(xir, xif)| (xir, xif, 1.0)).collect();
+
+        self.convolute_with_xia(lumi_cache, order_mask, bin_indices, lumi_mask, &xi)
+    }
+
+    /// Perform a convolution like [`Grid::convolute`], but additionally vary the scale whose
+    /// logarithms are given by [`Order::logxia`] with the third factor of each tuple in `xi`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the particle basis of this grid, see [`Grid::lumi_id_types`], isn't
+    /// `pdg_mc_ids` or `evol`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the hadrons of `lumi_cache` are not compatible with this grid.
+    pub fn convolute_with_xia(
+        &self,
+        lumi_cache: &mut LumiCache,
+        order_mask: &[bool],
+        bin_indices: &[usize],
+        lumi_mask: &[bool],
+        xi: &[(f64, f64, f64)],
+    ) -> Result<Vec<f64>, GridError> {
+        let self_lumi = self.pdg_lumi()?;
+        let xi_rf: Vec<_> = xi.iter().map(|&(xir, xif, _)| (xir, xif)).collect();
+        lumi_cache.setup(self, &xi_rf).unwrap();
 
         let bin_indices = if bin_indices.is_empty() {
             (0..self.bin_info().bins()).collect()
@@ -516,13 +669,15 @@ impl Grid {
         };
         let mut bins = vec![0.0; bin_indices.len() * xi.len()];
         let normalizations = self.bin_info().normalizations();
-        let self_lumi = self.pdg_lumi();
 
-        for (xi_index, &(xir, xif)) in xi.iter().enumerate() {
+        for (xi_index, &(xir, xif, xia)) in xi.iter().enumerate() {
             for ((ord, bin, lumi), subgrid) in self.subgrids.indexed_iter() {
                 let order = &self.orders[ord];
 
-                if ((order.logxir > 0) && (xir == 1.0)) || ((order.logxif > 0) && (xif == 1.0)) {
+                if ((order.logxir > 0) && (xir == 1.0))
+                    || ((order.logxif > 0) && (xif == 1.0))
+                    || ((order.logxia > 0) && (xia == 1.0))
+                {
                     continue;
                 }
 
@@ -592,7 +747,11 @@ impl Grid {
                     //}
                     //value *= factor
                 }
-                
+
+                if order.logxia > 0 {
+                    value *= (xia * xia).ln().powi(order.logxia.try_into().unwrap());
+                }
+
                 // SZ
                 //if self.key_values().unwrap().contains_key("with_power") {
                 //    value = value.powi(self.key_values().unwrap().get("with_power").unwrap().parse::<i32>().unwrap());
@@ -601,7 +760,7 @@ impl Grid {
             }
         }
 
-        bins
+        Ok(bins)
     }
 
     /// Convolutes a single subgrid `(order, bin, lumi)` with the PDFs strong coupling given by
@@ -609,9 +768,14 @@ impl Grid {
     /// axes of the result correspond to the values given by the subgrid `q2`, `x1` and `x2` grid
     /// values.
     ///
+    /// # Errors
+    ///
+    /// Returns an error if the particle basis of this grid, see [`Grid::lumi_id_types`], isn't
+    /// `pdg_mc_ids` or `evol`.
+    ///
     /// # Panics
     ///
-    /// TODO
+    /// Panics if the hadrons of `lumi_cache` are not compatible with this grid.
     pub fn convolute_subgrid(
         &self,
         lumi_cache: &mut LumiCache,
@@ -620,11 +784,11 @@ impl Grid {
         lumi: usize,
         xir: f64,
         xif: f64,
-    ) -> Array3<f64> {
+    ) -> Result<Array3<f64>, GridError> {
+        let self_lumi = self.pdg_lumi()?;
         lumi_cache.setup(self, &[(xir, xif)]).unwrap();
 
         let normalizations = self.bin_info().normalizations();
-        let self_lumi = self.pdg_lumi();
 
         let subgrid = &self.subgrids[[ord, bin, lumi]];
         let order = &self.orders[ord];
@@ -664,8 +828,14 @@ impl Grid {
             array *= (xif * xif).ln().powi(order.logxif.try_into().unwrap());
         }
 
+        // the scale belonging to `logxia` isn't varied, so these logarithms vanish
+        if order.logxia > 0 {
+            array.fill(0.0);
+        }
+
         array /= normalizations[bin];
-        array
+
+        Ok(array)
     }
 
     /// Fills the grid with an ntuple for the given `order`, `observable`, and `lumi`.
@@ -675,17 +845,50 @@ impl Grid {
     /// TODO
     pub fn fill(&mut self, order: usize, observable: f64, lumi: usize, ntuple: &Ntuple<f64>) {
         if let Some(bin) = self.bin_limits.index(observable) {
-            let subgrid = &mut self.subgrids[[order, bin, lumi]];
-            if let SubgridEnum::EmptySubgridV1(_) = subgrid {
-                if let MoreMembers::V3(mmv3) = &self.more_members {
-                    *subgrid = mmv3.subgrid_template.clone_empty();
-                } else {
-                    unreachable!();
-                }
-            }
+            self.fill_bin(order, bin, lumi, ntuple);
+        }
+    }
+
+    /// Fills the grid with an ntuple for the given `order`, `lumi`, and the multi-dimensional
+    /// observable `observables`, which has one entry for each dimension of the bins. The bins
+    /// filled are determined from the limits of the remapper, see [`BinRemapper::indices`], or,
+    /// if the grid has no remapper, from the one-dimensional bin limits. If bins overlap, every
+    /// bin containing the observables is filled; if no bin contains them, the grid isn't changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `observables` is not the same as the number of dimensions of the
+    /// bins.
+    pub fn fill_nd(
+        &mut self,
+        order: usize,
+        observables: &[f64],
+        lumi: usize,
+        ntuple: &Ntuple<f64>,
+    ) {
+        let bins = if let Some(remapper) = self.remapper() {
+            remapper.indices(observables)
+        } else {
+            assert_eq!(observables.len(), 1);
+            self.bin_limits.index(observables[0]).into_iter().collect()
+        };
+
+        for bin in bins {
+            self.fill_bin(order, bin, lumi, ntuple);
+        }
+    }
 
-            subgrid.fill(ntuple);
+    fn fill_bin(&mut self, order: usize, bin: usize, lumi: usize, ntuple: &Ntuple<f64>) {
+        let subgrid = &mut self.subgrids[[order, bin, lumi]];
+        if let SubgridEnum::EmptySubgridV1(_) = subgrid {
+            if let MoreMembers::V3(mmv3) = &self.more_members {
+                *subgrid = mmv3.subgrid_template.clone_empty();
+            } else {
+                unreachable!();
+            }
         }
+
+        subgrid.fill(ntuple);
     }
 
     /// Construct a `Grid` by deserializing it from `reader`. Reading is buffered.
@@ -721,30 +924,59 @@ impl Grid {
             0
         };
 
-        if file_version != 0 {
-            return Err(GridError::FileVersionMismatch {
+        match file_version {
+            0 => bincode::deserialize_from::<_, GridV0>(reader)
+                .map(Into::into)
+                .map_err(GridError::ReadFailure),
+            1 => bincode::deserialize_from(reader).map_err(GridError::ReadFailure),
+            _ => Err(GridError::FileVersionMismatch {
                 file_version,
-                supported_version: 0,
-            });
+                supported_version: 1,
+            }),
         }
-
-        bincode::deserialize_from(reader).map_err(GridError::ReadFailure)
     }
 
-    /// Serializes `self` into `writer`. Writing is buffered.
+    /// Serializes `self` into `writer`. Writing is buffered. The file format version written is
+    /// the oldest one that is able to represent `self`, which is version 0 unless there are orders
+    /// with non-zero [`Order::logxia`].
     ///
     /// # Errors
     ///
     /// If writing fails an error is returned.
     pub fn write(&self, writer: impl Write) -> Result<(), GridError> {
         let mut writer = BufWriter::new(writer);
-        let file_header = b"PineAPPL\0\0\0\0\0\0\0\0";
+        let file_version = u64::from(self.orders.iter().any(|order| order.logxia > 0));
 
         // first write PineAPPL file header
-        writer.write(file_header).map_err(GridError::IoFailure)?;
+        writer.write(b"PineAPPL").map_err(GridError::IoFailure)?;
+        writer
+            .write(&file_version.to_le_bytes())
+            .map_err(GridError::IoFailure)?;
 
         // then serialize
-        bincode::serialize_into(writer, self).map_err(GridError::WriteFailure)
+        if file_version == 0 {
+            let grid = GridV0Ref {
+                subgrids: &self.subgrids,
+                lumi: &self.lumi,
+                bin_limits: &self.bin_limits,
+                orders: self
+                    .orders
+                    .iter()
+                    .map(|order| OrderV0 {
+                        alphas: order.alphas,
+                        alpha: order.alpha,
+                        logxir: order.logxir,
+                        logxif: order.logxif,
+                    })
+                    .collect(),
+                subgrid_params: &self.subgrid_params,
+                more_members: &self.more_members,
+            };
+
+            bincode::serialize_into(writer, &grid).map_err(GridError::WriteFailure)
+        } else {
+            bincode::serialize_into(writer, self).map_err(GridError::WriteFailure)
+        }
     }
 
     /// Serializes `self` into `writer`, using LZ4 compression. Writing is buffered.
@@ -796,22 +1028,24 @@ impl Grid {
         &self.lumi
     }
 
-    /// Merges the bins for the corresponding range together in a single one.
+    /// Merges the bins for the corresponding range together in a single one. Observables that
+    /// reference any of the merged bins are removed, see [`Grid::observables`].
     ///
     /// # Errors
     ///
-    /// When the given bins are non-consecutive, an error is returned.
+    /// When the given bins are non-consecutive or the range is empty, an error is returned.
     pub fn merge_bins(&mut self, bins: Range<usize>) -> Result<(), GridError> {
-        self.bin_limits
-            .merge_bins(bins.clone())
-            .map_err(GridError::MergeBinError)?;
-
+        // the remapper performs the stricter checks, so it must be modified first
         if let Some(remapper) = self.remapper_mut() {
             remapper
                 .merge_bins(bins.clone())
                 .map_err(GridError::MergeBinError)?;
         }
 
+        self.bin_limits
+            .merge_bins(bins.clone())
+            .map_err(GridError::MergeBinError)?;
+
         let bin_count = self.bin_info().bins();
         let mut old_subgrids = mem::replace(
             &mut self.subgrids,
@@ -844,6 +1078,18 @@ impl Grid {
             }
         }
 
+        if bins.len() > 1 {
+            self.remap_observables(|bin| {
+                if bins.contains(&bin) {
+                    None
+                } else if bin >= bins.end {
+                    Some(bin - bins.len() + 1)
+                } else {
+                    Some(bin)
+                }
+            });
+        }
+
         Ok(())
     }
 
@@ -857,38 +1103,59 @@ impl Grid {
     /// # Panics
     ///
     /// TODO
-    pub fn merge(&mut self, mut other: Self) -> Result<(), GridError> {
+    pub fn merge(&mut self, other: Self) -> Result<(), GridError> {
+        let append_bins = self.bin_info() != other.bin_info();
+
+        self.merge_impl(other, append_bins)
+    }
+
+    /// Merges `other` into `self` like [`Grid::merge`], but the bins of `other` are always
+    /// appended to the ones of `self`, even if they are the same.
+    pub(crate) fn append(&mut self, other: Self) -> Result<(), GridError> {
+        self.merge_impl(other, true)
+    }
+
+    fn merge_impl(&mut self, mut other: Self, append_bins: bool) -> Result<(), GridError> {
         let mut new_orders: Vec<Order> = Vec::new();
         let mut new_bins = 0;
         let mut new_entries: Vec<LumiEntry> = Vec::new();
 
-        if self.bin_info() != other.bin_info() {
+        if append_bins {
+            let lhs_dim = self.bin_info().dimensions();
+            let rhs_dim = other.bin_info().dimensions();
+
+            // check before `self` is modified
+            if lhs_dim != rhs_dim {
+                return Err(GridError::MergeBinError(
+                    MergeBinError::IncompatibleDimensions {
+                        lhs: lhs_dim,
+                        rhs: rhs_dim,
+                    },
+                ));
+            }
+
             let lhs_bins = self.bin_info().bins();
             new_bins = other.bin_info().bins();
 
-            let lhs_remapper = self.remapper_mut();
-            let rhs_remapper = other.remapper();
+            // one-dimensional bins are kept as they are, if they can be joined
+            if self.remapper().is_some()
+                || other.remapper().is_some()
+                || self.bin_limits.merge(&other.bin_limits).is_err()
+            {
+                // otherwise the bins of `other` are added as a list of arbitrary bins
+                self.convert_bin_limits_to_remapper();
+                other.convert_bin_limits_to_remapper();
 
-            if let Some(lhs) = lhs_remapper {
-                if let Some(rhs) = rhs_remapper {
-                    lhs.merge(rhs).map_err(GridError::MergeBinError)?;
+                let lhs = self.remapper_mut().unwrap_or_else(|| unreachable!());
+                let rhs = other.remapper().unwrap_or_else(|| unreachable!());
 
-                    let a = u32::try_from(lhs_bins).unwrap_or_else(|_| unreachable!());
-                    let b = u32::try_from(lhs_bins + new_bins).unwrap_or_else(|_| unreachable!());
+                lhs.merge(rhs).map_err(GridError::MergeBinError)?;
 
-                    self.bin_limits = BinLimits::new((0..=b).map(f64::from).collect());
-                    other.bin_limits = BinLimits::new((a..=b).map(f64::from).collect());
-                } else {
-                    // Return an error
-                    todo!();
-                }
-            } else if rhs_remapper.is_none() {
-                self.bin_limits
-                    .merge(&other.bin_limits)
-                    .map_err(GridError::InvalidBinLimits)?;
-            } else {
-                // Return an error
-                todo!();
+                let a = u32::try_from(lhs_bins).unwrap_or_else(|_| unreachable!());
+                let b = u32::try_from(lhs_bins + new_bins).unwrap_or_else(|_| unreachable!());
+
+                self.bin_limits = BinLimits::new((0..=b).map(f64::from).collect());
+                other.bin_limits = BinLimits::new((a..=b).map(f64::from).collect());
             }
         }
 
@@ -926,13 +1193,12 @@ impl Grid {
         self.orders.append(&mut new_orders);
         self.lumi.append(&mut new_entries);
 
-        let bin_indices: Vec<_> = (0..other.bin_info().bins())
-            .map(|bin| {
-                self.bin_info()
-                    .find_bin(&other.bin_info().bin_limits(bin))
-                    .expect(&format!("failed for {}", bin))
-            })
-            .collect();
+        // the bins of `other` are either the same as the ones of `self` or appended to them
+        let bin_offset = if new_bins == 0 {
+            0
+        } else {
+            self.bin_info().bins() - new_bins
+        };
 
         for ((i, j, k), subgrid) in other
             .subgrids
@@ -943,7 +1209,7 @@ impl Grid {
             let other_entry = &other.lumi[k];
 
             let self_i = self.orders.iter().position(|x| x == other_order).unwrap();
-            let self_j = bin_indices[j];
+            let self_j = bin_offset + j;
             let self_k = self.lumi.iter().position(|y| y == other_entry).unwrap();
 
             if self.subgrids[[self_i, self_j, self_k]].is_empty() {
@@ -1019,6 +1285,216 @@ impl Grid {
         }
     }
 
+    /// Adds the renormalization and factorization scale logarithms, which are fixed by the
+    /// renormalization group equations, to this grid by constructing the orders with non-zero
+    /// `logxir` and `logxif` from the orders with lower powers of the strong coupling. Only orders
+    /// up to the highest power of the strong coupling already present, separately for each power
+    /// of `alpha`, are generated.
+    ///
+    /// The parameter `beta` contains the coefficients of the beta function, normalized such that
+    /// the derivative of the strong coupling with respect to the logarithm of the squared
+    /// renormalization scale is `-sum_j beta[j] * alphas^(j + 2)`; if it is empty no
+    /// renormalization scale logarithms are generated. The entry `kernels[j]` evaluates the
+    /// splitting functions multiplying `alphas^(j + 1)`, where the strong coupling is evaluated at
+    /// the factorization scale and its running, given by `beta`, generates additional
+    /// factorization scale logarithms. Called with a parton id `a` and a grid of
+    /// `x` values, it must return, for each parton id `b` with a non-zero splitting into `a`, the
+    /// matrix `m` such that the derivative of the PDF of `a` at the `i`-th point of the grid with
+    /// respect to the logarithm of the squared factorization scale is the sum over `k` of
+    /// `m[[i, k]]` times the PDF of `b` at the `k`-th point. Parton ids must use the same
+    /// convention as the luminosity function of the grid. If `kernels` is empty, no factorization
+    /// scale logarithms are generated. The factorization scale logarithms are stored using new
+    /// luminosity entries, which can be simplified using [`Grid::optimize`].
+    ///
+    /// # Errors
+    ///
+    /// If the grid already contains non-empty subgrids with the logarithms that should be
+    /// generated, an error is returned. Logarithms that aren't generated, for instance the ones of
+    /// orders with the lowest power of the strong coupling, may be present.
+    ///
+    /// # Panics
+    ///
+    /// TODO
+    pub fn add_scale_logs(
+        &mut self,
+        beta: &[f64],
+        kernels: &[&SplittingKernel],
+    ) -> Result<(), GridError> {
+        let alphas_range = self.alphas_range();
+
+        // logarithms that aren't generated, for instance the ones of QED, may be present
+        for ((ord, _, _), subgrid) in self.subgrids.indexed_iter() {
+            let order = &self.orders[ord];
+            let generated = alphas_range.get(&order.alpha).map_or(false, |&(min, max)| {
+                (order.alphas > min) && (order.alphas <= max)
+            });
+
+            if !subgrid.is_empty()
+                && generated
+                && ((!beta.is_empty() && order.logxir > 0)
+                    || (!kernels.is_empty() && order.logxif > 0))
+            {
+                return Err(GridError::ScaleLogsPresent(order.clone()));
+            }
+        }
+
+        let max_alphas: HashMap<u32, u32> = alphas_range
+            .iter()
+            .map(|(&alpha, &(_, max))| (alpha, max))
+            .collect();
+
+        let min_alphas = self.orders.iter().map(|order| order.alphas).min();
+        let max_alphas_all = max_alphas.values().copied().max();
+
+        let (min_alphas, max_alphas_all) = match (min_alphas, max_alphas_all) {
+            (Some(min), Some(max)) => (min, max),
+            _ => return Ok(()),
+        };
+
+        let has_pdf1 = self.has_pdf1();
+        let has_pdf2 = self.has_pdf2();
+
+        // every contribution has a higher power of the strong coupling than its source, so going
+        // through the powers in ascending order makes sure all sources are complete
+        for alphas in (min_alphas + 1)..=max_alphas_all {
+            let mut contributions: Vec<(Order, usize, LumiEntry, SubgridEnum)> = Vec::new();
+
+            for ((ord, bin, lumi), subgrid) in self.subgrids.indexed_iter() {
+                let order = &self.orders[ord];
+
+                if subgrid.is_empty()
+                    || (order.alphas >= alphas)
+                    || max_alphas
+                        .get(&order.alpha)
+                        .map_or(true, |&max| alphas > max)
+                {
+                    continue;
+                }
+
+                // index of the beta-function coefficient or splitting-function kernel
+                let index = usize::try_from(alphas - order.alphas - 1).unwrap();
+
+                if let Some(&coefficient) = beta.get(index) {
+                    if order.alphas > 0 {
+                        let mut new_subgrid: SubgridEnum =
+                            ImportOnlySubgridV2::from(subgrid).into();
+                        new_subgrid.scale(
+                            f64::from(order.alphas) * coefficient / f64::from(order.logxir + 1),
+                        );
+
+                        contributions.push((
+                            Order::new(
+                                alphas,
+                                order.alpha,
+                                order.logxir + 1,
+                                order.logxif,
+                                order.logxia,
+                            ),
+                            bin,
+                            self.lumi[lumi].clone(),
+                            new_subgrid,
+                        ));
+                    }
+                }
+
+                // the renormalization group equation above generates all terms with
+                // renormalization scale logarithms, including the ones multiplying factorization
+                // scale logarithms
+                if order.logxir > 0 {
+                    continue;
+                }
+
+                // the splitting functions multiply powers of the strong coupling evaluated at the
+                // factorization scale, whose expansion in the strong coupling evaluated at the
+                // renormalization scale generates additional factorization scale logarithms
+                for (j, kernel) in kernels.iter().enumerate().take(index + 1) {
+                    let ratio = coupling_ratio(beta, j + 1, index - j);
+
+                    for (m, &coefficient) in ratio[index - j].iter().enumerate() {
+                        if coefficient == 0.0 {
+                            continue;
+                        }
+
+                        let logxif = order.logxif + u32::try_from(m).unwrap() + 1;
+                        let new_order = Order::new(alphas, order.alpha, 0, logxif, order.logxia);
+
+                        contributions.extend(
+                            apply_kernel_to_entry(
+                                subgrid,
+                                &self.lumi[lumi],
+                                *kernel,
+                                [has_pdf1, has_pdf2],
+                                -coefficient / f64::from(logxif),
+                            )
+                            .into_iter()
+                            .map(|(entry, subgrid)| (new_order.clone(), bin, entry, subgrid)),
+                        );
+                    }
+                }
+            }
+
+            for (order, bin, entry, subgrid) in contributions {
+                self.add_subgrid(order, bin, entry, subgrid);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the lowest and highest power of the strong coupling of the non-empty orders without
+    /// scale logarithms, for each power of `alpha`.
+    fn alphas_range(&self) -> HashMap<u32, (u32, u32)> {
+        let mut alphas_range: HashMap<u32, (u32, u32)> = HashMap::new();
+
+        for ((ord, _, _), subgrid) in self.subgrids.indexed_iter() {
+            let order = &self.orders[ord];
+
+            if !subgrid.is_empty() && (order.logxir == 0) && (order.logxif == 0) {
+                let (min, max) = alphas_range
+                    .entry(order.alpha)
+                    .or_insert((order.alphas, order.alphas));
+                *min = (*min).min(order.alphas);
+                *max = (*max).max(order.alphas);
+            }
+        }
+
+        alphas_range
+    }
+
+    fn add_subgrid(
+        &mut self,
+        order: Order,
+        bin: usize,
+        entry: LumiEntry,
+        mut subgrid: SubgridEnum,
+    ) {
+        if subgrid.is_empty() {
+            return;
+        }
+
+        let ord = self.orders.iter().position(|o| *o == order);
+        let lumi = self.lumi.iter().position(|l| *l == entry);
+
+        self.increase_shape(&(usize::from(ord.is_none()), 0, usize::from(lumi.is_none())));
+
+        let ord = ord.unwrap_or_else(|| {
+            self.orders.push(order);
+            self.orders.len() - 1
+        });
+        let lumi = lumi.unwrap_or_else(|| {
+            self.lumi.push(entry);
+            self.lumi.len() - 1
+        });
+
+        let target = &mut self.subgrids[[ord, bin, lumi]];
+
+        if target.is_empty() {
+            *target = subgrid;
+        } else {
+            target.merge(&mut subgrid, false);
+        }
+    }
+
     /// Returns the subgrid parameters.
     #[must_use]
     pub fn orders(&self) -> &[Order] {
@@ -1030,6 +1506,60 @@ impl Grid {
         self.lumi = lumis;
     }
 
+    /// Rotates the luminosity function of this grid into the particle basis of `rotation` and
+    /// sets the metadata `lumi_id_types` accordingly. Luminosities that become the same are merged
+    /// and luminosities that become zero are removed. Particles that are not part of the
+    /// rotation, for instance leptons, are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the particle basis of this grid, see [`Grid::lumi_id_types`], isn't `pdg_mc_ids`,
+    /// `evol` or the basis of `rotation`, an error is returned.
+    pub fn rotate_basis(&mut self, rotation: &BasisRotation) -> Result<(), GridError> {
+        let lumi_id_types = self.lumi_id_types().to_string();
+        let mut translations = HashMap::new();
+
+        for &(a, b, _) in self.lumi.iter().flat_map(LumiEntry::entry) {
+            for pid in [a, b] {
+                if let Entry::Vacant(entry) = translations.entry(pid) {
+                    entry.insert(rotation.translate(&lumi_id_types, pid)?);
+                }
+            }
+        }
+
+        self.lumi = self
+            .lumi
+            .iter()
+            .map(|entry| {
+                let entry = LumiEntry::translate(entry, &|pid| translations[&pid].clone());
+                let max = entry
+                    .entry()
+                    .iter()
+                    .map(|(_, _, factor)| factor.abs())
+                    .fold(0.0, f64::max);
+                // remove factors that vanish up to rounding errors
+                let tuples: Vec<_> = entry
+                    .entry()
+                    .iter()
+                    .copied()
+                    .filter(|(_, _, factor)| factor.abs() > 1e-14 * max)
+                    .collect();
+
+                if tuples.is_empty() {
+                    // keep an entry with a vanishing factor, which is removed below
+                    let (a, b, _) = entry.entry()[0];
+                    LumiEntry::new(vec![(a, b, 0.0)])
+                } else {
+                    LumiEntry::new(tuples)
+                }
+            })
+            .collect();
+        self.set_key_value("lumi_id_types", rotation.lumi_id_types());
+        self.optimize_lumi();
+
+        Ok(())
+    }
+
     /// Returns the subgrid with the specified indices `order`, `bin`, and `lumi`.
     #[must_use]
     pub fn subgrid(&self, order: usize, bin: usize, lumi: usize) -> &SubgridEnum {
@@ -1098,6 +1628,23 @@ impl Grid {
         }
     }
 
+    /// If this grid doesn't have a remapper, store the one-dimensional bin limits in a new one,
+    /// which in contrast to [`BinLimits`] supports non-consecutive and overlapping bins.
+    fn convert_bin_limits_to_remapper(&mut self) {
+        if self.remapper().is_none() {
+            let limits = self.bin_limits.limits();
+
+            self.set_remapper(
+                BinRemapper::new(
+                    self.bin_limits.bin_sizes(),
+                    limits.windows(2).map(|win| (win[0], win[1])).collect(),
+                )
+                .unwrap_or_else(|_| unreachable!()),
+            )
+            .unwrap_or_else(|_| unreachable!());
+        }
+    }
+
     /// Returns all information about the bins in this grid.
     #[must_use]
     pub const fn bin_info(&self) -> BinInfo {
@@ -1304,6 +1851,63 @@ impl Grid {
             .insert(key.to_owned(), value.to_owned());
     }
 
+    /// Returns the observables defined for this grid, which are stored as the value of the key
+    /// `observables`. See [`Observables`] for their format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::InvalidObservables`] if the stored value can not be parsed or if it
+    /// references bins that do not exist.
+    pub fn observables(&self) -> Result<Option<Observables>, GridError> {
+        self.key_values()
+            .and_then(|kv| kv.get("observables"))
+            .map(|value| {
+                let observables: Observables =
+                    value.parse().map_err(GridError::InvalidObservables)?;
+                observables
+                    .validate(self.bin_info().bins())
+                    .map_err(GridError::InvalidObservables)?;
+                Ok(observables)
+            })
+            .transpose()
+    }
+
+    /// Sets the observables of this grid, which combine its bins into, for instance, ratios or
+    /// normalized distributions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::InvalidObservables`] if the `observables` reference bins that do not
+    /// exist.
+    pub fn set_observables(&mut self, observables: &Observables) -> Result<(), GridError> {
+        observables
+            .validate(self.bin_info().bins())
+            .map_err(GridError::InvalidObservables)?;
+        self.set_key_value("observables", &observables.to_string());
+
+        Ok(())
+    }
+
+    /// Replaces the bin indices of the observables with the values returned from `map`, which
+    /// returns `None` for bins that no longer exist. Observables that reference such a bin are
+    /// removed and if none are left, the key `observables` is deleted.
+    fn remap_observables(&mut self, map: impl Fn(usize) -> Option<usize>) {
+        // observables that can not be parsed are left untouched
+        if let Some(observables) = self
+            .key_values()
+            .and_then(|kv| kv.get("observables"))
+            .and_then(|value| value.parse::<Observables>().ok())
+        {
+            let observables = observables.remap_bins(map);
+
+            if observables.is_empty() {
+                self.key_values_mut().remove("observables");
+            } else {
+                self.set_key_value("observables", &observables.to_string());
+            }
+        }
+    }
+
     /// Provide information used to compute a suitable EKO for the current grid.
     /// More specific, the `x_grid` and `muf2_grid` are extracted and checked.
     ///
@@ -1477,6 +2081,7 @@ impl Grid {
                 alpha: 0,
                 logxir: 0,
                 logxif: 0,
+                logxia: 0,
             }],
             subgrid_params: SubgridParams::default(),
             more_members: self.more_members.clone(),
@@ -1533,7 +2138,7 @@ impl Grid {
                 // iterate over the source grid orders and add all of them together into
                 // `src_array`, using the right powers of alphas
                 for (order, powers) in self.orders.iter().enumerate() {
-                    if !order_mask.is_empty() && !order_mask[order] {
+                    if (!order_mask.is_empty() && !order_mask[order]) || (powers.logxia > 0) {
                         continue;
                     }
 
@@ -1879,29 +2484,202 @@ impl Grid {
             )));
         }
 
-        let (subgrids, lumi) = if self.has_pdf1() && self.has_pdf2() {
-            evolution::evolve_with_two(self, &operator, info, order_mask)
-        } else {
-            evolution::evolve_with_one(self, &operator, info, order_mask)
-        }?;
-
-        let mut grid = Self {
-            subgrids,
+        self.evolve_with_slice_iter(
+            info.fac1
+                .iter()
+                .zip(operator.axis_iter(Axis(0)))
+                .map(|(&fac1, operator)| {
+                    Ok::<_, Infallible>((
+                        OperatorSliceInfo {
+                            fac0: info.fac0,
+                            pids0: info.pids0.clone(),
+                            x0: info.x0.clone(),
+                            fac1,
+                            pids1: info.pids1.clone(),
+                            x1: info.x1.clone(),
+                            lumi_id_types: info.lumi_id_types.clone(),
+                        },
+                        CowArray::from(operator),
+                    ))
+                }),
+            order_mask,
+            (info.xir, info.xif),
+            &AlphasTable {
+                ren1: info.ren1.clone(),
+                alphas: info.alphas.clone(),
+            },
+        )
+    }
+
+    /// Converts this `Grid` into an [`FkTable`] using an evolution kernel operator (EKO) that is
+    /// given one squared factorization scale at a time by the iterator `slices`. Each item of
+    /// `slices` contains an operator slice and the information describing it, and the operator
+    /// slices are only kept in memory while they are processed. The iterator must yield an
+    /// operator for each squared factorization scale of this `Grid` multiplied with the square of
+    /// `xi.1`; slices for other scales are ignored. The strong couplings for the renormalization
+    /// scales of this `Grid`, multiplied with the square of `xi.0`, must be given in
+    /// `alphas_table`. The parameter `order_mask` has the same meaning as for [`Grid::evolve`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GridError::OperatorSliceFailure`] if `slices` returns an error, and a
+    /// [`GridError::EvolutionFailure`] if the operator slices are incompatible with this `Grid` or
+    /// among themselves.
+    ///
+    /// # Panics
+    ///
+    /// TODO
+    pub fn evolve_with_slice_iter<'a, E: Into<Box<dyn std::error::Error + Send + Sync>>>(
+        &self,
+        slices: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
+        order_mask: &[bool],
+        xi: (f64, f64),
+        alphas_table: &AlphasTable,
+    ) -> Result<FkTable, GridError> {
+        let mut accumulator = FkTableAccumulator::new();
+
+        for result in slices {
+            let (info, operator) =
+                result.map_err(|err| GridError::OperatorSliceFailure(err.into()))?;
+            let operator = operator.view();
+
+            self.evolve_slice(
+                [(&info, &operator), (&info, &operator)],
+                order_mask,
+                xi,
+                alphas_table,
+                &mut accumulator,
+            )?;
+        }
+
+        self.fk_table_from_accumulator(accumulator, order_mask, xi)
+    }
+
+    /// Converts this `Grid` into an [`FkTable`] like [`Grid::evolve_with_slice_iter`], but with a
+    /// separate evolution kernel operator for each initial state, given by `slices_a` and
+    /// `slices_b` for the first and second initial state, respectively. Both iterators must yield
+    /// their slices for the same squared factorization scales in the same order, and the slices
+    /// must use the same particle basis, but they may differ in their particle identifiers,
+    /// `x`-grid coordinates and fitting scales. The resulting `FkTable` has a separate `x` grid
+    /// for each initial state. The fitting scales of both initial states are returned by
+    /// [`FkTable::muf2_per_initial_state`]; if they are different, the one of the first convolved
+    /// initial state is stored as [`FkTable::muf2`]. For grids with a single convolution the
+    /// operator slices of the other initial state are not used.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GridError::OperatorSliceFailure`] if `slices_a` or `slices_b` returns an error,
+    /// and a [`GridError::EvolutionFailure`] if the operator slices are incompatible with this
+    /// `Grid` or among themselves, or if both iterators yield a different number of slices.
+    pub fn evolve_with_slice_iter2<'a, E: Into<Box<dyn std::error::Error + Send + Sync>>>(
+        &self,
+        slices_a: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
+        slices_b: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
+        order_mask: &[bool],
+        xi: (f64, f64),
+        alphas_table: &AlphasTable,
+    ) -> Result<FkTable, GridError> {
+        let mut accumulator = FkTableAccumulator::new();
+
+        for slices in slices_a.into_iter().zip_longest(slices_b) {
+            let (result_a, result_b) = slices.both().ok_or_else(|| {
+                GridError::EvolutionFailure(
+                    "the number of operator slices for both initial states is different"
+                        .to_string(),
+                )
+            })?;
+            let (info_a, operator_a) =
+                result_a.map_err(|err| GridError::OperatorSliceFailure(err.into()))?;
+            let (info_b, operator_b) =
+                result_b.map_err(|err| GridError::OperatorSliceFailure(err.into()))?;
+
+            self.evolve_slice(
+                [(&info_a, &operator_a.view()), (&info_b, &operator_b.view())],
+                order_mask,
+                xi,
+                alphas_table,
+                &mut accumulator,
+            )?;
+        }
+
+        self.fk_table_from_accumulator(accumulator, order_mask, xi)
+    }
+
+    fn evolve_slice(
+        &self,
+        slices: [(&OperatorSliceInfo, &ArrayView4<f64>); 2],
+        order_mask: &[bool],
+        xi: (f64, f64),
+        alphas_table: &AlphasTable,
+        accumulator: &mut FkTableAccumulator,
+    ) -> Result<(), GridError> {
+        for (info, operator) in slices {
+            let op_info_dim = (
+                info.pids1.len(),
+                info.x1.len(),
+                info.pids0.len(),
+                info.x0.len(),
+            );
+
+            if operator.dim() != op_info_dim {
+                return Err(GridError::EvolutionFailure(format!(
+                    "operator information {:?} does not match the operator's dimensions: {:?}",
+                    op_info_dim,
+                    operator.dim(),
+                )));
+            }
+        }
+
+        if self.has_pdf1() && self.has_pdf2() {
+            evolution::evolve_slice_with_two(
+                self,
+                slices,
+                order_mask,
+                xi,
+                alphas_table,
+                accumulator,
+            )
+        } else {
+            evolution::evolve_slice_with_one(
+                self,
+                slices,
+                order_mask,
+                xi,
+                alphas_table,
+                accumulator,
+            )
+        }
+    }
+
+    fn fk_table_from_accumulator(
+        &self,
+        accumulator: FkTableAccumulator,
+        order_mask: &[bool],
+        xi: (f64, f64),
+    ) -> Result<FkTable, GridError> {
+        let (subgrids, lumi, lumi_id_types, [fac0_a, fac0_b]) =
+            accumulator.into_subgrids(self, &evolution::fac1(self, order_mask, xi))?;
+
+        let mut grid = Self {
+            subgrids,
             lumi,
             bin_limits: self.bin_limits.clone(),
-            orders: vec![Order::new(0, 0, 0, 0)],
+            orders: vec![Order::new(0, 0, 0, 0, 0)],
             subgrid_params: SubgridParams::default(),
             more_members: self.more_members.clone(),
         };
 
         // write additional metadata
-        grid.set_key_value("lumi_id_types", &info.lumi_id_types);
+        grid.set_key_value("lumi_id_types", &lumi_id_types);
+        grid.set_key_value("fk_muf2_1", &fac0_a.to_string());
+        grid.set_key_value("fk_muf2_2", &fac0_b.to_string());
 
         Ok(FkTable::try_from(grid).unwrap())
     }
 
     /// Deletes bins with the corresponding `bin_indices`. Repeated indices and indices larger or
-    /// equal the bin length are ignored.
+    /// equal the bin length are ignored. Observables that reference any of the deleted bins are
+    /// removed, the bin indices of the remaining ones are adjusted, see [`Grid::observables`].
     pub fn delete_bins(&mut self, bin_indices: &[usize]) {
         let mut bin_indices: Vec<_> = bin_indices
             .iter()
@@ -1926,53 +2704,202 @@ impl Grid {
         }
 
         let bin_ranges = bin_ranges;
-        let mut ranges = bin_ranges.as_slice();
-        let old_limits = self.bin_limits.limits();
-
-        // remove the bins from the right first, so as not to invalidate any indices
-        if let Some((range, remainder)) = ranges.split_last() {
-            if range.end == self.bin_info().bins() {
-                self.bin_limits.delete_bins_right(range.end - range.start);
-                ranges = remainder;
-            }
+        let bins = self.bin_info().bins();
+
+        // if bins in the middle are removed the remaining ones are non-consecutive, which requires
+        // a remapper
+        if bin_ranges
+            .iter()
+            .any(|range| (range.start != 0) && (range.end != bins))
+        {
+            self.convert_bin_limits_to_remapper();
         }
 
-        // indices on the left aren't affected by removal of bins to their right
-        if let Some((range, remainder)) = ranges.split_first() {
-            if range.start == 0 {
-                self.bin_limits.delete_bins_left(range.end);
-                ranges = remainder;
+        if let Some(remapper) = self.remapper_mut() {
+            remapper.delete_bins(&bin_ranges);
+
+            // the following should not be needed, but let's set these limits to integer values
+            self.bin_limits = BinLimits::new(
+                iter::successors(Some(0.0), |x| Some(x + 1.0))
+                    .take(bins - bin_indices.len() + 1)
+                    .collect(),
+            );
+        } else {
+            // remove the bins from the right first, so as not to invalidate any indices
+            for range in bin_ranges.iter().rev() {
+                if range.end == bins {
+                    self.bin_limits.delete_bins_right(range.end - range.start);
+                } else {
+                    self.bin_limits.delete_bins_left(range.end);
+                }
             }
         }
 
-        if !ranges.is_empty() {
-            // if there's no remapper we need to store the bin limits in a new remapper
-            if self.remapper_mut().is_none() {
-                self.set_remapper(
-                    BinRemapper::new(
-                        old_limits.windows(2).map(|win| win[1] - win[0]).collect(),
-                        old_limits.windows(2).map(|win| (win[0], win[1])).collect(),
-                    )
-                    .unwrap_or_else(|_| unreachable!()),
-                )
-                .unwrap_or_else(|_| unreachable!());
+        for &bin_index in bin_indices.iter().rev() {
+            self.subgrids.remove_index(Axis(1), bin_index);
+        }
+
+        // a bin that wasn't deleted is shifted by the number of deleted bins before it
+        self.remap_observables(|bin| {
+            bin_indices
+                .binary_search(&bin)
+                .err()
+                .map(|shift| bin - shift)
+        });
+    }
+
+    /// Replaces the bins of this grid with the bins given by `bin_indices`, in that order. This
+    /// allows to extract a subset of the bins, to duplicate bins and to change their order. If the
+    /// resulting bins can not be described by one-dimensional consecutive limits, a remapper is
+    /// created, whose limits and normalizations are selected together with the subgrids. The
+    /// observables are changed to reference the selected bins; observables that reference bins
+    /// that are not selected are removed, see [`Grid::observables`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::InvalidBinSelection`] if `bin_indices` is empty or if it contains an
+    /// index that is larger or equal the number of bins.
+    pub fn select_bins(&mut self, bin_indices: &[usize]) -> Result<(), GridError> {
+        let bins = self.bin_info().bins();
+
+        if bin_indices.is_empty() || bin_indices.iter().any(|&index| index >= bins) {
+            return Err(GridError::InvalidBinSelection {
+                bin_indices: bin_indices.to_vec(),
+                bins,
+            });
+        }
+
+        // without a remapper only an ascending range of consecutive bins can be kept as it is
+        if self.remapper().is_none()
+            && bin_indices
+                .windows(2)
+                .all(|window| window[1] == window[0] + 1)
+        {
+            let limits = self.bin_limits.limits();
+            self.bin_limits = BinLimits::new(
+                limits[bin_indices[0]..=(bin_indices[bin_indices.len() - 1] + 1)].to_vec(),
+            );
+        } else {
+            self.convert_bin_limits_to_remapper();
+
+            if let Some(remapper) = self.remapper_mut() {
+                remapper.select_bins(bin_indices);
             }
 
             // the following should not be needed, but let's set these limits to integer values
             self.bin_limits = BinLimits::new(
                 iter::successors(Some(0.0), |x| Some(x + 1.0))
-                    .take(old_limits.len() - bin_indices.len())
+                    .take(bin_indices.len() + 1)
                     .collect(),
             );
         }
 
-        if let Some(remapper) = self.remapper_mut() {
-            remapper.delete_bins(&bin_ranges);
+        self.subgrids = self.subgrids.select(Axis(1), bin_indices);
+        self.remap_observables(|bin| bin_indices.iter().position(|&index| index == bin));
+
+        Ok(())
+    }
+
+    /// Changes the order of the bins such that the `i`-th bin of the resulting grid is the bin
+    /// with index `bin_indices[i]` of the original one. See [`Grid::select_bins`] for the more
+    /// general operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::InvalidBinSelection`] if `bin_indices` is not a permutation of all
+    /// bin indices.
+    pub fn reorder_bins(&mut self, bin_indices: &[usize]) -> Result<(), GridError> {
+        let bins = self.bin_info().bins();
+        let mut sorted = bin_indices.to_vec();
+        sorted.sort_unstable();
+
+        if !sorted.into_iter().eq(0..bins) {
+            return Err(GridError::InvalidBinSelection {
+                bin_indices: bin_indices.to_vec(),
+                bins,
+            });
         }
 
-        for &bin_index in bin_indices.iter().rev() {
-            self.subgrids.remove_index(Axis(1), bin_index);
+        self.select_bins(bin_indices)
+    }
+
+    /// Merges the bins of this grid such that the bins of the last dimension have the limits given
+    /// by `edges`, which must be sorted in ascending order. For multi-dimensional bins this is
+    /// done separately for each slice, see [`BinInfo::slices`]. Since the subgrids of the merged
+    /// bins are summed, the result is exact. Bins that lie outside of the range spanned by `edges`
+    /// are deleted, as are observables that reference merged or deleted bins.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::MisalignedBinEdges`] if fewer than two edges are given, if they are
+    /// not sorted, or if any edge does not coincide with a limit of the existing bins, which must
+    /// not have gaps between two edges.
+    pub fn rebin(&mut self, edges: &[f64]) -> Result<(), GridError> {
+        let error = || GridError::MisalignedBinEdges {
+            edges: edges.to_vec(),
+        };
+
+        if edges.len() < 2 || edges.windows(2).any(|window| window[0] >= window[1]) {
+            return Err(error());
+        }
+
+        let same = |a: f64, b: f64| approx_eq!(f64, a, b, ulps = 64);
+        let bin_info = self.bin_info();
+        let dimension = bin_info.dimensions() - 1;
+        let left = bin_info.left(dimension);
+        let right = bin_info.right(dimension);
+        let mut deletions = Vec::new();
+        let mut merges = Vec::new();
+
+        for (start, end) in bin_info.slices() {
+            let mut bin = start;
+
+            // skip all bins left of the first edge
+            while bin < end && (right[bin] < edges[0] || same(right[bin], edges[0])) {
+                deletions.push(bin);
+                bin += 1;
+            }
+
+            for window in edges.windows(2) {
+                if bin == end || !same(left[bin], window[0]) {
+                    return Err(error());
+                }
+
+                let first = bin;
+
+                loop {
+                    if bin == end
+                        || (bin != first && !same(left[bin], right[bin - 1]))
+                        || (right[bin] > window[1] && !same(right[bin], window[1]))
+                    {
+                        return Err(error());
+                    }
+
+                    bin += 1;
+
+                    if same(right[bin - 1], window[1]) {
+                        break;
+                    }
+                }
+
+                merges.push(first..bin);
+            }
+
+            // delete all bins right of the last edge
+            deletions.extend(bin..end);
+        }
+
+        self.delete_bins(&deletions);
+
+        // merge from the right so as not to invalidate the indices of the remaining ranges
+        for range in merges.into_iter().rev() {
+            if range.len() > 1 {
+                let shift = deletions.iter().filter(|&&bin| bin < range.start).count();
+                self.merge_bins((range.start - shift)..(range.end - shift))?;
+            }
         }
+
+        Ok(())
     }
 
     pub(crate) fn rewrite_lumi(&mut self, add: &[(i32, i32)], del: &[i32]) {
@@ -2060,47 +2987,183 @@ impl Grid {
     }
 }
 
+/// Multiplies two series in the strong coupling, whose coefficients are polynomials in a
+/// logarithm, and truncates the result after the power `order` of the strong coupling.
+fn log_series_mul(lhs: &[Vec<f64>], rhs: &[Vec<f64>], order: usize) -> Vec<Vec<f64>> {
+    let mut result = vec![Vec::new(); order + 1];
+
+    for (k1, poly1) in lhs.iter().enumerate().take(order + 1) {
+        for (k2, poly2) in rhs.iter().enumerate().take(order + 1 - k1) {
+            let poly = &mut result[k1 + k2];
+
+            for (m1, c1) in poly1.iter().enumerate() {
+                for (m2, c2) in poly2.iter().enumerate() {
+                    if poly.len() <= m1 + m2 {
+                        poly.resize(m1 + m2 + 1, 0.0);
+                    }
+
+                    poly[m1 + m2] += c1 * c2;
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns the expansion of `(alphas(muf2) / alphas(mur2))^power` in powers of `alphas(mur2)` up
+/// to and including `order`. The `k`-th entry contains the coefficients of the polynomial in
+/// `ln(muf2 / mur2)` multiplying `alphas(mur2)^k`. The running of the strong coupling is given by
+/// the coefficients `beta` of the beta function, normalized as in [`Grid::add_scale_logs`].
+fn coupling_ratio(beta: &[f64], power: usize, order: usize) -> Vec<Vec<f64>> {
+    // the ratio `u` fulfills `du/dl = -sum_i beta[i] * alphas(mur2)^(i + 1) * u^(i + 2)`, which is
+    // solved order by order, since the right-hand side only requires lower orders of `u`
+    let mut ratio = vec![vec![1.0]];
+
+    for k in 1..=order {
+        let mut derivative: Vec<f64> = Vec::new();
+        let mut ratio_power = log_series_mul(&ratio, &ratio, k);
+
+        for (i, &coefficient) in beta.iter().enumerate().take(k) {
+            // `ratio_power` is `u^(i + 2)`
+            for (m, c) in ratio_power[k - i - 1].iter().enumerate() {
+                if derivative.len() <= m {
+                    derivative.resize(m + 1, 0.0);
+                }
+
+                derivative[m] -= coefficient * c;
+            }
+
+            ratio_power = log_series_mul(&ratio_power, &ratio, k);
+        }
+
+        // the integration constant vanishes, since both couplings are the same for `muf2 = mur2`
+        ratio.push(
+            iter::once(0.0)
+                .chain(
+                    derivative
+                        .iter()
+                        .enumerate()
+                        .map(|(m, c)| c / f64_from_usize(m + 1)),
+                )
+                .collect(),
+        );
+    }
+
+    (0..power).fold(vec![vec![1.0]], |series, _| {
+        log_series_mul(&series, &ratio, order)
+    })
+}
+
+/// Applies `kernel` to each initial state of `subgrid` with a PDF, for every partonic
+/// combination of `entry`, and returns the resulting subgrids with their luminosity entries.
+fn apply_kernel_to_entry(
+    subgrid: &SubgridEnum,
+    entry: &LumiEntry,
+    kernel: &SplittingKernel,
+    [has_pdf1, has_pdf2]: [bool; 2],
+    factor: f64,
+) -> Vec<(LumiEntry, SubgridEnum)> {
+    let mut contributions = Vec::new();
+
+    for &(a, b, f) in entry.entry() {
+        if has_pdf1 {
+            for (c, matrix) in kernel(a, &subgrid.x1_grid()) {
+                contributions.push((
+                    lumi_entry![c, b, 1.0],
+                    apply_kernel(subgrid, &matrix, true, factor * f),
+                ));
+            }
+        }
+
+        if has_pdf2 {
+            for (c, matrix) in kernel(b, &subgrid.x2_grid()) {
+                contributions.push((
+                    lumi_entry![a, c, 1.0],
+                    apply_kernel(subgrid, &matrix, false, factor * f),
+                ));
+            }
+        }
+    }
+
+    contributions
+}
+
+fn apply_kernel(
+    subgrid: &SubgridEnum,
+    matrix: &Array2<f64>,
+    first: bool,
+    factor: f64,
+) -> SubgridEnum {
+    let mu2_grid = subgrid.mu2_grid();
+    let x1_grid = subgrid.x1_grid();
+    let x2_grid = subgrid.x2_grid();
+    let mut array = SparseArray3::new(mu2_grid.len(), x1_grid.len(), x2_grid.len());
+
+    for ((imu2, ix1, ix2), value) in subgrid.indexed_iter() {
+        let row = matrix.row(if first { ix1 } else { ix2 });
+
+        for (k, &m) in row.iter().enumerate().filter(|(_, &m)| m != 0.0) {
+            let index = if first {
+                [imu2, k, ix2]
+            } else {
+                [imu2, ix1, k]
+            };
+            array[index] += factor * value * m;
+        }
+    }
+
+    ImportOnlySubgridV2::new(
+        array,
+        mu2_grid.into_owned(),
+        x1_grid.into_owned(),
+        x2_grid.into_owned(),
+    )
+    .into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::import_only_subgrid::ImportOnlySubgridV1;
     use crate::lumi_entry;
     use float_cmp::assert_approx_eq;
+    use ndarray::Array4;
     use std::fs::File;
 
     #[test]
     fn order_cmp() {
         let mut orders = vec![
-            Order::new(1, 2, 1, 0),
-            Order::new(1, 2, 0, 1),
-            Order::new(1, 2, 0, 0),
-            Order::new(0, 3, 1, 0),
-            Order::new(0, 3, 0, 1),
-            Order::new(0, 3, 0, 0),
-            Order::new(0, 2, 0, 0),
+            Order::new(1, 2, 1, 0, 0),
+            Order::new(1, 2, 0, 1, 0),
+            Order::new(1, 2, 0, 0, 0),
+            Order::new(0, 3, 1, 0, 0),
+            Order::new(0, 3, 0, 1, 0),
+            Order::new(0, 3, 0, 0, 0),
+            Order::new(0, 2, 0, 0, 0),
         ];
 
         orders.sort();
 
-        assert_eq!(orders[0], Order::new(0, 2, 0, 0));
-        assert_eq!(orders[1], Order::new(1, 2, 0, 0));
-        assert_eq!(orders[2], Order::new(1, 2, 0, 1));
-        assert_eq!(orders[3], Order::new(1, 2, 1, 0));
-        assert_eq!(orders[4], Order::new(0, 3, 0, 0));
-        assert_eq!(orders[5], Order::new(0, 3, 0, 1));
-        assert_eq!(orders[6], Order::new(0, 3, 1, 0));
+        assert_eq!(orders[0], Order::new(0, 2, 0, 0, 0));
+        assert_eq!(orders[1], Order::new(1, 2, 0, 0, 0));
+        assert_eq!(orders[2], Order::new(1, 2, 0, 1, 0));
+        assert_eq!(orders[3], Order::new(1, 2, 1, 0, 0));
+        assert_eq!(orders[4], Order::new(0, 3, 0, 0, 0));
+        assert_eq!(orders[5], Order::new(0, 3, 0, 1, 0));
+        assert_eq!(orders[6], Order::new(0, 3, 1, 0, 0));
     }
 
     #[test]
     fn order_create_mask() {
         // Drell—Yan orders
         let orders = [
-            Order::new(0, 2, 0, 0), //   LO        :          alpha^2
-            Order::new(1, 2, 0, 0), //  NLO QCD    : alphas   alpha^2
-            Order::new(0, 3, 0, 0), //  NLO  EW    :          alpha^3
-            Order::new(2, 2, 0, 0), // NNLO QCD    : alphas^2 alpha^2
-            Order::new(1, 3, 0, 0), // NNLO QCD—EW : alphas   alpha^3
-            Order::new(0, 4, 0, 0), // NNLO EW     :          alpha^4
+            Order::new(0, 2, 0, 0, 0), //   LO        :          alpha^2
+            Order::new(1, 2, 0, 0, 0), //  NLO QCD    : alphas   alpha^2
+            Order::new(0, 3, 0, 0, 0), //  NLO  EW    :          alpha^3
+            Order::new(2, 2, 0, 0, 0), // NNLO QCD    : alphas^2 alpha^2
+            Order::new(1, 3, 0, 0, 0), // NNLO QCD—EW : alphas   alpha^3
+            Order::new(0, 4, 0, 0, 0), // NNLO EW     :          alpha^4
         ];
 
         assert_eq!(
@@ -2170,18 +3233,18 @@ mod tests {
 
         // Top-pair production orders
         let orders = [
-            Order::new(2, 0, 0, 0), //   LO QCD    : alphas^2
-            Order::new(1, 1, 0, 0), //   LO QCD—EW : alphas   alpha
-            Order::new(0, 2, 0, 0), //   LO  EW    :          alpha^2
-            Order::new(3, 0, 0, 0), //  NLO QCD    : alphas^3
-            Order::new(2, 1, 0, 0), //  NLO QCD—EW : alphas^2 alpha
-            Order::new(1, 2, 0, 0), //  NLO QCD—EW : alphas   alpha^2
-            Order::new(0, 3, 0, 0), //  NLO  EW    :          alpha^3
-            Order::new(4, 0, 0, 0), // NNLO QCD    : alphas^4
-            Order::new(3, 1, 0, 0), // NNLO QCD—EW : alphas^3 alpha
-            Order::new(2, 2, 0, 0), // NNLO QCD—EW : alphas^2 alpha^2
-            Order::new(1, 3, 0, 0), // NNLO QCD—EW : alphas   alpha^3
-            Order::new(0, 4, 0, 0), // NNLO EW     :          alpha^4
+            Order::new(2, 0, 0, 0, 0), //   LO QCD    : alphas^2
+            Order::new(1, 1, 0, 0, 0), //   LO QCD—EW : alphas   alpha
+            Order::new(0, 2, 0, 0, 0), //   LO  EW    :          alpha^2
+            Order::new(3, 0, 0, 0, 0), //  NLO QCD    : alphas^3
+            Order::new(2, 1, 0, 0, 0), //  NLO QCD—EW : alphas^2 alpha
+            Order::new(1, 2, 0, 0, 0), //  NLO QCD—EW : alphas   alpha^2
+            Order::new(0, 3, 0, 0, 0), //  NLO  EW    :          alpha^3
+            Order::new(4, 0, 0, 0, 0), // NNLO QCD    : alphas^4
+            Order::new(3, 1, 0, 0, 0), // NNLO QCD—EW : alphas^3 alpha
+            Order::new(2, 2, 0, 0, 0), // NNLO QCD—EW : alphas^2 alpha^2
+            Order::new(1, 3, 0, 0, 0), // NNLO QCD—EW : alphas   alpha^3
+            Order::new(0, 4, 0, 0, 0), // NNLO EW     :          alpha^4
         ];
 
         assert_eq!(
@@ -2250,6 +3313,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn grid_fill_nd() {
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            SubgridParams::default(),
+        );
+        let ntuple = Ntuple {
+            x1: 0.1,
+            x2: 0.2,
+            q2: 90.0_f64.powi(2),
+            weight: 1.0,
+        };
+
+        grid.fill_nd(0, &[1.5], 0, &ntuple);
+
+        assert!(!grid.subgrid(0, 1, 0).is_empty());
+
+        grid.set_remapper(
+            BinRemapper::new(
+                vec![1.0; 4],
+                vec![
+                    (0.0, 1.0),
+                    (0.0, 2.0),
+                    (0.0, 1.0),
+                    (2.0, 5.0),
+                    (1.0, 3.0),
+                    (0.0, 1.0),
+                    (1.0, 3.0),
+                    (1.0, 5.0),
+                ],
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        grid.fill_nd(0, &[2.5, 3.0], 0, &ntuple);
+        grid.fill_nd(0, &[3.0, 0.5], 0, &ntuple);
+
+        assert!(grid.subgrid(0, 0, 0).is_empty());
+        assert!(!grid.subgrid(0, 1, 0).is_empty());
+        assert!(grid.subgrid(0, 2, 0).is_empty());
+        assert!(!grid.subgrid(0, 3, 0).is_empty());
+    }
+
     #[test]
     fn grid_with_subgrid_type() {
         let subgrid_type = String::from("Idontexist");
@@ -2272,7 +3381,7 @@ mod tests {
                 lumi_entry![2, 2, 1.0; 4, 4, 1.0],
                 lumi_entry![1, 1, 1.0; 3, 3, 1.0],
             ],
-            vec![Order::new(0, 2, 0, 0)],
+            vec![Order::new(0, 2, 0, 0, 0)],
             vec![0.0, 0.25, 0.5, 0.75, 1.0],
             SubgridParams::default(),
         );
@@ -2287,7 +3396,7 @@ mod tests {
                 lumi_entry![1, 1, 1.0; 3, 3, 1.0],
                 lumi_entry![2, 2, 1.0; 4, 4, 1.0],
             ],
-            vec![Order::new(1, 2, 0, 0), Order::new(1, 2, 0, 1)],
+            vec![Order::new(1, 2, 0, 0, 0), Order::new(1, 2, 0, 1, 0)],
             vec![0.0, 0.25, 0.5, 0.75, 1.0],
             SubgridParams::default(),
         );
@@ -2300,6 +3409,167 @@ mod tests {
         assert_eq!(grid.orders().len(), 1);
     }
 
+    #[test]
+    fn grid_add_scale_logs() {
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(1, 2, 0, 0, 0), Order::new(2, 2, 0, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        for order in 0..2 {
+            grid.fill(
+                order,
+                0.5,
+                0,
+                &Ntuple {
+                    x1: 0.1,
+                    x2: 0.2,
+                    q2: 90.0_f64.powi(2),
+                    weight: 1.0,
+                },
+            );
+        }
+
+        let sum = |subgrid: &SubgridEnum| subgrid.indexed_iter().map(|(_, value)| value).sum();
+        let lo: f64 = sum(grid.subgrid(0, 0, 0));
+        let identity = |pid: i32, x: &[f64]| vec![(pid, Array2::eye(x.len()))];
+
+        grid.add_scale_logs(&[0.5], &[&identity]).unwrap();
+
+        assert_eq!(
+            grid.orders(),
+            [
+                Order::new(1, 2, 0, 0, 0),
+                Order::new(2, 2, 0, 0, 0),
+                Order::new(2, 2, 1, 0, 0),
+                Order::new(2, 2, 0, 1, 0),
+            ]
+        );
+        assert_eq!(grid.lumi(), [lumi_entry![2, 2, 1.0]]);
+        assert_approx_eq!(f64, sum(grid.subgrid(2, 0, 0)), 0.5 * lo, ulps = 4);
+        assert_approx_eq!(f64, sum(grid.subgrid(3, 0, 0)), -2.0 * lo, ulps = 4);
+
+        assert!(matches!(
+            grid.add_scale_logs(&[0.5], &[]),
+            Err(GridError::ScaleLogsPresent(order)) if order == Order::new(2, 2, 1, 0, 0)
+        ));
+    }
+
+    #[test]
+    fn grid_add_scale_logs_nnlo() {
+        // powers of the strong coupling of the lowest order
+        const P: u32 = 1;
+        let (beta0, beta1) = (0.5, 0.25);
+        // the kernels are multiples of the identity, so every convolution is a multiplication
+        let (p0, p1) = (1.5, 0.75);
+
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            (0..3).map(|n| Order::new(P + n, 2, 0, 0, 0)).collect(),
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        for (order, weight) in [1.0, 2.0, 3.0].into_iter().enumerate() {
+            grid.fill(
+                order,
+                0.5,
+                0,
+                &Ntuple {
+                    x1: 0.1,
+                    x2: 0.2,
+                    q2: 90.0_f64.powi(2),
+                    weight,
+                },
+            );
+        }
+
+        let sum = |subgrid: &SubgridEnum| -> f64 { subgrid.indexed_iter().map(|(_, v)| v).sum() };
+        let sigma0 = sum(grid.subgrid(0, 0, 0));
+        let sigma1 = sum(grid.subgrid(1, 0, 0));
+        let kernel0 = move |pid: i32, x: &[f64]| vec![(pid, p0 * Array2::eye(x.len()))];
+        let kernel1 = move |pid: i32, x: &[f64]| vec![(pid, p1 * Array2::eye(x.len()))];
+
+        grid.add_scale_logs(&[beta0, beta1], &[&kernel0, &kernel1])
+            .unwrap();
+
+        let coefficient = |alphas, logxir, logxif| {
+            let order = grid
+                .orders()
+                .iter()
+                .position(|order| *order == Order::new(alphas, 2, logxir, logxif, 0))
+                .unwrap();
+            sum(grid.subgrid(order, 0, 0))
+        };
+
+        // both initial states are evolved, which doubles the splitting functions
+        let (p0, p1) = (2.0 * p0, 2.0 * p1);
+        let p = f64::from(P);
+
+        assert_eq!(grid.orders().len(), 3 + 2 + 5);
+
+        assert_approx_eq!(f64, coefficient(P + 1, 1, 0), p * beta0 * sigma0, ulps = 8);
+        assert_approx_eq!(f64, coefficient(P + 1, 0, 1), -p0 * sigma0, ulps = 8);
+
+        assert_approx_eq!(
+            f64,
+            coefficient(P + 2, 2, 0),
+            0.5 * p * (p + 1.0) * beta0 * beta0 * sigma0,
+            ulps = 8
+        );
+        assert_approx_eq!(
+            f64,
+            coefficient(P + 2, 1, 0),
+            ((p + 1.0) * beta0).mul_add(sigma1, p * beta1 * sigma0),
+            ulps = 8
+        );
+        assert_approx_eq!(
+            f64,
+            coefficient(P + 2, 0, 1),
+            -p0.mul_add(sigma1, p1 * sigma0),
+            ulps = 8
+        );
+        // the running of the strong coupling in the splitting functions contributes `beta0 * p0`
+        assert_approx_eq!(
+            f64,
+            coefficient(P + 2, 0, 2),
+            0.5 * p0 * (p0 + beta0) * sigma0,
+            ulps = 8
+        );
+        assert_approx_eq!(
+            f64,
+            coefficient(P + 2, 1, 1),
+            -(p + 1.0) * beta0 * p0 * sigma0,
+            ulps = 8
+        );
+    }
+
+    #[test]
+    fn coupling_ratio_expansion() {
+        let (beta0, beta1) = (0.5, 0.25);
+
+        // alphas(muf2) / alphas(mur2) = 1 - beta0 as l + (beta0^2 l^2 - beta1 l) as^2 + ...
+        assert_eq!(
+            coupling_ratio(&[beta0, beta1], 1, 2),
+            [
+                vec![1.0],
+                vec![0.0, -beta0],
+                vec![0.0, -beta1, beta0 * beta0]
+            ]
+        );
+        assert_eq!(
+            coupling_ratio(&[beta0, beta1], 2, 2),
+            [
+                vec![1.0],
+                vec![0.0, -2.0 * beta0],
+                vec![0.0, -2.0 * beta1, 3.0 * beta0 * beta0]
+            ]
+        );
+        assert_eq!(coupling_ratio(&[], 2, 1), [vec![1.0], vec![0.0]]);
+    }
+
     #[test]
     fn grid_merge_orders() {
         let mut grid = Grid::new(
@@ -2307,7 +3577,7 @@ mod tests {
                 lumi_entry![2, 2, 1.0; 4, 4, 1.0],
                 lumi_entry![1, 1, 1.0; 3, 3, 1.0],
             ],
-            vec![Order::new(0, 2, 0, 0)],
+            vec![Order::new(0, 2, 0, 0, 0)],
             vec![0.0, 0.25, 0.5, 0.75, 1.0],
             SubgridParams::default(),
         );
@@ -2322,9 +3592,9 @@ mod tests {
                 lumi_entry![1, 1, 1.0; 3, 3, 1.0],
             ],
             vec![
-                Order::new(1, 2, 0, 0),
-                Order::new(1, 2, 0, 1),
-                Order::new(0, 2, 0, 0),
+                Order::new(1, 2, 0, 0, 0),
+                Order::new(1, 2, 0, 1, 0),
+                Order::new(0, 2, 0, 0, 0),
             ],
             vec![0.0, 0.25, 0.5, 0.75, 1.0],
             SubgridParams::default(),
@@ -2368,7 +3638,7 @@ mod tests {
                 lumi_entry![2, 2, 1.0; 4, 4, 1.0],
                 lumi_entry![1, 1, 1.0; 3, 3, 1.0],
             ],
-            vec![Order::new(0, 2, 0, 0)],
+            vec![Order::new(0, 2, 0, 0, 0)],
             vec![0.0, 0.25, 0.5, 0.75, 1.0],
             SubgridParams::default(),
         );
@@ -2379,7 +3649,7 @@ mod tests {
 
         let mut other = Grid::new(
             vec![lumi_entry![22, 22, 1.0], lumi_entry![2, 2, 1.0; 4, 4, 1.0]],
-            vec![Order::new(0, 2, 0, 0)],
+            vec![Order::new(0, 2, 0, 0, 0)],
             vec![0.0, 0.25, 0.5, 0.75, 1.0],
             SubgridParams::default(),
         );
@@ -2411,7 +3681,7 @@ mod tests {
                 lumi_entry![2, 2, 1.0; 4, 4, 1.0],
                 lumi_entry![1, 1, 1.0; 3, 3, 1.0],
             ],
-            vec![Order::new(0, 2, 0, 0)],
+            vec![Order::new(0, 2, 0, 0, 0)],
             vec![0.0, 0.25, 0.5],
             SubgridParams::default(),
         );
@@ -2426,7 +3696,7 @@ mod tests {
                 lumi_entry![1, 1, 1.0; 3, 3, 1.0],
                 lumi_entry![2, 2, 1.0; 4, 4, 1.0],
             ],
-            vec![Order::new(0, 2, 0, 0)],
+            vec![Order::new(0, 2, 0, 0, 0)],
             vec![0.5, 0.75, 1.0],
             SubgridParams::default(),
         );
@@ -2450,28 +3720,449 @@ mod tests {
         assert_eq!(grid.orders().len(), 1);
     }
 
-    // TODO: convolute_subgrid, merge_bins, subgrid, set_subgrid
-
     #[test]
-    fn grid_key_value() {
+    fn grid_merge_non_consecutive_bins() {
         let mut grid = Grid::new(
-            vec![lumi_entry![21, 21, 1.0]],
-            vec![Order {
-                alphas: 0,
-                alpha: 0,
-                logxir: 0,
-                logxif: 0,
-            }],
-            vec![0.0, 1.0],
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0],
             SubgridParams::default(),
         );
+        let ntuple = Ntuple {
+            x1: 0.1,
+            x2: 0.2,
+            q2: 90.0_f64.powi(2),
+            weight: 1.0,
+        };
 
-        assert_eq!(
-            grid.key_values().unwrap().get("initial_state_1").unwrap(),
-            "2212"
+        let mut other = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![3.0, 4.0],
+            SubgridParams::default(),
         );
+        other.fill(0, 3.5, 0, &ntuple);
 
-        grid.key_values_mut()
+        grid.merge(other).unwrap();
+
+        assert_eq!(
+            grid.bin_info().limits(),
+            [[(0.0, 1.0)], [(1.0, 2.0)], [(3.0, 4.0)]]
+        );
+        assert_eq!(grid.bin_info().normalizations(), [1.0, 1.0, 1.0]);
+        assert!(grid.subgrid(0, 1, 0).is_empty());
+        assert!(!grid.subgrid(0, 2, 0).is_empty());
+
+        // overlapping bins
+        let mut other = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.5, 1.5],
+            SubgridParams::default(),
+        );
+        other.fill(0, 1.0, 0, &ntuple);
+
+        grid.merge(other).unwrap();
+
+        assert_eq!(
+            grid.bin_info().limits(),
+            [[(0.0, 1.0)], [(1.0, 2.0)], [(3.0, 4.0)], [(0.5, 1.5)]]
+        );
+        assert!(!grid.subgrid(0, 3, 0).is_empty());
+
+        grid.fill_nd(0, &[1.25], 0, &ntuple);
+
+        assert!(!grid.subgrid(0, 1, 0).is_empty());
+
+        // bins with a gap can not be merged and the grid must not be changed
+        assert!(matches!(
+            grid.merge_bins(1..3),
+            Err(GridError::MergeBinError(MergeBinError::NonConsecutiveRange(range))) if range == (1..3)
+        ));
+        assert_eq!(grid.bin_info().bins(), 4);
+
+        grid.merge_bins(0..2).unwrap();
+
+        assert_eq!(
+            grid.bin_info().limits(),
+            [[(0.0, 2.0)], [(3.0, 4.0)], [(0.5, 1.5)]]
+        );
+        assert_eq!(grid.bin_info().normalizations(), [2.0, 1.0, 1.0]);
+        assert!(!grid.subgrid(0, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn grid_merge_incompatible_dimensions() {
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+        let mut other = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+        other
+            .set_remapper(BinRemapper::new(vec![1.0], vec![(0.0, 1.0), (2.0, 3.0)]).unwrap())
+            .unwrap();
+
+        assert!(matches!(
+            grid.merge(other),
+            Err(GridError::MergeBinError(
+                MergeBinError::IncompatibleDimensions { lhs: 1, rhs: 2 }
+            ))
+        ));
+
+        // the failed merge must not modify the grid
+        assert!(grid.remapper().is_none());
+        assert_eq!(grid.bin_info().limits(), [[(0.0, 1.0)], [(1.0, 2.0)]]);
+    }
+
+    #[test]
+    fn grid_delete_bins() {
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            SubgridParams::default(),
+        );
+
+        grid.delete_bins(&[4]);
+
+        assert!(grid.remapper().is_none());
+        assert_eq!(
+            grid.bin_info().limits(),
+            [[(0.0, 1.0)], [(1.0, 2.0)], [(2.0, 3.0)], [(3.0, 4.0)]]
+        );
+
+        grid.delete_bins(&[0, 2]);
+
+        assert_eq!(grid.bin_info().limits(), [[(1.0, 2.0)], [(3.0, 4.0)]]);
+        assert_eq!(grid.bin_info().bins(), 2);
+        assert_eq!(grid.subgrids().dim().1, 2);
+    }
+
+    #[test]
+    fn grid_select_bins() {
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            SubgridParams::default(),
+        );
+
+        grid.fill(
+            0,
+            2.5,
+            0,
+            &Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 90.0_f64.powi(2),
+                weight: 1.0,
+            },
+        );
+
+        grid.select_bins(&[1, 2, 3]).unwrap();
+
+        assert!(grid.remapper().is_none());
+        assert_eq!(
+            grid.bin_info().limits(),
+            [[(1.0, 2.0)], [(2.0, 3.0)], [(3.0, 4.0)]]
+        );
+        assert!(!grid.subgrids()[[0, 1, 0]].is_empty());
+
+        grid.select_bins(&[1, 0, 1]).unwrap();
+
+        assert_eq!(
+            grid.bin_info().limits(),
+            [[(2.0, 3.0)], [(1.0, 2.0)], [(2.0, 3.0)]]
+        );
+        assert_eq!(grid.bin_info().normalizations(), [1.0, 1.0, 1.0]);
+        assert_eq!(grid.subgrids().dim().1, 3);
+        assert!(!grid.subgrids()[[0, 0, 0]].is_empty());
+        assert!(grid.subgrids()[[0, 1, 0]].is_empty());
+        assert!(!grid.subgrids()[[0, 2, 0]].is_empty());
+
+        grid.reorder_bins(&[1, 2, 0]).unwrap();
+
+        assert_eq!(
+            grid.bin_info().limits(),
+            [[(1.0, 2.0)], [(2.0, 3.0)], [(2.0, 3.0)]]
+        );
+        assert!(grid.subgrids()[[0, 0, 0]].is_empty());
+
+        assert!(matches!(
+            grid.reorder_bins(&[0, 1]),
+            Err(GridError::InvalidBinSelection { bins: 3, .. })
+        ));
+        assert!(matches!(
+            grid.select_bins(&[3]),
+            Err(GridError::InvalidBinSelection { bins: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn grid_rebin() {
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            SubgridParams::default(),
+        );
+
+        grid.fill(
+            0,
+            2.5,
+            0,
+            &Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 90.0_f64.powi(2),
+                weight: 1.0,
+            },
+        );
+
+        assert!(matches!(
+            grid.rebin(&[1.0, 2.5]),
+            Err(GridError::MisalignedBinEdges { .. })
+        ));
+        assert!(matches!(
+            grid.rebin(&[2.0, 1.0]),
+            Err(GridError::MisalignedBinEdges { .. })
+        ));
+        assert!(matches!(
+            grid.rebin(&[4.0, 6.0]),
+            Err(GridError::MisalignedBinEdges { .. })
+        ));
+
+        grid.rebin(&[1.0, 2.0, 4.0]).unwrap();
+
+        assert!(grid.remapper().is_none());
+        assert_eq!(grid.bin_info().limits(), [[(1.0, 2.0)], [(2.0, 4.0)]]);
+        assert_eq!(grid.bin_info().normalizations(), [1.0, 2.0]);
+        assert!(grid.subgrids()[[0, 0, 0]].is_empty());
+        assert!(!grid.subgrids()[[0, 1, 0]].is_empty());
+
+        // two-dimensional bins with two slices of three bins each
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            SubgridParams::default(),
+        );
+
+        grid.set_remapper("0,1,2;0,1,2,3|0,2,3,4".parse().unwrap())
+            .unwrap();
+
+        grid.fill(
+            0,
+            4.5,
+            0,
+            &Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 90.0_f64.powi(2),
+                weight: 1.0,
+            },
+        );
+
+        assert!(matches!(
+            grid.rebin(&[0.0, 1.0]),
+            Err(GridError::MisalignedBinEdges { .. })
+        ));
+
+        grid.rebin(&[0.0, 2.0, 3.0]).unwrap();
+
+        assert_eq!(
+            grid.bin_info().limits(),
+            [
+                [(0.0, 1.0), (0.0, 2.0)],
+                [(0.0, 1.0), (2.0, 3.0)],
+                [(1.0, 2.0), (0.0, 2.0)],
+                [(1.0, 2.0), (2.0, 3.0)]
+            ]
+        );
+        assert_eq!(grid.bin_info().normalizations(), [2.0, 1.0, 2.0, 1.0]);
+        assert_eq!(grid.subgrids().dim().1, 4);
+        assert!(grid.subgrids()[[0, 2, 0]].is_empty());
+        assert!(!grid.subgrids()[[0, 3, 0]].is_empty());
+    }
+
+    // TODO: convolute_subgrid, merge_bins, subgrid, set_subgrid
+
+    #[test]
+    fn grid_observables() {
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+
+        assert_eq!(grid.observables().unwrap(), None);
+
+        let observables: Observables = "b0 / (b0 + b1)".parse().unwrap();
+        grid.set_observables(&observables).unwrap();
+
+        assert_eq!(grid.key_values().unwrap()["observables"], "b0 / (b0 + b1)");
+        assert_eq!(grid.observables().unwrap(), Some(observables));
+
+        assert!(matches!(
+            grid.set_observables(&"b2".parse().unwrap()),
+            Err(GridError::InvalidObservables(
+                ObservablesError::BinOutOfRange { bin: 2, bins: 2 }
+            ))
+        ));
+
+        grid.set_key_value("observables", "b0 +");
+
+        assert!(matches!(
+            grid.observables(),
+            Err(GridError::InvalidObservables(
+                ObservablesError::UnexpectedEnd
+            ))
+        ));
+    }
+
+    fn grid_with_observables(limits: Vec<f64>, observables: &str) -> Grid {
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            limits,
+            SubgridParams::default(),
+        );
+        grid.set_observables(&observables.parse().unwrap()).unwrap();
+        grid
+    }
+
+    fn observables_string(grid: &Grid) -> Option<String> {
+        grid.observables()
+            .unwrap()
+            .map(|observables| observables.to_string())
+    }
+
+    #[test]
+    fn grid_observables_delete_bins() {
+        let mut grid =
+            grid_with_observables(vec![0.0, 1.0, 2.0, 3.0, 4.0], "b0 / (b0 + b1); b2 / b3; b3");
+
+        grid.delete_bins(&[1]);
+
+        assert_eq!(observables_string(&grid).unwrap(), "b1 / b2; b2");
+
+        grid.delete_bins(&[1, 2]);
+
+        assert_eq!(observables_string(&grid), None);
+    }
+
+    #[test]
+    fn grid_observables_select_bins() {
+        let mut grid =
+            grid_with_observables(vec![0.0, 1.0, 2.0, 3.0, 4.0], "b0 / (b0 + b1); b2 / b3; b3");
+
+        grid.select_bins(&[3, 2, 3]).unwrap();
+
+        assert_eq!(observables_string(&grid).unwrap(), "b1 / b0; b0");
+    }
+
+    #[test]
+    fn grid_observables_reorder_bins() {
+        let mut grid =
+            grid_with_observables(vec![0.0, 1.0, 2.0, 3.0, 4.0], "b0 / (b0 + b1); b2 / b3; b3");
+
+        grid.reorder_bins(&[3, 2, 1, 0]).unwrap();
+
+        assert_eq!(
+            observables_string(&grid).unwrap(),
+            "b3 / (b3 + b2); b1 / b0; b0"
+        );
+    }
+
+    #[test]
+    fn grid_observables_merge_bins() {
+        let mut grid =
+            grid_with_observables(vec![0.0, 1.0, 2.0, 3.0, 4.0], "b0 / (b0 + b1); b2 / b3; b3");
+
+        // merging a single bin doesn't change anything
+        grid.merge_bins(1..2).unwrap();
+
+        assert_eq!(
+            observables_string(&grid).unwrap(),
+            "b0 / (b0 + b1); b2 / b3; b3"
+        );
+
+        grid.merge_bins(0..2).unwrap();
+
+        assert_eq!(observables_string(&grid).unwrap(), "b1 / b2; b2");
+    }
+
+    #[test]
+    fn grid_observables_rebin() {
+        let mut grid = grid_with_observables(
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            "b1 / b4; b2 / b3; b3; b4",
+        );
+
+        // deletes the first bin and merges the third and fourth
+        grid.rebin(&[1.0, 2.0, 4.0, 5.0]).unwrap();
+
+        assert_eq!(observables_string(&grid).unwrap(), "b0 / b2; b2");
+    }
+
+    #[test]
+    fn grid_observables_merge() {
+        // bins of `other` are appended, which leaves the indices of `grid` unchanged
+        let mut grid = grid_with_observables(vec![0.0, 1.0, 2.0], "b0 / (b0 + b1)");
+        let other = grid_with_observables(vec![2.0, 3.0, 4.0], "b1");
+
+        grid.merge(other).unwrap();
+
+        assert_eq!(grid.bin_info().bins(), 4);
+        assert_eq!(observables_string(&grid).unwrap(), "b0 / (b0 + b1)");
+
+        // the same bins are merged with each other
+        let mut grid = grid_with_observables(vec![0.0, 1.0, 2.0], "b0 / (b0 + b1)");
+        let other = grid_with_observables(vec![0.0, 1.0, 2.0], "b1");
+
+        grid.merge(other).unwrap();
+
+        assert_eq!(grid.bin_info().bins(), 2);
+        assert_eq!(observables_string(&grid).unwrap(), "b0 / (b0 + b1)");
+
+        let mut grid = grid_with_observables(vec![0.0, 1.0, 2.0], "b0 / (b0 + b1)");
+        let other = grid_with_observables(vec![0.0, 1.0, 2.0], "b1");
+
+        grid.append(other).unwrap();
+
+        assert_eq!(grid.bin_info().bins(), 4);
+        assert_eq!(observables_string(&grid).unwrap(), "b0 / (b0 + b1)");
+    }
+
+    #[test]
+    fn grid_key_value() {
+        let mut grid = Grid::new(
+            vec![lumi_entry![21, 21, 1.0]],
+            vec![Order {
+                alphas: 0,
+                alpha: 0,
+                logxir: 0,
+                logxif: 0,
+                logxia: 0,
+            }],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        assert_eq!(
+            grid.key_values().unwrap().get("initial_state_1").unwrap(),
+            "2212"
+        );
+
+        grid.key_values_mut()
             .insert("initial_state_1".into(), "-2212".into());
         grid.set_key_value("initial_state_2", "-2212");
 
@@ -2491,6 +4182,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn grid_write_read_file_versions() {
+        let grid = Grid::new(
+            vec![lumi_entry![21, 21, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0), Order::new(1, 2, 1, 2, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        // grids without `logxia` are written with the old file format ...
+        let mut buffer = Vec::new();
+        grid.write(&mut buffer).unwrap();
+        assert_eq!(&buffer[0..16], b"PineAPPL\0\0\0\0\0\0\0\0");
+
+        // ... which previous versions read without knowing about `logxia`
+        let grid_v0: GridV0 = bincode::deserialize(&buffer[16..]).unwrap();
+        assert_eq!(
+            grid_v0
+                .orders
+                .iter()
+                .map(|order| (order.alphas, order.alpha, order.logxir, order.logxif))
+                .collect::<Vec<_>>(),
+            [(0, 2, 0, 0), (1, 2, 1, 2)]
+        );
+        assert_eq!(Grid::from(grid_v0).orders(), grid.orders());
+        assert_eq!(
+            Grid::read(buffer.as_slice()).unwrap().orders(),
+            grid.orders()
+        );
+
+        let grid = Grid::new(
+            vec![lumi_entry![21, 21, 1.0]],
+            vec![Order::new(0, 2, 0, 0, 0), Order::new(1, 2, 0, 0, 1)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        let mut buffer = Vec::new();
+        grid.write(&mut buffer).unwrap();
+        assert_eq!(&buffer[0..16], b"PineAPPL\x01\0\0\0\0\0\0\0");
+        assert_eq!(
+            Grid::read(buffer.as_slice()).unwrap().orders(),
+            grid.orders()
+        );
+
+        buffer[8] = 2;
+        assert!(matches!(
+            Grid::read(buffer.as_slice()),
+            Err(GridError::FileVersionMismatch {
+                file_version: 2,
+                supported_version: 1
+            })
+        ));
+    }
+
     // TODO: properly test axes returned
 
     #[allow(deprecated)]
@@ -2524,6 +4270,7 @@ mod tests {
                 alpha: 0,
                 logxir: 0,
                 logxif: 0,
+                logxia: 0,
             }],
             vec![0.0, 1.0],
             subgrid_params,
@@ -2660,4 +4407,459 @@ mod tests {
         assert_eq!(info.ren1.len(), 1);
         assert_approx_eq!(f64, info.ren1[0], 6456.443904000001, ulps = 64);
     }
+
+    /// Returns a grid with two bins, two channels and the scales `10` and `100`, whose subgrids
+    /// use `x` for both `x` grids.
+    fn evolve_test_grid(x: &[f64]) -> Grid {
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, 2, 1.0], lumi_entry![21, -2, 0.5]],
+            vec![Order::new(1, 2, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+        let mut values = (1..).map(f64::from);
+
+        for bin in 0..2 {
+            for lumi in 0..2 {
+                let mut array = SparseArray3::new(2, x.len(), x.len());
+
+                for ((ifac, ix1), ix2) in (0..2)
+                    .cartesian_product(0..x.len())
+                    .cartesian_product(0..x.len())
+                {
+                    array[[ifac, ix1, ix2]] = values.next().unwrap();
+                }
+
+                grid.set_subgrid(
+                    0,
+                    bin,
+                    lumi,
+                    ImportOnlySubgridV2::new(
+                        array,
+                        vec![
+                            Mu2 {
+                                ren: 10.0,
+                                fac: 10.0,
+                            },
+                            Mu2 {
+                                ren: 100.0,
+                                fac: 100.0,
+                            },
+                        ],
+                        x.to_vec(),
+                        x.to_vec(),
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        grid
+    }
+
+    #[test]
+    fn evolve_with_slice_iter() {
+        const X: [f64; 3] = [0.1, 0.3, 0.7];
+
+        let grid = evolve_test_grid(&X);
+
+        let info = OperatorInfo {
+            fac0: 1.0,
+            pids0: vec![1, 2, 21],
+            x0: vec![0.2, 0.5],
+            fac1: vec![10.0, 100.0],
+            pids1: vec![-2, 2, 21],
+            x1: X.to_vec(),
+            ren1: vec![10.0, 100.0],
+            alphas: vec![0.2, 0.1],
+            xir: 1.0,
+            xif: 1.0,
+            lumi_id_types: "pdg_mc_ids".to_string(),
+        };
+        let operator = Array5::from_shape_fn((2, 3, 3, 3, 2), |(f, p1, x1, p0, x0)| {
+            f64::from(u8::try_from((3 * f + 5 * p1 + 7 * x1 + 11 * p0 + 13 * x0) % 17).unwrap())
+                - 4.0
+        });
+        let reference = grid.evolve(operator.view(), &info, &[]).unwrap();
+
+        let slice = |fac1: f64, index: usize| {
+            Ok::<_, Infallible>((
+                OperatorSliceInfo {
+                    fac0: info.fac0,
+                    pids0: info.pids0.clone(),
+                    x0: info.x0.clone(),
+                    fac1,
+                    pids1: info.pids1.clone(),
+                    x1: info.x1.clone(),
+                    lumi_id_types: info.lumi_id_types.clone(),
+                },
+                CowArray::from(operator.index_axis(Axis(0), index)),
+            ))
+        };
+        let alphas_table = AlphasTable {
+            ren1: info.ren1.clone(),
+            alphas: info.alphas.clone(),
+        };
+
+        // the order of the slices doesn't matter and slices for other scales are ignored
+        let fk_table = grid
+            .evolve_with_slice_iter(
+                [slice(100.0, 1), slice(1000.0, 0), slice(10.0, 0)],
+                &[],
+                (1.0, 1.0),
+                &alphas_table,
+            )
+            .unwrap();
+
+        assert_eq!(fk_table.lumi(), reference.lumi());
+        assert_eq!(fk_table.x_grid(), reference.x_grid());
+
+        for (lhs, rhs) in fk_table.table().iter().zip(reference.table().iter()) {
+            assert_approx_eq!(f64, *lhs, *rhs, ulps = 16);
+        }
+
+        assert!(matches!(
+            grid.evolve_with_slice_iter([slice(10.0, 0)], &[], (1.0, 1.0), &alphas_table),
+            Err(GridError::EvolutionFailure(message)) if message == "no operator for muf2 = 100 found"
+        ));
+    }
+
+    /// Returns the operator information, without `fac1`, and the operator for the initial state
+    /// `side` of the grid from [`evolve_test_grid`]. Both initial states have different particles,
+    /// `x` grids and fitting scales.
+    fn evolve_test_operator(side: usize) -> (OperatorSliceInfo, Array5<f64>) {
+        let (fac0, pids0, x0, a, b) = [
+            (1.0, vec![1, 2, 21], vec![0.2, 0.5], 3, 5),
+            (2.0, vec![-2, 21], vec![0.1, 0.3, 0.6], 7, 2),
+        ][side]
+            .clone();
+        let dim = (2, 3, 3, pids0.len(), x0.len());
+
+        (
+            OperatorSliceInfo {
+                fac0,
+                pids0,
+                x0,
+                fac1: 0.0,
+                pids1: vec![-2, 2, 21],
+                x1: vec![0.1, 0.3, 0.7],
+                lumi_id_types: "pdg_mc_ids".to_string(),
+            },
+            Array5::from_shape_fn(dim, |(f, p1, x1, p0, x0)| {
+                f64::from(u8::try_from((a * f + b * p1 + 7 * x1 + 11 * p0 + 13 * x0) % 17).unwrap())
+                    - 4.0
+            }),
+        )
+    }
+
+    type OperatorSlice = (OperatorSliceInfo, CowArray<'static, f64, Ix4>);
+
+    /// Returns the operator slices of [`evolve_test_operator`] for the squared factorization
+    /// scales `fac1`, which must be given in the order of the operator.
+    fn evolve_test_slices(side: usize, fac1: &[f64]) -> Vec<Result<OperatorSlice, Infallible>> {
+        let (info, operator) = evolve_test_operator(side);
+
+        fac1.iter()
+            .enumerate()
+            .map(|(index, &fac1)| {
+                Ok((
+                    OperatorSliceInfo {
+                        fac1,
+                        ..info.clone()
+                    },
+                    CowArray::from(operator.index_axis(Axis(0), index).to_owned()),
+                ))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn evolve_with_slice_iter2() {
+        let grid = evolve_test_grid(&[0.1, 0.3, 0.7]);
+        let (infos, operators): (Vec<_>, Vec<_>) = (0..2).map(evolve_test_operator).unzip();
+        let alphas_table = AlphasTable {
+            ren1: vec![10.0, 100.0],
+            alphas: vec![0.2, 0.1],
+        };
+
+        let fk_table = grid
+            .evolve_with_slice_iter2(
+                evolve_test_slices(0, &[10.0, 100.0]),
+                evolve_test_slices(1, &[10.0, 100.0]),
+                &[],
+                (1.0, 1.0),
+                &alphas_table,
+            )
+            .unwrap();
+
+        assert_approx_eq!(f64, fk_table.muf2(), 1.0, ulps = 4);
+        let [muf2_1, muf2_2] = fk_table.muf2_per_initial_state();
+        assert_approx_eq!(f64, muf2_1, 1.0, ulps = 4);
+        assert_approx_eq!(f64, muf2_2, 2.0, ulps = 4);
+        assert_eq!(fk_table.x_grid(), [0.1, 0.2, 0.3, 0.5, 0.6]);
+
+        for subgrid in fk_table.grid().subgrids().iter().filter(|s| !s.is_empty()) {
+            assert_eq!(subgrid.x1_grid(), infos[0].x0);
+            assert_eq!(subgrid.x2_grid(), infos[1].x0);
+        }
+
+        // PDFs at the fitting scales and the corresponding evolved PDFs, both without `x`
+        let pdf0 = |side: usize, pid: i32, x: f64| {
+            f64::from(pid + 30) * x.sqrt() * if side == 0 { 1.0 } else { (1.0 - x).powi(2) }
+        };
+        let pdf1 = |side: usize, ifac: usize, pid1: i32, ix1: usize| -> f64 {
+            let info = &infos[side];
+            let p1 = info.pids1.iter().position(|&pid| pid == pid1).unwrap();
+
+            (0..info.pids0.len())
+                .cartesian_product(0..info.x0.len())
+                .map(|(p0, x0)| {
+                    operators[side][[ifac, p1, ix1, p0, x0]]
+                        * pdf0(side, info.pids0[p0], info.x0[x0])
+                })
+                .sum()
+        };
+
+        let reference: Vec<f64> = (0..2)
+            .map(|bin| {
+                let mut result = 0.0;
+
+                for (lumi, entry) in grid.lumi().iter().enumerate() {
+                    let subgrid = grid.subgrid(0, bin, lumi);
+
+                    for ((ifac, ix1, ix2), value) in subgrid.indexed_iter() {
+                        for &(a, b, factor) in entry.entry() {
+                            result += factor
+                                * alphas_table.alphas[ifac]
+                                * value
+                                * pdf1(0, ifac, a, ix1)
+                                * pdf1(1, ifac, b, ix2);
+                        }
+                    }
+                }
+
+                result
+            })
+            .collect();
+
+        let pids0 = [-2, 1, 2, 21];
+        let x_grid = fk_table.x_grid();
+        let xfx = |side| {
+            Array2::from_shape_fn((pids0.len(), x_grid.len()), |(p, x)| {
+                x_grid[x] * pdf0(side, pids0[p], x_grid[x])
+            })
+        };
+        let results = fk_table
+            .convolute_tabulated_with_two(&pids0, xfx(0).view(), &pids0, xfx(1).view(), &[], &[])
+            .unwrap();
+
+        assert_eq!(results.len(), reference.len());
+
+        for (result, reference) in results.iter().zip(&reference) {
+            assert_approx_eq!(f64, *result, *reference, ulps = 64);
+        }
+    }
+
+    #[test]
+    fn evolve_with_slice_iter2_errors() {
+        let grid = evolve_test_grid(&[0.1, 0.3, 0.7]);
+        let alphas_table = AlphasTable {
+            ren1: vec![10.0, 100.0],
+            alphas: vec![0.2, 0.1],
+        };
+
+        assert!(matches!(
+            grid.evolve_with_slice_iter2(
+                evolve_test_slices(0, &[10.0, 100.0]),
+                evolve_test_slices(1, &[10.0]),
+                &[],
+                (1.0, 1.0),
+                &alphas_table,
+            ),
+            Err(GridError::EvolutionFailure(message))
+                if message == "the number of operator slices for both initial states is different"
+        ));
+        assert!(matches!(
+            grid.evolve_with_slice_iter2(
+                evolve_test_slices(0, &[10.0, 100.0]),
+                evolve_test_slices(1, &[100.0, 10.0]),
+                &[],
+                (1.0, 1.0),
+                &alphas_table,
+            ),
+            Err(GridError::EvolutionFailure(message))
+                if message == "the operators for both initial states must be given for the same muf2, but they are given for muf2 = 10 and muf2 = 100"
+        ));
+    }
+
+    #[test]
+    fn evolve_with_slice_iter_and_basis_rotation() {
+        const X: [f64; 2] = [0.1, 0.4];
+        const PIDS: [i32; 14] = [22, -6, -5, -4, -3, -2, -1, 21, 1, 2, 3, 4, 5, 6];
+
+        let mut grid = Grid::new(
+            vec![lumi_entry![2, -2, 1.0], lumi_entry![21, 1, 0.5]],
+            vec![Order::new(0, 2, 0, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+        let mut values = (1..).map(f64::from);
+
+        for lumi in 0..2 {
+            let mut array = SparseArray3::new(1, X.len(), X.len());
+
+            for (ix1, ix2) in (0..X.len()).cartesian_product(0..X.len()) {
+                array[[0, ix1, ix2]] = values.next().unwrap();
+            }
+
+            grid.set_subgrid(
+                0,
+                0,
+                lumi,
+                ImportOnlySubgridV2::new(
+                    array,
+                    vec![Mu2 {
+                        ren: 10.0,
+                        fac: 10.0,
+                    }],
+                    X.to_vec(),
+                    X.to_vec(),
+                )
+                .into(),
+            );
+        }
+
+        let pids1 = vec![-2, 1, 2, 21];
+        let operator =
+            Array4::from_shape_fn((pids1.len(), 2, PIDS.len(), 3), |(p1, x1, p0, x0)| {
+                f64::from(u8::try_from((5 * p1 + 7 * x1 + 11 * p0 + 13 * x0) % 17).unwrap()) - 4.0
+            });
+        let info = OperatorSliceInfo {
+            fac0: 1.0,
+            pids0: PIDS.to_vec(),
+            x0: vec![0.01, 0.2, 0.5],
+            fac1: 10.0,
+            pids1,
+            x1: X.to_vec(),
+            lumi_id_types: "pdg_mc_ids".to_string(),
+        };
+        let alphas_table = AlphasTable {
+            ren1: vec![10.0],
+            alphas: vec![0.2],
+        };
+        let evolve = |info: OperatorSliceInfo, operator| {
+            grid.evolve_with_slice_iter(
+                [Ok::<_, Infallible>((info, CowArray::from(operator)))],
+                &[],
+                (1.0, 1.0),
+                &alphas_table,
+            )
+            .unwrap()
+        };
+
+        let pdg_fk_table = evolve(info.clone(), operator.clone());
+        let (evol_info, evol_operator) = BasisRotation::evol()
+            .rotate(&info, operator.view())
+            .unwrap();
+        let evol_fk_table = evolve(evol_info.clone(), evol_operator.clone());
+
+        assert_eq!(
+            evol_fk_table.grid().key_values().unwrap()["lumi_id_types"],
+            "evol"
+        );
+
+        // rotating back into the PDG basis must give the original FK table
+        let (pdg_info, pdg_operator) = BasisRotation::pdg_mc_ids()
+            .rotate(&evol_info, evol_operator.view())
+            .unwrap();
+        assert_eq!(pdg_info.pids0, PIDS);
+
+        for (lhs, rhs) in pdg_operator.iter().zip(operator.iter()) {
+            assert_approx_eq!(f64, *lhs, *rhs, epsilon = 1e-12);
+        }
+
+        // the predictions must not depend on the basis
+        let xfx = Array2::from_shape_fn((PIDS.len(), 3), |(p, x)| {
+            f64::from(u8::try_from(3 * p + x + 1).unwrap()).sqrt()
+        });
+        let evol_pids = BasisRotation::evol().pids().to_vec();
+        let evol_xfx = Array2::from_shape_fn((evol_pids.len(), 3), |(e, x)| {
+            pids::evol_to_pdg_mc_ids(evol_pids[e])
+                .into_iter()
+                .map(|(pid, factor)| {
+                    factor * xfx[[PIDS.iter().position(|&p| p == pid).unwrap(), x]]
+                })
+                .sum()
+        });
+
+        let convolute = |fk_table: &FkTable, pids: &[i32], xfx: &Array2<f64>| {
+            fk_table
+                .convolute_tabulated_with_two(pids, xfx.view(), pids, xfx.view(), &[], &[])
+                .unwrap()[0]
+        };
+
+        assert_approx_eq!(
+            f64,
+            convolute(&pdg_fk_table, &PIDS, &xfx),
+            convolute(&evol_fk_table, &evol_pids, &evol_xfx),
+            ulps = 64
+        );
+
+        assert!(matches!(
+            BasisRotation::new("custom", vec![1, 2], vec![1, 2], Array2::ones((2, 2))),
+            Err(GridError::EvolutionFailure(message)) if message == "the basis rotation is not invertible"
+        ));
+    }
+
+    #[test]
+    fn rotate_basis() {
+        let mut grid =
+            Grid::read(File::open("../pineappl_cli/data/LHCB_WP_7TEV.pineappl.lz4").unwrap())
+                .unwrap();
+        let mut xfx = |id: i32, x: f64, _: f64| {
+            // the grid uses `0` for the gluon
+            let id = if id == 0 { 21 } else { id };
+            x.powf(0.3) * (1.0 - x).powi(3) * f64::from(id + 30)
+        };
+        let mut alphas = |_| 0.118;
+        let mut convolute = |grid: &Grid| {
+            let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+            grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])
+        };
+        let reference = convolute(&grid).unwrap();
+
+        grid.rotate_basis(&BasisRotation::evol()).unwrap();
+
+        assert_eq!(grid.lumi_id_types(), "evol");
+        assert!(grid
+            .lumi()
+            .iter()
+            .flat_map(LumiEntry::entry)
+            .all(|&(a, b, _)| [a, b]
+                .iter()
+                .all(|pid| [21, 22].contains(pid) || *pid >= 100)));
+
+        for (lhs, rhs) in convolute(&grid).unwrap().iter().zip(&reference) {
+            assert_approx_eq!(f64, *lhs, *rhs, ulps = 512);
+        }
+
+        grid.rotate_basis(&BasisRotation::pdg_mc_ids()).unwrap();
+
+        assert_eq!(grid.lumi_id_types(), "pdg_mc_ids");
+
+        for (lhs, rhs) in convolute(&grid).unwrap().iter().zip(&reference) {
+            assert_approx_eq!(f64, *lhs, *rhs, ulps = 512);
+        }
+
+        grid.set_key_value("lumi_id_types", "custom");
+
+        assert!(matches!(
+            grid.rotate_basis(&BasisRotation::evol()),
+            Err(GridError::UnknownLumiIdTypes(lumi_id_types)) if lumi_id_types == "custom"
+        ));
+        assert!(matches!(
+            convolute(&grid),
+            Err(GridError::UnknownLumiIdTypes(lumi_id_types)) if lumi_id_types == "custom"
+        ));
+    }
 }
@@ -8,6 +8,8 @@
 mod convert;
 
 pub mod bin;
+#[cfg(feature = "dglap")]
+pub mod dglap;
 pub mod empty_subgrid;
 pub mod evolution;
 pub mod fk_table;
@@ -16,6 +18,7 @@ pub mod import_only_subgrid;
 pub mod lagrange_subgrid;
 pub mod lumi;
 pub mod ntuple_subgrid;
+pub mod observable;
 pub mod pids;
 pub mod sparse_array3;
 pub mod subgrid;
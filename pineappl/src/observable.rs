@@ -0,0 +1,508 @@
+//! Module containing expressions that combine the bins of a grid into new observables, for
+//! instance ratios or normalized distributions.
+
+use std::fmt::{self, Display, Formatter};
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+use thiserror::Error;
+
+/// Error type returned when parsing or validating [`Observables`].
+#[derive(Debug, Eq, Error, PartialEq)]
+pub enum ObservablesError {
+    /// Returned when the parser encounters a character that is not allowed at this position.
+    #[error("unexpected character '{character}' at position {position}")]
+    UnexpectedCharacter {
+        /// Character that was found.
+        character: char,
+        /// Position of the character in the string.
+        position: usize,
+    },
+    /// Returned when an expression ends prematurely.
+    #[error("unexpected end of expression")]
+    UnexpectedEnd,
+    /// Returned when a number or bin index could not be parsed.
+    #[error("could not parse '{0}'")]
+    InvalidNumber(String),
+    /// Returned when an expression does not reference any bin.
+    #[error("the expression '{0}' does not reference any bin")]
+    NoBinReference(String),
+    /// Returned when an expression references a bin that does not exist.
+    #[error("the observables reference bin {bin}, but there are only {bins} bins")]
+    BinOutOfRange {
+        /// Index of the bin that was referenced.
+        bin: usize,
+        /// Number of bins available.
+        bins: usize,
+    },
+}
+
+/// Arithmetic expression of bins, which is evaluated for every convolution separately.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    /// A constant number.
+    Number(f64),
+    /// The result of the bin with the given index.
+    Bin(usize),
+    /// Negation of an expression.
+    Neg(Box<Self>),
+    /// Sum of two expressions.
+    Add(Box<Self>, Box<Self>),
+    /// Difference of two expressions.
+    Sub(Box<Self>, Box<Self>),
+    /// Product of two expressions.
+    Mul(Box<Self>, Box<Self>),
+    /// Quotient of two expressions.
+    Div(Box<Self>, Box<Self>),
+}
+
+impl Expression {
+    /// Evaluates this expression, using `bins` as the results of the bins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the expression references a bin whose index is larger or equal the length of
+    /// `bins`.
+    #[must_use]
+    pub fn evaluate(&self, bins: &[f64]) -> f64 {
+        match self {
+            Self::Number(number) => *number,
+            Self::Bin(bin) => bins[*bin],
+            Self::Neg(expr) => -expr.evaluate(bins),
+            Self::Add(lhs, rhs) => lhs.evaluate(bins) + rhs.evaluate(bins),
+            Self::Sub(lhs, rhs) => lhs.evaluate(bins) - rhs.evaluate(bins),
+            Self::Mul(lhs, rhs) => lhs.evaluate(bins) * rhs.evaluate(bins),
+            Self::Div(lhs, rhs) => lhs.evaluate(bins) / rhs.evaluate(bins),
+        }
+    }
+
+    /// Returns the indices of all bins referenced by this expression, in the order in which they
+    /// appear.
+    #[must_use]
+    pub fn bins(&self) -> Vec<usize> {
+        match self {
+            Self::Number(_) => vec![],
+            Self::Bin(bin) => vec![*bin],
+            Self::Neg(expr) => expr.bins(),
+            Self::Add(lhs, rhs)
+            | Self::Sub(lhs, rhs)
+            | Self::Mul(lhs, rhs)
+            | Self::Div(lhs, rhs) => {
+                let mut bins = lhs.bins();
+                bins.extend(rhs.bins());
+                bins
+            }
+        }
+    }
+
+    /// Returns a copy of this expression in which the index of every bin is replaced by the value
+    /// returned from `map`. If `map` returns `None` for any of the bins, `None` is returned.
+    #[must_use]
+    pub fn remap_bins(&self, map: &dyn Fn(usize) -> Option<usize>) -> Option<Self> {
+        let remap = |expr: &Self| expr.remap_bins(map).map(Box::new);
+
+        Some(match self {
+            Self::Number(number) => Self::Number(*number),
+            Self::Bin(bin) => Self::Bin(map(*bin)?),
+            Self::Neg(expr) => Self::Neg(remap(expr)?),
+            Self::Add(lhs, rhs) => Self::Add(remap(lhs)?, remap(rhs)?),
+            Self::Sub(lhs, rhs) => Self::Sub(remap(lhs)?, remap(rhs)?),
+            Self::Mul(lhs, rhs) => Self::Mul(remap(lhs)?, remap(rhs)?),
+            Self::Div(lhs, rhs) => Self::Div(remap(lhs)?, remap(rhs)?),
+        })
+    }
+
+    const fn is_sum(&self) -> bool {
+        matches!(self, Self::Add(..) | Self::Sub(..))
+    }
+
+    const fn is_binary(&self) -> bool {
+        matches!(
+            self,
+            Self::Add(..) | Self::Sub(..) | Self::Mul(..) | Self::Div(..)
+        )
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let wrap = |f: &mut Formatter, expr: &Self, parens: bool| {
+            if parens {
+                write!(f, "({expr})")
+            } else {
+                write!(f, "{expr}")
+            }
+        };
+
+        match self {
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Bin(bin) => write!(f, "b{bin}"),
+            Self::Neg(expr) => {
+                write!(f, "-")?;
+                wrap(f, expr, expr.is_binary())
+            }
+            Self::Add(lhs, rhs) => {
+                write!(f, "{lhs} + ")?;
+                wrap(f, rhs, rhs.is_sum())
+            }
+            Self::Sub(lhs, rhs) => {
+                write!(f, "{lhs} - ")?;
+                wrap(f, rhs, rhs.is_sum())
+            }
+            Self::Mul(lhs, rhs) => {
+                wrap(f, lhs, lhs.is_sum())?;
+                write!(f, " * ")?;
+                wrap(f, rhs, rhs.is_binary())
+            }
+            Self::Div(lhs, rhs) => {
+                wrap(f, lhs, lhs.is_sum())?;
+                write!(f, " / ")?;
+                wrap(f, rhs, rhs.is_binary())
+            }
+        }
+    }
+}
+
+/// A list of observables, each one given by an [`Expression`] of the bins of a grid.
+///
+/// Their string representation separates the expressions with semicolons and every expression may
+/// use numbers, bins written as `b0`, `b1`, ..., the operators `+`, `-`, `*`, `/` and parentheses.
+/// For instance the ratio of the first two bins and the first bin normalized to the sum of the
+/// first three bins are written as `b0 / b1; b0 / (b0 + b1 + b2)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observables(Vec<Expression>);
+
+impl Observables {
+    /// Constructor.
+    #[must_use]
+    pub const fn new(expressions: Vec<Expression>) -> Self {
+        Self(expressions)
+    }
+
+    /// Returns the expressions of all observables.
+    #[must_use]
+    pub fn expressions(&self) -> &[Expression] {
+        &self.0
+    }
+
+    /// Returns the number of observables.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no observables.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// For each observable return the index of the first bin it references. The limits of this
+    /// bin are used as the limits of the observable.
+    #[must_use]
+    pub fn reference_bins(&self) -> Vec<usize> {
+        self.0
+            .iter()
+            .map(|expr| expr.bins().first().copied().unwrap_or_default())
+            .collect()
+    }
+
+    /// Checks that all observables only reference bins whose indices are smaller than `bins`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObservablesError::BinOutOfRange`] if a bin index is too large.
+    pub fn validate(&self, bins: usize) -> Result<(), ObservablesError> {
+        self.0
+            .iter()
+            .flat_map(Expression::bins)
+            .find(|&bin| bin >= bins)
+            .map_or(Ok(()), |bin| {
+                Err(ObservablesError::BinOutOfRange { bin, bins })
+            })
+    }
+
+    /// Returns the observables with the bin indices replaced by the values returned from `map`,
+    /// which must return `None` for bins that no longer exist. Observables referencing such a bin
+    /// are removed.
+    #[must_use]
+    pub fn remap_bins(&self, map: impl Fn(usize) -> Option<usize>) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter_map(|expr| expr.remap_bins(&map))
+                .collect(),
+        )
+    }
+
+    /// Evaluates all observables. The parameter `results` must contain the results of all bins,
+    /// with `scales` consecutive values for each bin, as returned by
+    /// [`Grid::convolute`](super::grid::Grid::convolute). The returned vector has the same layout,
+    /// with the bins replaced by the observables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an observable references a bin that is not contained in `results`.
+    #[must_use]
+    pub fn evaluate(&self, results: &[f64], scales: usize) -> Vec<f64> {
+        let bins: Vec<Vec<_>> = (0..scales)
+            .map(|scale| {
+                results
+                    .iter()
+                    .skip(scale)
+                    .step_by(scales)
+                    .copied()
+                    .collect()
+            })
+            .collect();
+
+        self.0
+            .iter()
+            .flat_map(|expr| bins.iter().map(|values| expr.evaluate(values)))
+            .collect()
+    }
+}
+
+impl Display for Observables {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (index, expr) in self.0.iter().enumerate() {
+            if index != 0 {
+                write!(f, "; ")?;
+            }
+
+            write!(f, "{expr}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Observables {
+    type Err = ObservablesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut offset = 0;
+        let mut expressions = Vec::new();
+
+        for string in s.split(';') {
+            let mut parser = Parser {
+                chars: string.char_indices().peekable(),
+                offset,
+            };
+            let expr = parser.expr()?;
+
+            if let Some((position, character)) = parser.next() {
+                return Err(ObservablesError::UnexpectedCharacter {
+                    character,
+                    position: offset + position,
+                });
+            }
+
+            if expr.bins().is_empty() {
+                return Err(ObservablesError::NoBinReference(string.trim().to_string()));
+            }
+
+            expressions.push(expr);
+            offset += string.len() + 1;
+        }
+
+        Ok(Self(expressions))
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    offset: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, ch)| ch.is_whitespace()).is_some() {}
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().map(|&(_, ch)| ch)
+    }
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        self.skip_whitespace();
+        self.chars.next()
+    }
+
+    fn expr(&mut self) -> Result<Expression, ObservablesError> {
+        let mut lhs = self.term()?;
+
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.next();
+            let rhs = Box::new(self.term()?);
+            lhs = if op == '+' {
+                Expression::Add(Box::new(lhs), rhs)
+            } else {
+                Expression::Sub(Box::new(lhs), rhs)
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expression, ObservablesError> {
+        let mut lhs = self.factor()?;
+
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.next();
+            let rhs = Box::new(self.factor()?);
+            lhs = if op == '*' {
+                Expression::Mul(Box::new(lhs), rhs)
+            } else {
+                Expression::Div(Box::new(lhs), rhs)
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn factor(&mut self) -> Result<Expression, ObservablesError> {
+        match self.next() {
+            Some((_, '-')) => Ok(Expression::Neg(Box::new(self.factor()?))),
+            Some((_, '(')) => {
+                let expr = self.expr()?;
+
+                match self.next() {
+                    Some((_, ')')) => Ok(expr),
+                    Some((position, character)) => Err(ObservablesError::UnexpectedCharacter {
+                        character,
+                        position: self.offset + position,
+                    }),
+                    None => Err(ObservablesError::UnexpectedEnd),
+                }
+            }
+            Some((_, 'b')) => {
+                let index = self.number_string();
+                index
+                    .parse()
+                    .map(Expression::Bin)
+                    .map_err(|_| ObservablesError::InvalidNumber(format!("b{index}")))
+            }
+            Some((_, ch)) if ch.is_ascii_digit() || (ch == '.') => {
+                let number = ch.to_string() + &self.number_string();
+                number
+                    .parse()
+                    .map(Expression::Number)
+                    .map_err(|_| ObservablesError::InvalidNumber(number))
+            }
+            Some((position, character)) => Err(ObservablesError::UnexpectedCharacter {
+                character,
+                position: self.offset + position,
+            }),
+            None => Err(ObservablesError::UnexpectedEnd),
+        }
+    }
+
+    fn number_string(&mut self) -> String {
+        let mut string = String::new();
+
+        while let Some((_, ch)) = self.chars.next_if(|&(_, ch)| {
+            ch.is_ascii_digit()
+                || (ch == '.')
+                || (ch == 'e')
+                || (ch == 'E')
+                || (matches!(ch, '+' | '-') && string.ends_with(['e', 'E']))
+        }) {
+            string.push(ch);
+        }
+
+        string
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observables_parse() {
+        let observables: Observables = "b0 / b1; b0/(b0+b1 + b2) ; -2.5e-1 * (b3 - b2)"
+            .parse()
+            .unwrap();
+
+        assert_eq!(observables.len(), 3);
+        assert_eq!(
+            observables.expressions()[0],
+            Expression::Div(Box::new(Expression::Bin(0)), Box::new(Expression::Bin(1)))
+        );
+        assert_eq!(
+            observables.to_string(),
+            "b0 / b1; b0 / (b0 + b1 + b2); -0.25 * (b3 - b2)"
+        );
+        assert_eq!(observables.reference_bins(), [0, 0, 3]);
+        assert_eq!(
+            observables.to_string().parse::<Observables>().unwrap(),
+            observables
+        );
+
+        assert_eq!(
+            "b0 - (b1 - b2)".parse::<Observables>().unwrap().to_string(),
+            "b0 - (b1 - b2)"
+        );
+        assert_eq!(
+            "b0 / (b1 * b2)".parse::<Observables>().unwrap().to_string(),
+            "b0 / (b1 * b2)"
+        );
+    }
+
+    #[test]
+    fn observables_parse_errors() {
+        assert_eq!(
+            "b0 / ".parse::<Observables>(),
+            Err(ObservablesError::UnexpectedEnd)
+        );
+        assert_eq!(
+            "b0; b1 $ b2".parse::<Observables>(),
+            Err(ObservablesError::UnexpectedCharacter {
+                character: '$',
+                position: 7
+            })
+        );
+        assert_eq!(
+            "bx".parse::<Observables>(),
+            Err(ObservablesError::InvalidNumber("b".to_string()))
+        );
+        assert_eq!(
+            "1.0 / 2.0".parse::<Observables>(),
+            Err(ObservablesError::NoBinReference("1.0 / 2.0".to_string()))
+        );
+        assert_eq!(
+            "b0 / b3".parse::<Observables>().unwrap().validate(3),
+            Err(ObservablesError::BinOutOfRange { bin: 3, bins: 3 })
+        );
+    }
+
+    #[test]
+    fn observables_remap_bins() {
+        let observables: Observables = "b1 / b0; -(b0 - b2) * 2; b3".parse().unwrap();
+
+        assert_eq!(
+            observables.remap_bins(|bin| Some(3 - bin)).to_string(),
+            "b2 / b3; -(b3 - b1) * 2; b0"
+        );
+        assert_eq!(
+            observables
+                .remap_bins(|bin| (bin != 1).then_some(bin))
+                .to_string(),
+            "-(b0 - b2) * 2; b3"
+        );
+        assert!(observables.remap_bins(|_| None).is_empty());
+    }
+
+    #[test]
+    fn observables_evaluate() {
+        let observables: Observables = "b1 / b0; (b0 - b1) / (b0 + b1)".parse().unwrap();
+        let results = [1.0, 2.0, 3.0, 4.0];
+
+        assert_eq!(observables.evaluate(&results, 1), [2.0, -1.0 / 3.0]);
+        assert_eq!(
+            observables.evaluate(&results, 2),
+            [3.0, 2.0, -0.5, -1.0 / 3.0]
+        );
+    }
+}
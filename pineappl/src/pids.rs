@@ -126,6 +126,41 @@ pub fn evol_to_pdg_mc_ids(id: i32) -> Vec<(i32, f64)> {
     }
 }
 
+/// Translates PDG Monte Carlo IDs into IDs of the evolution basis. This is the inverse of
+/// [`evol_to_pdg_mc_ids`], assuming that all six quark flavours are present.
+#[must_use]
+pub fn pdg_mc_ids_to_evol(id: i32) -> Vec<(i32, f64)> {
+    // quarks in the order in which they appear in the non-singlet combinations
+    const QUARKS: [i32; 6] = [2, 1, 3, 4, 5, 6];
+
+    (1..)
+        .zip(QUARKS)
+        .find_map(|(m, quark)| (quark == id.abs()).then_some(m))
+        .map_or_else(
+            || vec![(id, 1.0)],
+            |m| {
+                // the (anti-)quark is half the sum (difference) of the plus and minus combinations
+                let sign = if id > 0 { 0.5 } else { -0.5 };
+                let mut result = vec![(100, 1.0 / 12.0), (200, sign / 6.0)];
+
+                // the `n`th non-singlet combination contains the first `n - 1` quarks with
+                // coefficient one and the `n`th quark with coefficient `1 - n`
+                for n in m.max(2)..=6 {
+                    let factor = if m == n {
+                        -1.0 / f64::from(n)
+                    } else {
+                        1.0 / f64::from(n * (n - 1))
+                    };
+
+                    result.push((99 + n * n, 0.5 * factor));
+                    result.push((199 + n * n, sign * factor));
+                }
+
+                result
+            },
+        )
+}
+
 /// Return the charge-conjugated PDG ID of `pid`.
 #[must_use]
 pub const fn charge_conjugate_pdg_pid(pid: i32) -> i32 {
@@ -136,18 +171,15 @@ pub const fn charge_conjugate_pdg_pid(pid: i32) -> i32 {
 }
 
 /// Return the charge-conjugated particle ID of `pid` for the basis `lumi_id_types`. The returned
-/// tuple contains a factor that possible arises during the carge conjugation.
-///
-/// # Panics
-///
-/// TODO
+/// tuple contains a factor that possible arises during the carge conjugation. If the basis isn't
+/// `pdg_mc_ids` or `evol`, `None` is returned.
 #[must_use]
-pub fn charge_conjugate(lumi_id_types: &str, pid: i32) -> (i32, f64) {
+pub fn charge_conjugate(lumi_id_types: &str, pid: i32) -> Option<(i32, f64)> {
     match (lumi_id_types, pid) {
-        ("evol", 100 | 103 | 108 | 115 | 124 | 135) => (pid, 1.0),
-        ("evol", 200 | 203 | 208 | 215 | 224 | 235) => (pid, -1.0),
-        ("evol" | "pdg_mc_ids", _) => (charge_conjugate_pdg_pid(pid), 1.0),
-        _ => todo!(),
+        ("evol", 100 | 103 | 108 | 115 | 124 | 135) => Some((pid, 1.0)),
+        ("evol", 200 | 203 | 208 | 215 | 224 | 235) => Some((pid, -1.0)),
+        ("evol" | "pdg_mc_ids", _) => Some((charge_conjugate_pdg_pid(pid), 1.0)),
+        _ => None,
     }
 }
 
@@ -341,4 +373,30 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn inverse_of_evol_to_pdg_mc_ids() {
+        let pids = [22, -6, -5, -4, -3, -2, -1, 21, 1, 2, 3, 4, 5, 6];
+
+        for pid in pids {
+            for other in pids {
+                let coefficient: f64 = pdg_mc_ids_to_evol(pid)
+                    .into_iter()
+                    .flat_map(|(evol, factor)| {
+                        evol_to_pdg_mc_ids(evol)
+                            .into_iter()
+                            .filter(|&(pdg, _)| pdg == other)
+                            .map(move |(_, f)| factor * f)
+                    })
+                    .sum();
+
+                let expected = if pid == other { 1.0 } else { 0.0 };
+                assert!((coefficient - expected).abs() < 1e-15);
+            }
+        }
+
+        // check the gluon and photon
+        assert_eq!(pdg_mc_ids_to_evol(21), [(21, 1.0)]);
+        assert_eq!(pdg_mc_ids_to_evol(22), [(22, 1.0)]);
+    }
 }
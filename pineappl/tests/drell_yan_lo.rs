@@ -83,6 +83,7 @@ fn fill_drell_yan_lo_grid(
         alpha: 2,
         logxir: 0,
         logxif: 0,
+        logxia: 0,
     }];
 
     // we bin in rapidity from 0 to 2.4 in steps of 0.1
@@ -208,7 +209,7 @@ fn perform_grid_tests(
 
     // TEST 5: `convolute`
     let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
-    let bins = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+    let bins = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])?;
 
     for (result, reference) in bins.iter().zip(reference.iter()) {
         assert_approx_eq!(f64, *result, *reference, ulps = 16);
@@ -219,7 +220,7 @@ fn perform_grid_tests(
     let mut xfx2 = |id, x, q2| pdf.xfx_q2(id, x, q2);
     let mut alphas2 = |_| 0.0;
     let mut lumi_cache2 = LumiCache::with_two(2212, &mut xfx1, 2212, &mut xfx2, &mut alphas2);
-    let bins2 = grid.convolute(&mut lumi_cache2, &[], &[], &[], &[(1.0, 1.0)]);
+    let bins2 = grid.convolute(&mut lumi_cache2, &[], &[], &[], &[(1.0, 1.0)])?;
 
     for (result, reference) in bins2.iter().zip(reference.iter()) {
         assert_approx_eq!(f64, *result, *reference, ulps = 16);
@@ -231,10 +232,11 @@ fn perform_grid_tests(
     // TEST 6: `convolute_subgrid`
     let bins: Vec<_> = (0..grid.bin_info().bins())
         .map(|bin| {
-            grid.convolute_subgrid(&mut lumi_cache, 0, bin, 0, 1.0, 1.0)
-                .sum()
+            Ok(grid
+                .convolute_subgrid(&mut lumi_cache, 0, bin, 0, 1.0, 1.0)?
+                .sum())
         })
-        .collect();
+        .collect::<Result<_>>()?;
 
     for (result, reference) in bins.iter().zip(reference.iter()) {
         assert_approx_eq!(f64, *result, *reference, ulps = 24);
@@ -249,16 +251,17 @@ fn perform_grid_tests(
     // TEST 8: `convolute_subgrid` for the optimized subgrids
     let bins: Vec<_> = (0..grid.bin_info().bins())
         .map(|bin| {
-            grid.convolute_subgrid(&mut lumi_cache, 0, bin, 0, 1.0, 1.0)
-                .sum()
+            Ok(grid
+                .convolute_subgrid(&mut lumi_cache, 0, bin, 0, 1.0, 1.0)?
+                .sum())
         })
-        .collect();
+        .collect::<Result<_>>()?;
 
     for (result, reference_after_ssd) in bins.iter().zip(reference_after_ssd.iter()) {
         assert_approx_eq!(f64, *result, *reference_after_ssd, ulps = 24);
     }
 
-    let bins = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+    let bins = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])?;
 
     for (result, reference_after_ssd) in bins.iter().zip(reference_after_ssd.iter()) {
         assert_approx_eq!(f64, *result, *reference_after_ssd, ulps = 24);
@@ -291,7 +294,7 @@ fn perform_grid_tests(
         grid.merge_bins(bin..bin + 2)?;
     }
 
-    let merged2 = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+    let merged2 = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])?;
 
     for (result, reference_after_ssd) in merged2.iter().zip(
         reference_after_ssd
@@ -306,7 +309,7 @@ fn perform_grid_tests(
     // delete a few bins from the start
     grid.delete_bins(&[0, 1]);
 
-    let deleted = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+    let deleted = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])?;
 
     assert_eq!(deleted.len(), 10);
 
@@ -322,7 +325,7 @@ fn perform_grid_tests(
     // delete a few bins from the ending
     grid.delete_bins(&[8, 9]);
 
-    let deleted2 = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+    let deleted2 = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])?;
 
     assert_eq!(deleted2.len(), 8);
 
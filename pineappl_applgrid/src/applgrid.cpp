@@ -86,6 +86,80 @@ std::unique_ptr<appl::grid> make_grid(rust::Str filename)
     return std::unique_ptr<appl::grid>(new appl::grid(name));
 }
 
+std::unique_ptr<appl::grid> make_empty_grid(
+    rust::Slice<double const> obs,
+    rust::Str genpdf,
+    int leading_order,
+    int nloops,
+    rust::Str transform,
+    rust::Str qtransform,
+    bool is_dis
+) {
+    // the interpolation grids created here are replaced with `grid_add_igrid`
+    return std::unique_ptr<appl::grid>(new appl::grid(
+        std::vector<double>(obs.begin(), obs.end()),
+        2,
+        10.0,
+        100.0,
+        1,
+        2,
+        0.1,
+        0.9,
+        1,
+        std::string(genpdf.begin(), genpdf.end()),
+        leading_order,
+        nloops,
+        std::string(transform.begin(), transform.end()),
+        std::string(qtransform.begin(), qtransform.end()),
+        is_dis
+    ));
+}
+
+std::unique_ptr<appl::igrid> make_igrid(
+    int NQ2,
+    double Q2min,
+    double Q2max,
+    int Q2order,
+    int Nx,
+    double xmin,
+    double xmax,
+    int xorder,
+    rust::Str transform,
+    rust::Str qtransform,
+    int Nproc,
+    bool disflag
+) {
+    return std::unique_ptr<appl::igrid>(new appl::igrid(
+        NQ2,
+        Q2min,
+        Q2max,
+        Q2order,
+        Nx,
+        xmin,
+        xmax,
+        xorder,
+        std::string(transform.begin(), transform.end()),
+        std::string(qtransform.begin(), qtransform.end()),
+        Nproc,
+        disflag
+    ));
+}
+
+void make_lumi_pdf(rust::Str s, rust::Slice<int const> combinations)
+{
+    // the object registers itself with APPLgrid under the name `s`, which manages its lifetime
+    new lumi_pdf(
+        std::string(s.begin(), s.end()),
+        std::vector<int>(combinations.begin(), combinations.end())
+    );
+}
+
+void grid_add_igrid(appl::grid& grid, int bin, int order, std::unique_ptr<appl::igrid> igrid)
+{
+    // `grid` takes the ownership of `igrid`
+    grid.add_igrid(bin, order, igrid.release());
+}
+
 rust::Vec<int> grid_combine(appl::grid const& grid)
 {
     return std_vector_to_rust_vec(grid.combine());
@@ -107,11 +181,26 @@ rust::Vec<double> grid_convolute(
     return std_vector_to_rust_vec(results);
 }
 
+void grid_write(appl::grid& grid, rust::Str filename)
+{
+    grid.Write(std::string(filename.begin(), filename.end()));
+}
+
+SparseMatrix3d& igrid_weightgrid(appl::igrid& igrid, std::size_t lumi)
+{
+    return *igrid.weightgrid()[lumi];
+}
+
 double sparse_matrix_get(SparseMatrix3d const& matrix, int x, int y, int z)
 {
     return matrix(x, y, z);
 }
 
+void sparse_matrix_set(SparseMatrix3d& matrix, int x, int y, int z, double value)
+{
+    matrix(x, y, z) = value;
+}
+
 double weightfun(double x)
 {
     return appl::igrid::weightfun(x);
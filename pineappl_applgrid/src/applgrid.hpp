@@ -7,10 +7,40 @@
 #include <appl_grid/appl_grid.h>
 #include <appl_grid/lumi_pdf.h>
 #include <appl_igrid.h>
+#include <cstddef>
 #include <memory>
 
 std::unique_ptr<appl::grid> make_grid(rust::Str filename);
 
+std::unique_ptr<appl::grid> make_empty_grid(
+    rust::Slice<double const> obs,
+    rust::Str genpdf,
+    int leading_order,
+    int nloops,
+    rust::Str transform,
+    rust::Str qtransform,
+    bool is_dis
+);
+
+std::unique_ptr<appl::igrid> make_igrid(
+    int NQ2,
+    double Q2min,
+    double Q2max,
+    int Q2order,
+    int Nx,
+    double xmin,
+    double xmax,
+    int xorder,
+    rust::Str transform,
+    rust::Str qtransform,
+    int Nproc,
+    bool disflag
+);
+
+void make_lumi_pdf(rust::Str s, rust::Slice<int const> combinations);
+
+void grid_add_igrid(appl::grid& grid, int bin, int order, std::unique_ptr<appl::igrid> igrid);
+
 rust::Vec<int> grid_combine(appl::grid const& grid);
 
 rust::Vec<double> grid_convolute(
@@ -23,8 +53,14 @@ rust::Vec<double> grid_convolute(
     double escale
 );
 
+void grid_write(appl::grid& grid, rust::Str filename);
+
+SparseMatrix3d& igrid_weightgrid(appl::igrid& igrid, std::size_t lumi);
+
 double sparse_matrix_get(SparseMatrix3d const& matrix, int x, int y, int z);
 
+void sparse_matrix_set(SparseMatrix3d& matrix, int x, int y, int z, double value);
+
 double weightfun(double x);
 
 bool igrid_m_reweight(appl::igrid const& igrid);
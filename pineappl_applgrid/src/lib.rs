@@ -1,3 +1,4 @@
+#[allow(clippy::too_many_arguments)]
 #[cxx::bridge]
 pub mod ffi {
     #[repr(u32)]
@@ -68,6 +69,32 @@ pub mod ffi {
         include!("pineappl_applgrid/src/applgrid.hpp");
 
         fn make_grid(_: &str) -> Result<UniquePtr<grid>>;
+        fn make_empty_grid(
+            _: &[f64],
+            _: &str,
+            _: i32,
+            _: i32,
+            _: &str,
+            _: &str,
+            _: bool,
+        ) -> Result<UniquePtr<grid>>;
+        fn make_igrid(
+            _: i32,
+            _: f64,
+            _: f64,
+            _: i32,
+            _: i32,
+            _: f64,
+            _: f64,
+            _: i32,
+            _: &str,
+            _: &str,
+            _: i32,
+            _: bool,
+        ) -> Result<UniquePtr<igrid>>;
+        fn make_lumi_pdf(_: &str, _: &[i32]) -> Result<()>;
+
+        fn grid_add_igrid(_: Pin<&mut grid>, _: i32, _: i32, _: UniquePtr<igrid>);
 
         fn grid_combine(_: &grid) -> Vec<i32>;
         fn grid_convolute(
@@ -80,7 +107,12 @@ pub mod ffi {
             _: f64,
         ) -> Vec<f64>;
 
+        fn grid_write(_: Pin<&mut grid>, _: &str) -> Result<()>;
+
+        fn igrid_weightgrid(_: Pin<&mut igrid>, _: usize) -> Pin<&mut SparseMatrix3d>;
+
         fn sparse_matrix_get(_: &SparseMatrix3d, _: i32, _: i32, _: i32) -> f64;
+        fn sparse_matrix_set(_: Pin<&mut SparseMatrix3d>, _: i32, _: i32, _: i32, _: f64);
 
         // TODO: class member functions aren't supported yet by cxx, see
         // https://github.com/dtolnay/cxx/issues/447
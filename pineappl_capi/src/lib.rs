@@ -59,8 +59,10 @@
 //! [translation tables]: https://github.com/eqrion/cbindgen/blob/master/docs.md#std-types
 
 use itertools::izip;
+use ndarray::ArrayView2;
 use pineappl::bin::BinRemapper;
 use pineappl::empty_subgrid::EmptySubgridV1;
+use pineappl::fk_table::FkTable;
 use pineappl::grid::{Grid, Ntuple, Order};
 use pineappl::import_only_subgrid::ImportOnlySubgridV2;
 use pineappl::lumi::{LumiCache, LumiEntry};
@@ -300,7 +302,8 @@ pub unsafe extern "C" fn pineappl_grid_clone(grid: *const Grid) -> Box<Grid> {
 /// luminosity function `grid` was created with has entries, or `NULL` to enable all luminosities.
 /// The values `xi_ren` and `xi_fac` can be used to vary the renormalization and factorization from
 /// its central value, which corresponds to `1.0`. After convolution of the grid with the PDFs the
-/// differential cross section for each bin is written into `results`.
+/// differential cross section for each bin is written into `results`. Returns `false` without
+/// changing `results` if the particle basis of `grid` isn't supported, and `true` otherwise.
 ///
 /// # Safety
 ///
@@ -321,7 +324,7 @@ pub unsafe extern "C" fn pineappl_grid_convolute_with_one(
     xi_ren: f64,
     xi_fac: f64,
     results: *mut f64,
-) {
+) -> bool {
     let grid = &*grid;
     let mut pdf = |id, x, q2| xfx(id, x, q2, state);
     let mut als = |q2| alphas(q2, state);
@@ -338,13 +341,15 @@ pub unsafe extern "C" fn pineappl_grid_convolute_with_one(
     let results = slice::from_raw_parts_mut(results, grid.bin_info().bins());
     let mut lumi_cache = LumiCache::with_one(pdg_id, &mut pdf, &mut als);
 
-    results.copy_from_slice(&grid.convolute(
+    grid.convolute(
         &mut lumi_cache,
         &order_mask,
         &[],
         &lumi_mask,
         &[(xi_ren, xi_fac)],
-    ));
+    )
+    .map(|bins| results.copy_from_slice(&bins))
+    .is_ok()
 }
 
 /// Convolutes the specified grid with the PDFs `xfx1` and `xfx2`, which are the PDFs of hadrons
@@ -359,7 +364,8 @@ pub unsafe extern "C" fn pineappl_grid_convolute_with_one(
 /// created with has entries, or `NULL` to enable all luminosities. The values `xi_ren` and
 /// `xi_fac` can be used to vary the renormalization and factorization from its central value,
 /// which corresponds to `1.0`. After convolution of the grid with the PDFs the differential cross
-/// section for each bin is written into `results`.
+/// section for each bin is written into `results`. Returns `false` without changing `results` if
+/// the particle basis of `grid` isn't supported, and `true` otherwise.
 ///
 /// # Safety
 ///
@@ -382,7 +388,7 @@ pub unsafe extern "C" fn pineappl_grid_convolute_with_two(
     xi_ren: f64,
     xi_fac: f64,
     results: *mut f64,
-) {
+) -> bool {
     let grid = &*grid;
     let mut pdf1 = |id, x, q2| xfx1(id, x, q2, state);
     let mut pdf2 = |id, x, q2| xfx2(id, x, q2, state);
@@ -400,13 +406,15 @@ pub unsafe extern "C" fn pineappl_grid_convolute_with_two(
     let results = slice::from_raw_parts_mut(results, grid.bin_info().bins());
     let mut lumi_cache = LumiCache::with_two(pdg_id1, &mut pdf1, pdg_id2, &mut pdf2, &mut als);
 
-    results.copy_from_slice(&grid.convolute(
+    grid.convolute(
         &mut lumi_cache,
         &order_mask,
         &[],
         &lumi_mask,
         &[(xi_ren, xi_fac)],
-    ));
+    )
+    .map(|bins| results.copy_from_slice(&bins))
+    .is_ok()
 }
 
 /// Delete a grid previously created with `pineappl_grid_new`.
@@ -414,6 +422,37 @@ pub unsafe extern "C" fn pineappl_grid_convolute_with_two(
 #[allow(unused_variables)]
 pub extern "C" fn pineappl_grid_delete(grid: Option<Box<Grid>>) {}
 
+/// Evaluates the observables defined for `grid` and writes them into `results`. Returns `false`
+/// without changing `results` if `grid` does not define valid observables, and `true` otherwise.
+///
+/// The array `bin_results` must contain the results of all bins, for instance computed with
+/// `pineappl_grid_convolute_with_one` with an empty bin mask.
+///
+/// # Safety
+///
+/// If `grid` does not point to a valid `Grid` object, for example when `grid` is the null pointer,
+/// this function is not safe to call. The array `bin_results` must be as long as `grid` has bins
+/// and `results` as long as the value returned by `pineappl_grid_observables_count`.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_grid_evaluate_observables(
+    grid: *const Grid,
+    bin_results: *const f64,
+    results: *mut f64,
+) -> bool {
+    let grid = &*grid;
+
+    if let Ok(Some(observables)) = grid.observables() {
+        let bin_results = slice::from_raw_parts(bin_results, grid.bin_info().bins());
+        let results = slice::from_raw_parts_mut(results, observables.len());
+
+        results.copy_from_slice(&observables.evaluate(bin_results, 1));
+
+        true
+    } else {
+        false
+    }
+}
+
 /// Fill `grid` for the given momentum fractions `x1` and `x2`, at the scale `q2` for the given
 /// value of the `order`, `observable`, and `lumi` with `weight`.
 ///
@@ -435,6 +474,36 @@ pub unsafe extern "C" fn pineappl_grid_fill(
     (*grid).fill(order, observable, lumi, &Ntuple { x1, x2, q2, weight });
 }
 
+/// Fill `grid` for the given momentum fractions `x1` and `x2`, at the scale `q2` for the given
+/// value of the `order`, `lumi`, and the multi-dimensional observable `observables` with
+/// `weight`.
+///
+/// The bin is determined from the limits of the remapper, see `pineappl_grid_set_remapper`, or
+/// from the one-dimensional bin limits if the grid has no remapper. If no bin contains
+/// `observables` the grid is not changed.
+///
+/// # Safety
+///
+/// If `grid` does not point to a valid `Grid` object, for example when `grid` is the null pointer,
+/// this function is not safe to call. The parameter `observables` must be an array with as many
+/// entries as the bins of `grid` have dimensions.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_grid_fill_nd(
+    grid: *mut Grid,
+    x1: f64,
+    x2: f64,
+    q2: f64,
+    order: usize,
+    observables: *const f64,
+    lumi: usize,
+    weight: f64,
+) {
+    let grid = &mut *grid;
+    let observables = slice::from_raw_parts(observables, grid.bin_info().dimensions());
+
+    grid.fill_nd(order, observables, lumi, &Ntuple { x1, x2, q2, weight });
+}
+
 /// Fill `grid` for the given momentum fractions `x1` and `x2`, at the scale `q2` for the given
 /// value of the `order` and `observable` with `weights`. The parameter of weight must contain a
 /// result for entry of the luminosity function the grid was created with.
@@ -533,6 +602,28 @@ pub unsafe extern "C" fn pineappl_grid_order_params(grid: *const Grid, order_par
     }
 }
 
+/// Write the order parameters of `grid` into `order_params`, including the power of the logarithm
+/// of the additional scale `logxia`.
+///
+/// # Safety
+///
+/// If `grid` does not point to a valid `Grid` object, for example when `grid` is the null pointer,
+/// this function is not safe to call. The pointer `order_params` must point to an array as large
+/// as five times the number of orders in `grid`.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_grid_order_params2(grid: *const Grid, order_params: *mut u32) {
+    let orders = (*grid).orders();
+    let order_params = slice::from_raw_parts_mut(order_params, 5 * orders.len());
+
+    for (i, order) in orders.iter().enumerate() {
+        order_params[5 * i] = order.alphas;
+        order_params[5 * i + 1] = order.alpha;
+        order_params[5 * i + 2] = order.logxir;
+        order_params[5 * i + 3] = order.logxif;
+        order_params[5 * i + 4] = order.logxia;
+    }
+}
+
 /// Return the number of orders in `grid`.
 ///
 /// # Safety
@@ -545,6 +636,23 @@ pub unsafe extern "C" fn pineappl_grid_order_count(grid: *const Grid) -> usize {
     (*grid).orders().len()
 }
 
+/// Return the number of observables defined for `grid`, which is zero if there are none or if they
+/// are not valid.
+///
+/// # Safety
+///
+/// If `grid` does not point to a valid `Grid` object, for example when `grid` is the null pointer,
+/// this function is not safe to call.
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_grid_observables_count(grid: *const Grid) -> usize {
+    (*grid)
+        .observables()
+        .ok()
+        .flatten()
+        .map_or(0, |observables| observables.len())
+}
+
 /// Creates a new and empty grid. The creation requires four different sets of parameters:
 /// - The luminosity function `lumi`: A pointer to the luminosity function that specifies how the
 /// cross section should be reconstructed.
@@ -587,9 +695,59 @@ pub unsafe extern "C" fn pineappl_grid_new(
             alpha: s[1],
             logxir: s[2],
             logxif: s[3],
+            logxia: 0,
         })
         .collect();
 
+    grid_new(lumi, orders, bins, bin_limits, key_vals)
+}
+
+/// Creates a new and empty grid, like [`pineappl_grid_new`], but with orders including `logxia`.
+///
+/// The array `order_params` must contain 5 integers for each order. The fifth integer is the
+/// exponent of the logarithm of the additional scale, for instance a fragmentation scale.
+///
+/// # Safety
+///
+/// The parameter `lumi` must point a valid luminosity function created by `pineappl_lumi_new`.
+/// `order_params` must be an array with a length of `5 * orders`, and `bin_limits` an array with
+/// length `bins + 1`. `key_vals` must be a valid `KeyVal` object created by `pineappl_keyval_new`.
+///
+/// # Panics
+///
+/// TODO
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_grid_new2(
+    lumi: *const Lumi,
+    orders: usize,
+    order_params: *const u32,
+    bins: usize,
+    bin_limits: *const f64,
+    key_vals: *const KeyVal,
+) -> Box<Grid> {
+    let order_params = slice::from_raw_parts(order_params, 5 * orders);
+    let orders: Vec<_> = order_params
+        .chunks(5)
+        .map(|s| Order {
+            alphas: s[0],
+            alpha: s[1],
+            logxir: s[2],
+            logxif: s[3],
+            logxia: s[4],
+        })
+        .collect();
+
+    grid_new(lumi, orders, bins, bin_limits, key_vals)
+}
+
+unsafe fn grid_new(
+    lumi: *const Lumi,
+    orders: Vec<Order>,
+    bins: usize,
+    bin_limits: *const f64,
+    key_vals: *const KeyVal,
+) -> Box<Grid> {
     let (subgrid_type, subgrid_params, extra) = grid_params(key_vals);
 
     let mut grid = Box::new(
@@ -765,6 +923,30 @@ pub unsafe extern "C" fn pineappl_grid_set_key_value(
     );
 }
 
+/// Sets the observables of `grid`, which combine its bins into, for instance, ratios or
+/// normalized distributions.
+///
+/// The string `observables` contains an expression for each observable, separated by semicolons,
+/// for example `b0 / b1; b0 / (b0 + b1 + b2)`. Returns `false` without changing `grid` if the
+/// expressions can not be parsed or refer to bins that do not exist, and `true` otherwise.
+///
+/// # Safety
+///
+/// If `grid` does not point to a valid `Grid` object, for example when `grid` is the null pointer,
+/// this function is not safe to call. The parameter `observables` must be a non-`NULL` and valid C
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_grid_set_observables(
+    grid: *mut Grid,
+    observables: *const c_char,
+) -> bool {
+    let observables = CStr::from_ptr(observables).to_string_lossy();
+
+    observables.parse().map_or(false, |observables| {
+        (*grid).set_observables(&observables).is_ok()
+    })
+}
+
 /// Sets a remapper for the grid. This can be used to 'upgrade' one-dimensional bin limits to
 /// N-dimensional ones. The new bin limits must be given in the form of tuples giving the left and
 /// right limits, and a tuple for each dimension.
@@ -801,6 +983,30 @@ pub unsafe extern "C" fn pineappl_grid_set_remapper(
     .unwrap();
 }
 
+/// Sets a remapper for the grid, whose limits are given by the remapping string `remapping` using
+/// the same syntax as `pineappl remap`, for instance `0,1,2;0,2,4`.
+///
+/// The normalization of each bin is the product of its widths. Returns `false` without changing
+/// `grid` if `remapping` can not be parsed or does not have as many bins as `grid`, and `true`
+/// otherwise.
+///
+/// # Safety
+///
+/// If `grid` does not point to a valid `Grid` object, for example when `grid` is the null pointer,
+/// this function is not safe to call. The parameter `remapping` must be a non-`NULL` and valid C
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_grid_set_remapper_from_str(
+    grid: *mut Grid,
+    remapping: *const c_char,
+) -> bool {
+    let remapping = CStr::from_ptr(remapping).to_string_lossy();
+
+    remapping
+        .parse()
+        .map_or(false, |remapper| (*grid).set_remapper(remapper).is_ok())
+}
+
 /// Write `grid` to a file with name `filename`. If `filename` ends in `.lz4` the grid is
 /// automatically LZ4 compressed.
 ///
@@ -826,6 +1032,223 @@ pub unsafe extern "C" fn pineappl_grid_write(grid: *const Grid, filename: *const
     }
 }
 
+/// Read a `PineAPPL` grid from a file with name `filename` and convert it to an FK table. If the
+/// file can not be read or the grid is not an FK table, `NULL` is returned.
+///
+/// # Safety
+///
+/// The parameter `filename` must be a non-`NULL` and valid C string.
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_fk_table_read(filename: *const c_char) -> Option<Box<FkTable>> {
+    let filename = CStr::from_ptr(filename).to_string_lossy();
+    let grid = File::open(filename.as_ref())
+        .ok()
+        .and_then(|reader| Grid::read(reader).ok())?;
+
+    FkTable::try_from(grid).ok().map(Box::new)
+}
+
+/// Delete an FK table previously created with `pineappl_fk_table_read`.
+#[no_mangle]
+#[allow(unused_variables)]
+pub extern "C" fn pineappl_fk_table_delete(fk_table: Option<Box<FkTable>>) {}
+
+/// Returns the number of bins in `fk_table`.
+///
+/// # Safety
+///
+/// If `fk_table` does not point to a valid `FkTable` object, for example when `fk_table` is the
+/// null pointer, this function is not safe to call.
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_fk_table_bin_count(fk_table: *const FkTable) -> usize {
+    (*fk_table).bins()
+}
+
+/// Returns the number of channels in `fk_table`.
+///
+/// # Safety
+///
+/// If `fk_table` does not point to a valid `FkTable` object, for example when `fk_table` is the
+/// null pointer, this function is not safe to call.
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_fk_table_lumi_count(fk_table: *const FkTable) -> usize {
+    (*fk_table).lumi().len()
+}
+
+/// Writes the PDG ids of the two initial-state partons of each channel of `fk_table` into `pids`.
+///
+/// # Safety
+///
+/// If `fk_table` does not point to a valid `FkTable` object, for example when `fk_table` is the
+/// null pointer, this function is not safe to call. The parameter `pids` must point to an array
+/// that is twice as long as `fk_table` has channels.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_fk_table_lumi(fk_table: *const FkTable, pids: *mut i32) {
+    let lumi = (*fk_table).lumi();
+    let pids = slice::from_raw_parts_mut(pids, 2 * lumi.len());
+
+    for (pids, (a, b)) in pids.chunks_exact_mut(2).zip(lumi) {
+        pids[0] = a;
+        pids[1] = b;
+    }
+}
+
+/// Returns the squared factorization scale of `fk_table`, at which the PDFs must be evaluated.
+///
+/// # Safety
+///
+/// If `fk_table` does not point to a valid `FkTable` object, for example when `fk_table` is the
+/// null pointer, this function is not safe to call.
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_fk_table_muf2(fk_table: *const FkTable) -> f64 {
+    (*fk_table).muf2()
+}
+
+/// Writes the squared factorization scales of `fk_table` for both initial states into `muf2`.
+///
+/// The values are the scales at which the PDFs of the first and second initial state must be
+/// evaluated. They are different if the initial states were evolved from different fitting
+/// scales, in which case the PDFs must be tabulated at these scales for
+/// `pineappl_fk_table_convolute_with_two`.
+///
+/// # Safety
+///
+/// If `fk_table` does not point to a valid `FkTable` object, for example when `fk_table` is the
+/// null pointer, this function is not safe to call. The parameter `muf2` must point to an array
+/// with two elements.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_fk_table_muf2_per_initial_state(
+    fk_table: *const FkTable,
+    muf2: *mut f64,
+) {
+    slice::from_raw_parts_mut(muf2, 2).copy_from_slice(&(*fk_table).muf2_per_initial_state());
+}
+
+/// Returns the number of points of the x grid of `fk_table`.
+///
+/// # Safety
+///
+/// If `fk_table` does not point to a valid `FkTable` object, for example when `fk_table` is the
+/// null pointer, this function is not safe to call.
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_fk_table_x_grid_count(fk_table: *const FkTable) -> usize {
+    (*fk_table).x_grid().len()
+}
+
+/// Writes the x grid of `fk_table`, at which the PDFs must be evaluated, into `x_grid`.
+///
+/// # Safety
+///
+/// If `fk_table` does not point to a valid `FkTable` object, for example when `fk_table` is the
+/// null pointer, this function is not safe to call. The parameter `x_grid` must point to an array
+/// that is as long as the value returned by `pineappl_fk_table_x_grid_count`.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_fk_table_x_grid(fk_table: *const FkTable, x_grid: *mut f64) {
+    let grid = (*fk_table).x_grid();
+
+    slice::from_raw_parts_mut(x_grid, grid.len()).copy_from_slice(&grid);
+}
+
+/// Convolutes `fk_table` with the PDF of a hadron with the PDG id `pdg_id`, which is tabulated in
+/// `xfx`.
+///
+/// The array `xfx` must store the PDF multiplied with `x` for the `npids` partons with the PDG ids
+/// given in `pids` at the scale returned by `pineappl_fk_table_muf2` and the points returned by
+/// `pineappl_fk_table_x_grid`, in this order, such that the x index varies fastest. The parameter
+/// `lumi_mask` must be as long as `fk_table` has channels, or `NULL` to enable all channels. The
+/// results for each bin are written into `results`. Returns `false` without changing `results` if
+/// an initial state of `fk_table` is neither the hadron `pdg_id` nor its anti-particle, and `true`
+/// otherwise.
+///
+/// # Safety
+///
+/// If `fk_table` does not point to a valid `FkTable` object, for example when `fk_table` is the
+/// null pointer, this function is not safe to call. The parameter `pids` must point to an array
+/// with `npids` elements and `xfx` to an array with `npids` times as many elements as the x grid
+/// has points. The parameter `lumi_mask` must either be the null pointer or point to an array that
+/// is as long as `fk_table` has channels. Finally, `results` must be as long as `fk_table` has
+/// bins.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_fk_table_convolute_with_one(
+    fk_table: *const FkTable,
+    pdg_id: i32,
+    npids: usize,
+    pids: *const i32,
+    xfx: *const f64,
+    lumi_mask: *const bool,
+    results: *mut f64,
+) -> bool {
+    let fk_table = &*fk_table;
+    let nx = fk_table.x_grid().len();
+    let pids = slice::from_raw_parts(pids, npids);
+    let xfx = ArrayView2::from_shape((npids, nx), slice::from_raw_parts(xfx, npids * nx))
+        .unwrap_or_else(|_| unreachable!());
+    let lumi_mask = if lumi_mask.is_null() {
+        vec![]
+    } else {
+        slice::from_raw_parts(lumi_mask, fk_table.lumi().len()).to_vec()
+    };
+    let results = slice::from_raw_parts_mut(results, fk_table.bins());
+
+    fk_table
+        .convolute_tabulated_with_one(pdg_id, pids, xfx, &[], &lumi_mask)
+        .map(|values| results.copy_from_slice(&values))
+        .is_ok()
+}
+
+/// Convolutes `fk_table` with the PDFs `xfx1` and `xfx2` of the first and second initial state,
+/// respectively.
+///
+/// The PDFs are never charge conjugated. See `pineappl_fk_table_convolute_with_one` for the
+/// meaning of the remaining parameters. Since the PDFs are given for the initial states directly,
+/// this function always returns `true`.
+///
+/// # Safety
+///
+/// If `fk_table` does not point to a valid `FkTable` object, for example when `fk_table` is the
+/// null pointer, this function is not safe to call. The parameters `pids1` and `pids2` must point
+/// to arrays with `npids1` and `npids2` elements, and `xfx1` and `xfx2` to arrays with `npids1` and
+/// `npids2` times as many elements as the x grid has points, respectively. The parameter
+/// `lumi_mask` must either be the null pointer or point to an array that is as long as `fk_table`
+/// has channels. Finally, `results` must be as long as `fk_table` has bins.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_fk_table_convolute_with_two(
+    fk_table: *const FkTable,
+    npids1: usize,
+    pids1: *const i32,
+    xfx1: *const f64,
+    npids2: usize,
+    pids2: *const i32,
+    xfx2: *const f64,
+    lumi_mask: *const bool,
+    results: *mut f64,
+) -> bool {
+    let fk_table = &*fk_table;
+    let nx = fk_table.x_grid().len();
+    let pids1 = slice::from_raw_parts(pids1, npids1);
+    let pids2 = slice::from_raw_parts(pids2, npids2);
+    let xfx1 = ArrayView2::from_shape((npids1, nx), slice::from_raw_parts(xfx1, npids1 * nx))
+        .unwrap_or_else(|_| unreachable!());
+    let xfx2 = ArrayView2::from_shape((npids2, nx), slice::from_raw_parts(xfx2, npids2 * nx))
+        .unwrap_or_else(|_| unreachable!());
+    let lumi_mask = if lumi_mask.is_null() {
+        vec![]
+    } else {
+        slice::from_raw_parts(lumi_mask, fk_table.lumi().len()).to_vec()
+    };
+    let results = slice::from_raw_parts_mut(results, fk_table.bins());
+
+    fk_table
+        .convolute_tabulated_with_two(pids1, xfx1, pids2, xfx2, &[], &lumi_mask)
+        .map(|values| results.copy_from_slice(&values))
+        .is_ok()
+}
+
 /// Adds a linear combination of initial states to the luminosity function `lumi`.
 ///
 /// # Safety
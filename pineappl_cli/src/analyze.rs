@@ -79,7 +79,7 @@ impl Subcommand for CkfOpts {
         };
 
         let limit = grid.lumi().len().min(self.limit);
-        let limits = helpers::convolute_limits(&grid, &[], ConvoluteMode::Normal);
+        let limits = helpers::convolute_limits(&grid, &[], ConvoluteMode::Normal)?;
         let results: Vec<_> = (0..grid.lumi().len())
             .map(|lumi| {
                 let mut lumi_mask = vec![false; grid.lumi().len()];
@@ -95,7 +95,7 @@ impl Subcommand for CkfOpts {
                     cfg.force_positive,
                 )
             })
-            .collect();
+            .collect::<Result<_>>()?;
         let results_den: Vec<_> = (0..grid.lumi().len())
             .map(|lumi| {
                 let mut lumi_mask = vec![false; grid.lumi().len()];
@@ -111,7 +111,7 @@ impl Subcommand for CkfOpts {
                     cfg.force_positive,
                 )
             })
-            .collect();
+            .collect::<Result<_>>()?;
 
         let (x, _, _) = helpers::labels_and_units(&grid, false);
         let mut title = Row::empty();
@@ -0,0 +1,191 @@
+use super::helpers::{self, ConvoluteMode, GlobalConfiguration, Subcommand};
+use anyhow::Result;
+use clap::{Parser, ValueHint};
+use lhapdf::Pdf;
+use prettytable::{cell, Row};
+use rayon::{prelude::*, ThreadPoolBuilder};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+
+/// Calculates strong coupling uncertainties.
+#[derive(Parser)]
+pub struct Opts {
+    /// Path to the input grid.
+    #[arg(value_hint = ValueHint::FilePath)]
+    input: PathBuf,
+    /// LHAPDF id or name of the PDF set with the central value of the strong coupling.
+    #[arg(value_parser = helpers::parse_pdfset)]
+    pdfset: String,
+    /// LHAPDF id or name of the PDF set with the downwards-varied value of the strong coupling.
+    #[arg(value_parser = helpers::parse_pdfset)]
+    pdfset_down: String,
+    /// LHAPDF id or name of the PDF set with the upwards-varied value of the strong coupling.
+    #[arg(value_parser = helpers::parse_pdfset)]
+    pdfset_up: String,
+    /// Rescale the uncertainty by the given factor, for instance to translate the variation of
+    /// the strong coupling contained in the sets into the desired one.
+    #[arg(default_value_t = 1.0, long, value_name = "FACTOR")]
+    scale: f64,
+    /// Symmetrize the strong coupling uncertainty.
+    #[arg(long)]
+    symmetric: bool,
+    /// Combine the strong coupling uncertainty in quadrature with the PDF uncertainty of the
+    /// central set.
+    #[arg(long)]
+    pdf: bool,
+    /// Confidence level of the PDF uncertainty in per cent.
+    #[arg(default_value_t = lhapdf::CL_1_SIGMA, long, requires = "pdf")]
+    cl: f64,
+    /// Show integrated numbers (without bin widths) instead of differential ones.
+    #[arg(long, short)]
+    integrated: bool,
+    /// Select orders manually.
+    #[arg(
+        long,
+        num_args = 1,
+        short,
+        value_delimiter = ',',
+        value_parser = helpers::parse_order
+    )]
+    orders: Vec<(u32, u32)>,
+    /// Number of threads to utilize.
+    #[arg(default_value_t = thread::available_parallelism().map_or(1, NonZeroUsize::get), long)]
+    threads: usize,
+    /// Set the number of fractional digits shown for absolute numbers.
+    #[arg(default_value_t = 7, long, value_name = "ABS")]
+    digits_abs: usize,
+    /// Set the number of fractional digits shown for relative numbers.
+    #[arg(default_value_t = 2, long, value_name = "REL")]
+    digits_rel: usize,
+}
+
+impl Opts {
+    const fn mode(&self) -> ConvoluteMode {
+        if self.integrated {
+            ConvoluteMode::Integrated
+        } else {
+            ConvoluteMode::Normal
+        }
+    }
+}
+
+impl Subcommand for Opts {
+    fn run(&self, cfg: &GlobalConfiguration) -> Result<ExitCode> {
+        let grid = helpers::read_grid(&self.input)?;
+
+        ThreadPoolBuilder::new()
+            .num_threads(self.threads)
+            .build_global()
+            .unwrap();
+
+        let limits = helpers::convolute_limits(&grid, &[], self.mode())?;
+        let convolute = |mut pdf: Pdf| {
+            helpers::convolute(
+                &grid,
+                &mut pdf,
+                &self.orders,
+                &[],
+                &[],
+                1,
+                self.mode(),
+                cfg.force_positive,
+            )
+        };
+        let results: Vec<_> = [&self.pdfset, &self.pdfset_down, &self.pdfset_up]
+            .into_par_iter()
+            .map(|pdfset| convolute(helpers::create_pdf(pdfset)?))
+            .collect::<Result<_>>()?;
+        let (central, down, up) = (&results[0], &results[1], &results[2]);
+
+        let pdf_results = if self.pdf {
+            let (set, member) = helpers::create_pdfset(&self.pdfset)?;
+            let results: Vec<_> = set
+                .mk_pdfs()
+                .into_par_iter()
+                .map(convolute)
+                .collect::<Result<Vec<_>>>()?
+                .concat();
+            Some((set, member, results))
+        } else {
+            None
+        };
+
+        let (x, y_label, y_unit) = helpers::labels_and_units(&grid, self.integrated);
+        let mut title = Row::empty();
+        title.add_cell(cell!(c->"b"));
+        for (x_label, x_unit) in x {
+            let mut cell = cell!(c->format!("{x_label}\n[{x_unit}]"));
+            cell.set_hspan(2);
+            title.add_cell(cell);
+        }
+        title.add_cell(cell!(c->format!("{y_label}\n[{y_unit}]")));
+        title.add_cell(cell!(c->"alphas uncertainty\n[%]").with_hspan(2));
+
+        if self.pdf {
+            title.add_cell(cell!(c->"PDF uncertainty\n[%]").with_hspan(2));
+            title.add_cell(cell!(c->"total uncertainty\n[%]").with_hspan(2));
+        }
+
+        let mut table = helpers::create_table();
+        table.set_titles(title);
+
+        for (bin, left_right_limits) in limits.iter().enumerate() {
+            // with `--pdf` the central value of the PDF set is the reference for all uncertainties
+            let (value, pdf_uncertainty) = if let Some((set, member, results)) = &pdf_results {
+                let values: Vec<_> = results
+                    .iter()
+                    .skip(bin)
+                    .step_by(limits.len())
+                    .copied()
+                    .collect();
+                let uncertainty = set.uncertainty(&values, self.cl, false)?;
+
+                (
+                    member.map_or(uncertainty.central, |member| values[member]),
+                    Some((uncertainty.errminus, uncertainty.errplus)),
+                )
+            } else {
+                (central[bin], None)
+            };
+            let (mut as_minus, mut as_plus) = if self.symmetric {
+                let delta = 0.5 * (up[bin] - down[bin]).abs();
+                (delta, delta)
+            } else {
+                (
+                    (value - up[bin].min(down[bin])).max(0.0),
+                    (up[bin].max(down[bin]) - value).max(0.0),
+                )
+            };
+            as_minus *= self.scale;
+            as_plus *= self.scale;
+
+            let row = table.add_empty_row();
+
+            row.add_cell(cell!(r->format!("{bin}")));
+            for (left, right) in left_right_limits {
+                row.add_cell(cell!(r->format!("{left}")));
+                row.add_cell(cell!(r->format!("{right}")));
+            }
+            row.add_cell(cell!(r->format!("{:.*e}", self.digits_abs, value)));
+            row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, (-as_minus / value) * 100.0)));
+            row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, (as_plus / value) * 100.0)));
+
+            if let Some((pdf_minus, pdf_plus)) = pdf_uncertainty {
+                let total_minus = as_minus.hypot(pdf_minus);
+                let total_plus = as_plus.hypot(pdf_plus);
+
+                for unc in [-pdf_minus, pdf_plus, -total_minus, total_plus] {
+                    row.add_cell(
+                        cell!(r->format!("{:.*}", self.digits_rel, (unc / value) * 100.0)),
+                    );
+                }
+            }
+        }
+
+        table.printstd();
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
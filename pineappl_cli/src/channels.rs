@@ -81,7 +81,7 @@ impl Subcommand for Opts {
             } else {
                 ConvoluteMode::Normal
             },
-        );
+        )?;
         let results: Vec<_> = (0..grid.lumi().len())
             .map(|lumi| {
                 let mut lumi_mask = vec![false; grid.lumi().len()];
@@ -101,7 +101,7 @@ impl Subcommand for Opts {
                     cfg.force_positive,
                 )
             })
-            .collect();
+            .collect::<Result<_>>()?;
 
         let (x, y_label, y_unit) = helpers::labels_and_units(&grid, self.integrated);
         let mut title = Row::empty();
@@ -0,0 +1,340 @@
+use super::helpers::{self, ConvoluteMode, GlobalConfiguration, Subcommand};
+use super::hepdata::{self, Table};
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::{Parser, ValueHint};
+use ndarray::Array2;
+use prettytable::{cell, Row};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Compares the predictions of a grid with experimental data using a chi-square.
+#[derive(Parser)]
+pub struct Opts {
+    /// Path to the input grid.
+    #[arg(value_hint = ValueHint::FilePath)]
+    input: PathBuf,
+    /// Path to the experimental data, either a HEPData table in YAML format or a CSV file.
+    #[arg(value_hint = ValueHint::FilePath)]
+    data: PathBuf,
+    /// LHAPDF id or name of the PDF set.
+    #[arg(value_parser = helpers::parse_pdfset)]
+    pdfset: String,
+    /// Use the t0 prescription with the given PDF set for the correlated uncertainties.
+    #[arg(long, value_name = "PDFSET", value_parser = helpers::parse_pdfset)]
+    t0: Option<String>,
+    /// Additionally show the chi-square for each member of the PDF set.
+    #[arg(long)]
+    members: bool,
+    /// Select orders manually.
+    #[arg(
+        long,
+        num_args = 1,
+        short,
+        value_delimiter = ',',
+        value_parser = helpers::parse_order
+    )]
+    orders: Vec<(u32, u32)>,
+    /// Set the number of fractional digits shown for absolute numbers.
+    #[arg(default_value_t = 7, long, value_name = "ABS")]
+    digits_abs: usize,
+    /// Set the number of fractional digits shown for relative numbers.
+    #[arg(default_value_t = 2, long, value_name = "REL")]
+    digits_rel: usize,
+}
+
+/// Central values and uncertainties of a measurement. Uncertainties whose label starts with `stat`
+/// or contains `uncorr` are uncorrelated between bins, all others are fully correlated.
+struct Measurement {
+    bins: usize,
+    values: Vec<f64>,
+    /// For each bin the sum of the squares of all uncorrelated uncertainties.
+    uncorrelated: Vec<f64>,
+    /// For each correlated source of uncertainty its value in each bin.
+    correlated: Vec<Vec<f64>>,
+}
+
+fn is_uncorrelated(label: &str) -> bool {
+    let label = label.to_lowercase();
+    label.starts_with("stat") || label.contains("uncorr")
+}
+
+impl Measurement {
+    fn read(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => Self::from_hepdata(&hepdata::read_table(path)?),
+            _ => Self::from_csv(&fs::read_to_string(path)?),
+        }
+    }
+
+    fn new(bins: usize) -> Self {
+        Self {
+            bins,
+            values: Vec::with_capacity(bins),
+            uncorrelated: Vec::with_capacity(bins),
+            correlated: Vec::new(),
+        }
+    }
+
+    fn add_uncertainty(&mut self, bin: usize, source: usize, label: &str, uncertainty: f64) {
+        if is_uncorrelated(label) {
+            self.uncorrelated[bin] += uncertainty * uncertainty;
+        } else {
+            if self.correlated.len() <= source {
+                self.correlated.resize(source + 1, vec![0.0; self.bins]);
+            }
+
+            self.correlated[source][bin] = uncertainty;
+        }
+    }
+
+    fn from_hepdata(table: &Table) -> Result<Self> {
+        let variable = table
+            .dependent_variables
+            .first()
+            .ok_or_else(|| anyhow!("the HEPData table does not have dependent variables"))?;
+        let mut result = Self::new(variable.values.len());
+
+        for (bin, value) in variable.values.iter().enumerate() {
+            let central = hepdata::number(
+                value
+                    .central
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("bin {} has no value", bin))?,
+                0.0,
+            )?;
+
+            result.values.push(central);
+            result.uncorrelated.push(0.0);
+
+            for (source, error) in value.errors.iter().enumerate() {
+                let uncertainty = if let Some(symerror) = &error.symerror {
+                    hepdata::number(symerror, central)?.abs()
+                } else if let Some(asymerror) = &error.asymerror {
+                    0.5 * (hepdata::number(&asymerror.plus, central)?.abs()
+                        + hepdata::number(&asymerror.minus, central)?.abs())
+                } else {
+                    bail!("uncertainty '{}' of bin {} has no value", error.label, bin);
+                };
+
+                result.add_uncertainty(bin, source, &error.label, uncertainty);
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn from_csv(contents: &str) -> Result<Self> {
+        let mut lines = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        let header: Vec<_> = lines
+            .next()
+            .ok_or_else(|| anyhow!("the CSV file does not have a header"))?
+            .split(',')
+            .map(str::trim)
+            .collect();
+        let rows: Vec<_> = lines.collect();
+        let mut result = Self::new(rows.len());
+
+        for (bin, row) in rows.into_iter().enumerate() {
+            let columns: Vec<_> = row.split(',').map(str::trim).collect();
+
+            ensure!(
+                columns.len() == header.len(),
+                "row {} of the CSV file has {} columns, but the header has {}",
+                bin,
+                columns.len(),
+                header.len()
+            );
+
+            let central: f64 = columns[0].parse()?;
+
+            result.values.push(central);
+            result.uncorrelated.push(0.0);
+
+            for (source, (label, column)) in header.iter().zip(&columns).skip(1).enumerate() {
+                let uncertainty =
+                    hepdata::number(&serde_yaml::Value::String((*column).to_owned()), central)?;
+                result.add_uncertainty(bin, source, label, uncertainty.abs());
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the covariance matrix. If `t0` is given the correlated uncertainties are rescaled
+    /// with these predictions instead of the measured central values, which therefore must not be
+    /// zero if there are correlated uncertainties.
+    fn covariance(&self, t0: Option<&[f64]>) -> Result<Array2<f64>> {
+        let bins = self.values.len();
+
+        if t0.is_some() && !self.correlated.is_empty() {
+            if let Some(bin) = self.values.iter().position(|&value| value == 0.0) {
+                bail!(
+                    "the t0 prescription can not rescale the uncertainties of bin {bin}, whose \
+                     data is zero"
+                );
+            }
+        }
+
+        Ok(Array2::from_shape_fn((bins, bins), |(i, j)| {
+            let correlated: f64 = self
+                .correlated
+                .iter()
+                .map(|source| {
+                    t0.map_or(source[i] * source[j], |t0| {
+                        source[i] / self.values[i] * t0[i] * source[j] / self.values[j] * t0[j]
+                    })
+                })
+                .sum();
+
+            if i == j {
+                self.uncorrelated[i] + correlated
+            } else {
+                correlated
+            }
+        }))
+    }
+}
+
+/// Calculates the chi-square for the differences `diff` using a Cholesky decomposition of
+/// `covariance`.
+fn chi2(diff: &[f64], covariance: &Array2<f64>) -> Result<f64> {
+    let bins = diff.len();
+    let mut lower = Array2::<f64>::zeros((bins, bins));
+
+    for i in 0..bins {
+        for j in 0..=i {
+            let sum =
+                covariance[[i, j]] - (0..j).map(|k| lower[[i, k]] * lower[[j, k]]).sum::<f64>();
+
+            if i == j {
+                ensure!(sum > 0.0, "the covariance matrix is not positive definite");
+                lower[[i, i]] = sum.sqrt();
+            } else {
+                lower[[i, j]] = sum / lower[[j, j]];
+            }
+        }
+    }
+
+    // solve `lower * y = diff`, the chi-square is the squared norm of `y`
+    let mut y = Vec::with_capacity(bins);
+
+    for (i, d) in diff.iter().enumerate() {
+        let sum: f64 = y.iter().enumerate().map(|(k, y)| lower[[i, k]] * y).sum();
+        y.push((d - sum) / lower[[i, i]]);
+    }
+
+    Ok(y.iter().map(|y| y * y).sum())
+}
+
+impl Subcommand for Opts {
+    fn run(&self, cfg: &GlobalConfiguration) -> Result<ExitCode> {
+        let grid = helpers::read_grid(&self.input)?;
+        let data = Measurement::read(&self.data)?;
+        let bins = grid.bin_info().bins();
+
+        ensure!(
+            data.values.len() == bins,
+            "the data has {} bins, but the grid has {}",
+            data.values.len(),
+            bins
+        );
+
+        let convolute = |pdf: &mut lhapdf::Pdf| {
+            helpers::convolute(
+                &grid,
+                pdf,
+                &self.orders,
+                &[],
+                &[],
+                1,
+                ConvoluteMode::Normal,
+                cfg.force_positive,
+            )
+        };
+
+        let t0 = self
+            .t0
+            .as_ref()
+            .map(|t0| convolute(&mut helpers::create_pdf(t0)?))
+            .transpose()?;
+        let covariance = data.covariance(t0.as_deref())?;
+        let theory = convolute(&mut helpers::create_pdf(&self.pdfset)?)?;
+        let diff: Vec<_> = theory
+            .iter()
+            .zip(&data.values)
+            .map(|(theory, data)| theory - data)
+            .collect();
+        let total = chi2(&diff, &covariance)?;
+
+        let (x, y_label, y_unit) = helpers::labels_and_units(&grid, false);
+        let mut title = Row::empty();
+        title.add_cell(cell!(c->"b"));
+        for (x_label, x_unit) in x {
+            let mut cell = cell!(c->format!("{x_label}\n[{x_unit}]"));
+            cell.set_hspan(2);
+            title.add_cell(cell);
+        }
+        title.add_cell(cell!(c->format!("data\n[{y_unit}]")));
+        title.add_cell(cell!(c->format!("{y_label}\n[{y_unit}]")));
+        title.add_cell(cell!(c->format!("sigma\n[{y_unit}]")));
+        title.add_cell(cell!(c->"pull\n[\u{3c3}]"));
+
+        let mut table = helpers::create_table();
+        table.set_titles(title);
+
+        for (bin, limits) in grid.bin_info().limits().iter().enumerate() {
+            let sigma = covariance[[bin, bin]].sqrt();
+            let row = table.add_empty_row();
+
+            row.add_cell(cell!(r->format!("{bin}")));
+            for (left, right) in limits {
+                row.add_cell(cell!(r->format!("{left}")));
+                row.add_cell(cell!(r->format!("{right}")));
+            }
+            row.add_cell(cell!(r->format!("{:.*e}", self.digits_abs, data.values[bin])));
+            row.add_cell(cell!(r->format!("{:.*e}", self.digits_abs, theory[bin])));
+            row.add_cell(cell!(r->format!("{:.*e}", self.digits_abs, sigma)));
+            row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, diff[bin] / sigma)));
+        }
+
+        table.printstd();
+
+        println!(
+            "\u{3c7}\u{b2} = {:.*} for {} data points",
+            self.digits_rel, total, bins
+        );
+
+        if self.members {
+            let (set, _) = helpers::create_pdfset(&self.pdfset)?;
+            let mut title = Row::empty();
+            title.add_cell(cell!(c->"m"));
+            title.add_cell(cell!(c->"\u{3c7}\u{b2}"));
+
+            let mut table = helpers::create_table();
+            table.set_titles(title);
+
+            for (member, mut pdf) in set.mk_pdfs().into_iter().enumerate() {
+                let diff: Vec<_> = convolute(&mut pdf)?
+                    .iter()
+                    .zip(&data.values)
+                    .map(|(theory, data)| theory - data)
+                    .collect();
+                let row = table.add_empty_row();
+
+                row.add_cell(cell!(r->format!("{member}")));
+                row.add_cell(
+                    cell!(r->format!("{:.*}", self.digits_rel, chi2(&diff, &covariance)?)),
+                );
+            }
+
+            println!();
+            table.printstd();
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -1,6 +1,5 @@
-use super::helpers::{self, ConvoluteMode, GlobalConfiguration, Subcommand};
+use super::helpers::{self, ConvoluteMode, GlobalConfiguration, ScaleVariations, Subcommand};
 use anyhow::Result;
-use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::{Parser, ValueHint};
 use prettytable::{cell, Row};
 use std::ops::RangeInclusive;
@@ -31,6 +30,9 @@ pub struct Opts {
     /// Show integrated numbers (without bin widths) instead of differential ones.
     #[arg(long, short)]
     integrated: bool,
+    /// Show the observables defined in the grid instead of its bins.
+    #[arg(conflicts_with_all = ["bins", "integrated"], long)]
+    observables: bool,
     /// Select orders manually.
     #[arg(
         long,
@@ -40,14 +42,10 @@ pub struct Opts {
         value_parser = helpers::parse_order
     )]
     orders: Vec<(u32, u32)>,
-    /// Set the number of scale variations.
-    #[arg(
-        default_value_t = 7,
-        long,
-        short,
-        value_parser = PossibleValuesParser::new(["1", "3", "7", "9"]).try_map(|s| s.parse::<usize>())
-    )]
-    scales: usize,
+    /// Set the scale-variation prescription: 1, 3, 7, 9, 3-ren, 3-fac or 31-corr, optionally
+    /// followed by @FACTOR, or a list of xir:xif pairs.
+    #[arg(default_value = "7", long, short, value_parser = helpers::parse_scales)]
+    scales: ScaleVariations,
     /// Set the number of fractional digits shown for absolute numbers.
     #[arg(default_value_t = 7, long, value_name = "ABS")]
     digits_abs: usize,
@@ -61,36 +59,32 @@ impl Subcommand for Opts {
         let grid = helpers::read_grid(&self.input)?;
         let mut pdf = helpers::create_pdf(&self.pdfsets[0])?;
         let bins: Vec<_> = self.bins.iter().cloned().flatten().collect();
+        let mode = if self.observables {
+            helpers::check_observables(&grid)?;
+            ConvoluteMode::Observables
+        } else if self.integrated {
+            ConvoluteMode::Integrated
+        } else {
+            ConvoluteMode::Normal
+        };
 
-        let results = helpers::convolute(
+        let results = helpers::convolute_scales(
             &grid,
             &mut pdf,
             &self.orders,
             &bins,
             &[],
-            self.scales,
-            if self.integrated {
-                ConvoluteMode::Integrated
-            } else {
-                ConvoluteMode::Normal
-            },
+            &self.scales,
+            mode,
             cfg.force_positive,
-        );
-        let limits = helpers::convolute_limits(
-            &grid,
-            &bins,
-            if self.integrated {
-                ConvoluteMode::Integrated
-            } else {
-                ConvoluteMode::Normal
-            },
-        );
+        )?;
+        let limits = helpers::convolute_limits(&grid, &bins, mode)?;
         let bin_count = limits.len();
 
         let other_results: Vec<_> = self.pdfsets[1..]
             .iter()
-            .flat_map(|pdfset| {
-                let mut pdf = helpers::create_pdf(pdfset).unwrap();
+            .map(|pdfset| {
+                let mut pdf = helpers::create_pdf(pdfset)?;
                 helpers::convolute(
                     &grid,
                     &mut pdf,
@@ -98,14 +92,13 @@ impl Subcommand for Opts {
                     &bins,
                     &[],
                     1,
-                    if self.integrated {
-                        ConvoluteMode::Integrated
-                    } else {
-                        ConvoluteMode::Normal
-                    },
+                    mode,
                     cfg.force_positive,
                 )
             })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
             .collect();
 
         let (x, y_label, y_unit) = helpers::labels_and_units(&grid, self.integrated);
@@ -119,10 +112,10 @@ impl Subcommand for Opts {
         title.add_cell(cell!(c->format!("{y_label}\n[{y_unit}]")));
 
         if self.absolute {
-            for scale in &helpers::SCALES_VECTOR[0..self.scales] {
+            for scale in self.scales.iter() {
                 title.add_cell(cell!(c->format!("({},{})\n[{}]", scale.0, scale.1, y_unit)));
             }
-        } else if self.scales != 1 {
+        } else if self.scales.len() != 1 {
             title.add_cell(cell!(c->"scale uncertainty\n[%]").with_hspan(2));
         }
 
@@ -137,7 +130,7 @@ impl Subcommand for Opts {
 
         for (index, (limits, values)) in limits
             .into_iter()
-            .zip(results.chunks_exact(self.scales))
+            .zip(results.chunks_exact(self.scales.len()))
             .enumerate()
         {
             let min_value = values
@@ -163,7 +156,7 @@ impl Subcommand for Opts {
                 for &value in values.iter() {
                     row.add_cell(cell!(r->format!("{:.*e}", self.digits_abs, value)));
                 }
-            } else if self.scales != 1 {
+            } else if self.scales.len() != 1 {
                 row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, (min_value / values[0] - 1.0) * 100.0)));
                 row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, (max_value / values[0] - 1.0) * 100.0)));
             }
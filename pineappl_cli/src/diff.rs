@@ -68,6 +68,7 @@ impl Subcommand for Opts {
             .filter(|order| {
                 (order.logxir == 0)
                     && (order.logxif == 0)
+                    && (order.logxia == 0)
                     && (self.orders1.is_empty()
                         || self
                             .orders1
@@ -81,6 +82,7 @@ impl Subcommand for Opts {
             .filter(|order| {
                 (order.logxir == 0)
                     && (order.logxif == 0)
+                    && (order.logxia == 0)
                     && (self.orders2.is_empty()
                         || self
                             .orders2
@@ -136,7 +138,7 @@ impl Subcommand for Opts {
             title.add_cell(cell);
         }
 
-        let limits1 = helpers::convolute_limits(&grid1, &[], ConvoluteMode::Normal);
+        let limits1 = helpers::convolute_limits(&grid1, &[], ConvoluteMode::Normal)?;
 
         if self.ignore_orders {
             let mut cell = cell!(c->"diff");
@@ -154,7 +156,7 @@ impl Subcommand for Opts {
                 1,
                 ConvoluteMode::Normal,
                 cfg.force_positive,
-            );
+            )?;
             let results2 = helpers::convolute(
                 &grid2,
                 &mut pdf,
@@ -164,7 +166,7 @@ impl Subcommand for Opts {
                 1,
                 ConvoluteMode::Normal,
                 cfg.force_positive,
-            );
+            )?;
 
             for (bin, (limits1, (result1, result2))) in limits1
                 .iter()
@@ -212,7 +214,7 @@ impl Subcommand for Opts {
                         cfg.force_positive,
                     )
                 })
-                .collect();
+                .collect::<Result<_>>()?;
             let order_results2: Vec<Vec<f64>> = orders
                 .iter()
                 .map(|&order| {
@@ -227,7 +229,7 @@ impl Subcommand for Opts {
                         cfg.force_positive,
                     )
                 })
-                .collect();
+                .collect::<Result<_>>()?;
 
             for (bin, limits1) in limits1.iter().enumerate() {
                 let row = table.add_empty_row();
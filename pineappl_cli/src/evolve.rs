@@ -1,33 +1,44 @@
 use super::helpers::{self, ConvoluteMode, GlobalConfiguration, Subcommand};
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
+use clap::builder::PossibleValuesParser;
 use clap::{Parser, ValueHint};
 use lhapdf::Pdf;
+use pineappl::evolution::BasisRotation;
 use pineappl::fk_table::FkTable;
 use pineappl::grid::Grid;
+use pineappl::lumi::{LumiCache, LumiEntry};
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
+/// An operator slice together with the information describing it.
+#[cfg(any(feature = "dglap", feature = "evolve"))]
+type Slice<'a> = (
+    pineappl::evolution::OperatorSliceInfo,
+    ndarray::CowArray<'a, f64, ndarray::Ix4>,
+);
+
 #[cfg(feature = "evolve")]
-fn evolve_grid(
-    grid: &Grid,
-    eko: &Path,
-    pdf: &Pdf,
-    orders: &[(u32, u32)],
-    xir: f64,
-    xif: f64,
-) -> Result<FkTable> {
+mod eko {
+    use super::Slice;
+    use anyhow::{anyhow, bail, Context, Result};
+    use float_cmp::approx_eq;
     use lz4_flex::frame::FrameDecoder;
-    use ndarray::Array5;
-    use ndarray_npy::ReadNpyExt;
-    use pineappl::evolution::OperatorInfo;
-    use pineappl::subgrid::{Mu2, Subgrid};
+    use ndarray::{Array4, Array5, Axis, CowArray};
+    use ndarray_npy::{NpzReader, ReadNpyExt};
+    use pineappl::evolution::OperatorSliceInfo;
+    use pineappl::pids;
     use serde::Deserialize;
+    use std::collections::HashMap;
     use std::fs::File;
-    use std::io::BufReader;
-    use tar::Archive;
+    use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+    use std::path::Path;
+    use tar::{Archive, Entry};
 
-    #[derive(Default, Deserialize)]
-    struct Metadata {
+    /// Metadata of the legacy EKO format, which stores all operators in a single file
+    /// `operators.npy.lz4`.
+    #[derive(Deserialize)]
+    struct MetadataV0 {
         #[serde(rename = "Q2grid")]
         q2_grid: Vec<f64>,
         inputgrid: Vec<f64>,
@@ -37,62 +48,521 @@ fn evolve_grid(
         targetpids: Vec<i32>,
     }
 
-    let mut archive = Archive::new(File::open(eko)?);
+    /// Metadata of the EKO format which stores the operator for each scale in a separate file in
+    /// the directory `operators`.
+    #[derive(Deserialize)]
+    struct MetadataV2 {
+        mu20: f64,
+        bases: BasesV2,
+    }
+
+    #[derive(Deserialize)]
+    struct BasesV2 {
+        xgrid: Vec<f64>,
+        pids: Vec<i32>,
+        #[serde(default)]
+        inputgrid: Option<Vec<f64>>,
+        #[serde(default)]
+        inputpids: Option<Vec<Vec<f64>>>,
+        #[serde(default)]
+        targetgrid: Option<Vec<f64>>,
+        #[serde(default)]
+        targetpids: Option<Vec<Vec<f64>>>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Metadata {
+        V0(MetadataV0),
+        V2(MetadataV2),
+    }
+
+    /// Header `operators/<NAME>.yaml` of the operator stored in `operators/<NAME>.npz.lz4`.
+    #[derive(Deserialize)]
+    struct OperatorHeaderV2 {
+        scale: f64,
+    }
+
+    /// Particle identifiers of the evolution basis, see [`pids::evol_to_pdg_mc_ids`].
+    const EVOL_IDS: [i32; 14] = [
+        21, 22, 100, 103, 108, 115, 124, 135, 200, 203, 208, 215, 224, 235,
+    ];
+
+    /// Properties of the operator slices that are the same for all scales.
+    pub struct SliceInfo {
+        fac0: f64,
+        pids0: Vec<i32>,
+        x0: Vec<f64>,
+        pids1: Vec<i32>,
+        x1: Vec<f64>,
+        lumi_id_types: String,
+    }
+
+    impl SliceInfo {
+        fn get(&self, fac1: f64) -> OperatorSliceInfo {
+            OperatorSliceInfo {
+                fac0: self.fac0,
+                pids0: self.pids0.clone(),
+                x0: self.x0.clone(),
+                fac1,
+                pids1: self.pids1.clone(),
+                x1: self.x1.clone(),
+                lumi_id_types: self.lumi_id_types.clone(),
+            }
+        }
+    }
+
+    /// Evolution kernel operator restricted to the squared factorization scales needed by a grid.
+    pub enum EkoSlices {
+        /// EKO in the legacy format, which has to be read completely.
+        V0 {
+            info: SliceInfo,
+            fac1: Vec<f64>,
+            operator: Array5<f64>,
+        },
+        /// EKO that stores the operator for each scale in a separate file, which is only read
+        /// when iterating over the slices.
+        V2 {
+            info: SliceInfo,
+            operators: Vec<OperatorEntry>,
+            file: File,
+        },
+    }
+
+    /// Location of the compressed operator for the squared factorization scale `fac1` inside the
+    /// tar archive of an EKO.
+    pub struct OperatorEntry {
+        name: String,
+        fac1: f64,
+        position: u64,
+        size: u64,
+    }
+
+    impl EkoSlices {
+        /// Returns an iterator over the operator slices, which can be passed to
+        /// [`Grid::evolve_with_slice_iter`]. The slices are ordered like the squared factorization
+        /// scales given to [`read`], and each slice is read from the archive only when it is
+        /// needed.
+        ///
+        /// [`Grid::evolve_with_slice_iter`]: pineappl::grid::Grid::evolve_with_slice_iter
+        pub fn iter_mut(&mut self) -> Box<dyn Iterator<Item = Result<Slice<'_>>> + '_> {
+            match self {
+                Self::V0 {
+                    info,
+                    fac1,
+                    operator,
+                } => Box::new(
+                    fac1.iter()
+                        .zip(operator.axis_iter(Axis(0)))
+                        .map(|(&fac1, operator)| Ok((info.get(fac1), CowArray::from(operator)))),
+                ),
+                Self::V2 {
+                    info,
+                    operators,
+                    file,
+                } => {
+                    let info = &*info;
+
+                    Box::new(
+                        operators
+                            .iter()
+                            .map(move |operator| read_slice(file, info, operator)),
+                    )
+                }
+            }
+        }
+    }
+
+    fn file_name<R: Read>(entry: &Entry<R>) -> Result<Option<String>> {
+        Ok(entry
+            .path()?
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned()))
+    }
+
+    fn in_operators_dir<R: Read>(entry: &Entry<R>) -> Result<bool> {
+        Ok(entry
+            .path()?
+            .parent()
+            .and_then(Path::file_name)
+            .map_or(false, |dir| dir == "operators"))
+    }
+
+    /// Determines the particle identifiers of the basis given by `rotation`, whose rows express
+    /// each basis vector as a linear combination of the PDG ids `pids`, and the corresponding
+    /// value of `lumi_id_types`. If there's no rotation the basis is given by `pids` itself.
+    fn basis(rotation: Option<&Vec<Vec<f64>>>, pids: &[i32]) -> Result<(Vec<i32>, String)> {
+        let rotation = if let Some(rotation) = rotation {
+            rotation
+        } else {
+            return Ok((pids.to_vec(), "pdg_mc_ids".to_string()));
+        };
+
+        let pdg_ids: Option<Vec<_>> = rotation
+            .iter()
+            .map(|row| {
+                let mut nonzero = row.iter().zip(pids).filter(|(&value, _)| value != 0.0);
+
+                match (nonzero.next(), nonzero.next()) {
+                    (Some((&value, &pid)), None) if approx_eq!(f64, value, 1.0, ulps = 4) => {
+                        Some(pid)
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+
+        if let Some(pdg_ids) = pdg_ids {
+            return Ok((pdg_ids, "pdg_mc_ids".to_string()));
+        }
+
+        let evol_ids: Option<Vec<_>> = rotation
+            .iter()
+            .map(|row| {
+                EVOL_IDS.iter().copied().find(|&id| {
+                    let combination = pids::evol_to_pdg_mc_ids(id);
+
+                    row.iter().zip(pids).all(|(&value, pid)| {
+                        let factor = combination
+                            .iter()
+                            .find_map(|(p, factor)| (p == pid).then_some(*factor))
+                            .unwrap_or(0.0);
+                        approx_eq!(f64, value, factor, ulps = 4)
+                    })
+                })
+            })
+            .collect();
+
+        if let Some(evol_ids) = evol_ids {
+            return Ok((evol_ids, "evol".to_string()));
+        }
+
+        bail!("the basis of the EKO is neither the PDG nor the evolution basis")
+    }
+
+    /// Returns the index into `scales` for each value of `fac1`.
+    fn scale_indices(scales: &[f64], fac1: &[f64]) -> Result<Vec<usize>> {
+        fac1.iter()
+            .map(|&fac| {
+                scales
+                    .iter()
+                    .position(|&scale| approx_eq!(f64, scale, fac, ulps = 64))
+                    .ok_or_else(|| anyhow!("the EKO does not contain an operator for muf2 = {fac}"))
+            })
+            .collect()
+    }
+
+    /// Reads the metadata of the evolution kernel operator stored in the tar archive `path` and
+    /// checks that it contains operators for the squared factorization scales `fac1`. Operators
+    /// for other scales are skipped.
+    pub fn read(path: &Path, fac1: &[f64]) -> Result<EkoSlices> {
+        let open = || -> Result<_> {
+            Ok(Archive::new(
+                File::open(path).context(format!("unable to open '{}'", path.display()))?,
+            ))
+        };
+
+        let mut metadata = None;
+        let mut headers = HashMap::new();
+        let mut operators = HashMap::new();
+
+        // the first pass only reads the small files that describe the operators
+        for entry in open()?.entries()? {
+            let entry = entry?;
+            let name = if let Some(name) = file_name(&entry)? {
+                name
+            } else {
+                continue;
+            };
+
+            if name == "metadata.yaml" {
+                metadata = Some(serde_yaml::from_reader::<_, Metadata>(entry).context(
+                    "`metadata.yaml` of the EKO is neither in the legacy nor in the new format",
+                )?);
+            } else if in_operators_dir(&entry)? {
+                if let Some(stem) = name.strip_suffix(".yaml") {
+                    let header: OperatorHeaderV2 = serde_yaml::from_reader(entry)
+                        .context(format!("unable to read the operator header '{name}'"))?;
+                    headers.insert(stem.to_string(), header.scale);
+                } else if let Some(stem) = name.strip_suffix(".npz.lz4") {
+                    // remember where the operator is stored to read it later on demand
+                    operators.insert(
+                        stem.to_string(),
+                        (name.clone(), entry.raw_file_position(), entry.size()),
+                    );
+                }
+            }
+        }
+
+        match metadata.ok_or_else(|| anyhow!("the EKO does not contain `metadata.yaml`"))? {
+            Metadata::V0(metadata) => read_v0(open()?, metadata, fac1),
+            Metadata::V2(metadata) => {
+                let operators = fac1
+                    .iter()
+                    .map(|&fac| {
+                        let (stem, &scale) = headers
+                            .iter()
+                            .find(|(_, &scale)| approx_eq!(f64, scale, fac, ulps = 64))
+                            .ok_or_else(|| {
+                                anyhow!("the EKO does not contain an operator for muf2 = {fac}")
+                            })?;
+                        let (name, position, size) = operators.get(stem).ok_or_else(|| {
+                            anyhow!(
+                                "the EKO does not contain the operator `operators/{stem}.npz.lz4`"
+                            )
+                        })?;
 
-    let mut operator = Default::default();
-    let mut metadata = Metadata::default();
+                        Ok(OperatorEntry {
+                            name: name.clone(),
+                            fac1: scale,
+                            position: *position,
+                            size: *size,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
 
-    for entry in archive.entries()? {
-        let file = entry?;
-        let path = file.header().path()?;
+                let bases = metadata.bases;
+                let (pids0, lumi_id_types) = basis(bases.inputpids.as_ref(), &bases.pids)?;
+                let (pids1, target_basis) = basis(bases.targetpids.as_ref(), &bases.pids)?;
 
-        if let Some(file_name) = path.file_name() {
-            // TODO: get rid of the unwrap
-            match file_name.to_str().unwrap() {
-                "metadata.yaml" => metadata = serde_yaml::from_reader(file)?,
-                "operators.npy.lz4" => {
-                    operator = Array5::<f64>::read_npy(FrameDecoder::new(BufReader::new(file)))?;
+                if target_basis != "pdg_mc_ids" {
+                    bail!("the target basis of the EKO must be the PDG basis");
                 }
-                _ => {}
+
+                Ok(EkoSlices::V2 {
+                    info: SliceInfo {
+                        fac0: metadata.mu20,
+                        pids0,
+                        x0: bases.inputgrid.unwrap_or_else(|| bases.xgrid.clone()),
+                        pids1,
+                        x1: bases.targetgrid.unwrap_or(bases.xgrid),
+                        lumi_id_types,
+                    },
+                    operators,
+                    file: File::open(path)
+                        .context(format!("unable to open '{}'", path.display()))?,
+                })
             }
         }
     }
 
-    // TODO: handle errors when files in the EKO are not present
+    fn read_v0(
+        mut archive: Archive<File>,
+        metadata: MetadataV0,
+        fac1: &[f64],
+    ) -> Result<EkoSlices> {
+        let indices = scale_indices(&metadata.q2_grid, fac1)?;
+
+        for entry in archive.entries()? {
+            let entry = entry?;
+
+            if file_name(&entry)?.as_deref() == Some("operators.npy.lz4") {
+                let operator = Array5::<f64>::read_npy(FrameDecoder::new(BufReader::new(entry)))?;
+
+                return Ok(EkoSlices::V0 {
+                    info: SliceInfo {
+                        fac0: metadata.q2_ref,
+                        pids0: metadata.inputpids,
+                        x0: metadata.inputgrid,
+                        pids1: metadata.targetpids,
+                        x1: metadata.targetgrid,
+                        lumi_id_types: "pdg_mc_ids".to_string(),
+                    },
+                    fac1: indices
+                        .iter()
+                        .map(|&index| metadata.q2_grid[index])
+                        .collect(),
+                    operator: operator.select(Axis(0), &indices),
+                });
+            }
+        }
+
+        bail!("the EKO does not contain `operators.npy.lz4`")
+    }
+
+    /// Reads the operator slice described by `operator` from the tar archive `file`.
+    fn read_slice<'a>(
+        file: &mut File,
+        info: &SliceInfo,
+        operator: &OperatorEntry,
+    ) -> Result<Slice<'a>> {
+        let OperatorEntry {
+            name,
+            fac1,
+            position,
+            size,
+        } = operator;
+
+        file.seek(SeekFrom::Start(*position))?;
+
+        let mut buffer = Vec::new();
+        FrameDecoder::new(BufReader::new(file.take(*size))).read_to_end(&mut buffer)?;
+        let operator: Array4<f64> = NpzReader::new(Cursor::new(buffer))?
+            .by_name("operator.npy")
+            .context(format!("unable to read the operator in '{name}'"))?;
+        let dim = (
+            info.pids1.len(),
+            info.x1.len(),
+            info.pids0.len(),
+            info.x0.len(),
+        );
+
+        if operator.dim() != dim {
+            bail!(
+                "the operator in '{}' has dimensions {:?}, but the metadata requires {:?}",
+                name,
+                operator.dim(),
+                dim
+            );
+        }
 
-    // TODO: the following should probably be a method of `Grid`
-    let mut ren1: Vec<_> = grid
-        .subgrids()
+        Ok((info.get(*fac1), CowArray::from(operator)))
+    }
+}
+
+#[cfg(feature = "evolve")]
+#[allow(clippy::too_many_arguments)]
+fn evolve_grid(
+    grid: &Grid,
+    eko: &Path,
+    eko2: Option<&Path>,
+    pdf: &Pdf,
+    orders: &[(u32, u32)],
+    xir: f64,
+    xif: f64,
+    basis: Option<&BasisRotation>,
+) -> Result<FkTable> {
+    use pineappl::evolution::AlphasTable;
+
+    let order_mask = order_mask(grid, orders);
+    let fac1: Vec<_> = grid
+        .evolve_info(&order_mask)
+        .fac1
         .iter()
-        .flat_map(|subgrid| {
-            subgrid
-                .mu2_grid()
-                .iter()
-                .map(|Mu2 { ren, .. }| xir * xir * ren)
-                .collect::<Vec<_>>()
-        })
+        .map(|&fac| xif * xif * fac)
         .collect();
-    ren1.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    ren1.dedup();
-    let ren1 = ren1;
-    let alphas: Vec<_> = ren1.iter().map(|&mur2| pdf.alphas_q2(mur2)).collect();
-
-    let info = OperatorInfo {
-        fac1: metadata.q2_grid.clone(),
-        pids0: metadata.inputpids,
-        x0: metadata.inputgrid,
-        pids1: metadata.targetpids,
-        x1: metadata.targetgrid,
-        fac0: metadata.q2_ref,
-        ren1,
-        alphas,
+    let mut eko_slices = eko::read(eko, &fac1)?;
+    let alphas_table = AlphasTable::from_grid(grid, xir, &mut |q2| pdf.alphas_q2(q2));
+
+    if let Some(eko2) = eko2 {
+        let mut eko_slices2 = eko::read(eko2, &fac1)?;
+
+        return Ok(grid.evolve_with_slice_iter2(
+            // both EKOs return their slices in the order of `fac1`, independently of the order in
+            // which their archives store them
+            rotate(eko_slices.iter_mut(), basis),
+            rotate(eko_slices2.iter_mut(), basis),
+            &order_mask,
+            (xir, xif),
+            &alphas_table,
+        )?);
+    }
+
+    Ok(grid.evolve_with_slice_iter(
+        rotate(eko_slices.iter_mut(), basis),
+        &order_mask,
+        (xir, xif),
+        &alphas_table,
+    )?)
+}
+
+#[cfg(feature = "dglap")]
+fn evolve_grid_builtin(
+    grid: &Grid,
+    order: &str,
+    pdf: &Pdf,
+    orders: &[(u32, u32)],
+    xir: f64,
+    xif: f64,
+    basis: Option<&BasisRotation>,
+) -> Result<FkTable> {
+    use anyhow::Context;
+    use ndarray::{Axis, CowArray};
+    use pineappl::dglap::{self, PerturbativeOrder, Theory};
+    use pineappl::evolution::{AlphasTable, OperatorSliceInfo};
+
+    let squared = |key: &str| -> Result<f64> {
+        let value: f64 = pdf
+            .set()
+            .entry(key)
+            .ok_or_else(|| anyhow!("the PDF set does not specify `{key}`"))?
+            .parse()
+            .context(format!("unable to parse `{key}` of the PDF set"))?;
+
+        Ok(value * value)
+    };
+    let theory = Theory {
+        order: if order == "LO" {
+            PerturbativeOrder::Lo
+        } else {
+            PerturbativeOrder::Nlo
+        },
+        fac0: squared("QMin")?,
+        thresholds: [squared("MCharm")?, squared("MBottom")?, squared("MTop")?],
+    };
+    let order_mask = order_mask(grid, orders);
+    let (info, operator) = dglap::evolution_operator(
+        &grid.evolve_info(&order_mask),
+        &theory,
         xir,
         xif,
-        lumi_id_types: "pdg_mc_ids".to_string(), // TODO: determine this from the operator
+        &mut |q2| pdf.alphas_q2(q2),
+    );
+    let alphas_table = AlphasTable {
+        ren1: info.ren1.clone(),
+        alphas: info.alphas.clone(),
     };
+    let slices = info
+        .fac1
+        .iter()
+        .zip(operator.axis_iter(Axis(0)))
+        .map(|(&fac1, operator)| {
+            Ok((
+                OperatorSliceInfo {
+                    fac0: info.fac0,
+                    pids0: info.pids0.clone(),
+                    x0: info.x0.clone(),
+                    fac1,
+                    pids1: info.pids1.clone(),
+                    x1: info.x1.clone(),
+                    lumi_id_types: info.lumi_id_types.clone(),
+                },
+                CowArray::from(operator),
+            ))
+        });
+
+    Ok(grid.evolve_with_slice_iter(
+        rotate(slices, basis),
+        &order_mask,
+        (xir, xif),
+        &alphas_table,
+    )?)
+}
 
-    let orders: Vec<_> = grid
-        .orders()
+/// Rotates each operator slice of `slices` into `basis`, if one is given.
+#[cfg(any(feature = "dglap", feature = "evolve"))]
+fn rotate<'a>(
+    slices: impl Iterator<Item = Result<Slice<'a>>> + 'a,
+    basis: Option<&'a BasisRotation>,
+) -> impl Iterator<Item = Result<Slice<'a>>> + 'a {
+    use ndarray::CowArray;
+
+    slices.map(move |slice| {
+        let (info, operator) = slice?;
+
+        if let Some(basis) = basis {
+            let (info, operator) = basis.rotate(&info, operator.view())?;
+
+            Ok((info, CowArray::from(operator)))
+        } else {
+            Ok((info, operator))
+        }
+    })
+}
+
+#[cfg(any(feature = "dglap", feature = "evolve"))]
+fn order_mask(grid: &Grid, orders: &[(u32, u32)]) -> Vec<bool> {
+    grid.orders()
         .iter()
         .map(|order| {
             orders.is_empty()
@@ -100,30 +570,87 @@ fn evolve_grid(
                     .iter()
                     .any(|other| (order.alphas == other.0) && (order.alpha == other.1))
         })
-        .collect();
-
-    Ok(grid.evolve(operator.view(), &info, &orders)?)
+        .collect()
 }
 
 #[cfg(not(feature = "evolve"))]
-fn evolve_grid(_: &Grid, _: &Path, _: &Pdf, _: &[(u32, u32)], _: f64, _: f64) -> Result<FkTable> {
+fn evolve_grid(
+    _: &Grid,
+    _: &Path,
+    _: Option<&Path>,
+    _: &Pdf,
+    _: &[(u32, u32)],
+    _: f64,
+    _: f64,
+    _: Option<&BasisRotation>,
+) -> Result<FkTable> {
     Err(anyhow!(
         "you need to install `pineappl` with feature `evolve`"
     ))
 }
 
+#[cfg(not(feature = "dglap"))]
+fn evolve_grid_builtin(
+    _: &Grid,
+    _: &str,
+    _: &Pdf,
+    _: &[(u32, u32)],
+    _: f64,
+    _: f64,
+    _: Option<&BasisRotation>,
+) -> Result<FkTable> {
+    Err(anyhow!(
+        "you need to install `pineappl` with feature `dglap`"
+    ))
+}
+
+/// Convolutes `grid`, which must be the grid of an FK table, with `pdf`, which is evaluated at the
+/// squared factorization scales given in `muf2` for the first and second initial state.
+fn convolute_per_initial_state(grid: &Grid, pdf: &mut Pdf, muf2: [f64; 2]) -> Result<Vec<f64>> {
+    // if the field 'Particle' is missing we assume it's a proton PDF
+    let pdf_pdg_id = pdf
+        .set()
+        .entry("Particle")
+        .map_or(Ok(2212), |string| string.parse::<i32>())?;
+    let x_max = pdf.x_max();
+    let x_min = pdf.x_min();
+    let pdf = &*pdf;
+    let xfx = |muf2| {
+        move |id, x, _| {
+            if x < x_min || x > x_max {
+                0.0
+            } else {
+                pdf.xfx_q2(id, x, muf2)
+            }
+        }
+    };
+    let mut xfx1 = xfx(muf2[0]);
+    let mut xfx2 = xfx(muf2[1]);
+    let mut alphas = |q2| pdf.alphas_q2(q2);
+    let mut cache = LumiCache::with_two(pdf_pdg_id, &mut xfx1, pdf_pdg_id, &mut xfx2, &mut alphas);
+
+    Ok(grid.convolute(&mut cache, &[], &[], &[], &[(1.0, 1.0)])?)
+}
+
 /// Evolve a grid with an evolution kernel operator to an FK table.
 #[derive(Parser)]
+#[command(
+    override_usage = "pineappl evolve [OPTIONS] <INPUT> <EKO> <OUTPUT> <PDFSET>
+       pineappl evolve [OPTIONS] --builtin <ORDER> <INPUT> <OUTPUT> <PDFSET>"
+)]
 pub struct Opts {
     /// Path to the input grid.
     #[arg(value_hint = ValueHint::FilePath)]
     input: PathBuf,
-    /// Path to the evolution kernel operator.
-    #[arg(value_hint = ValueHint::FilePath)]
-    eko: PathBuf,
-    /// Path to the converted grid.
-    #[arg(value_hint = ValueHint::FilePath)]
-    output: PathBuf,
+    /// Path to the evolution kernel operator, which must be omitted with `--builtin`, followed by
+    /// the path to the converted grid.
+    #[arg(
+        num_args = 1..=2,
+        required = true,
+        value_hint = ValueHint::FilePath,
+        value_names = ["EKO", "OUTPUT"]
+    )]
+    eko_output: Vec<PathBuf>,
     /// LHAPDF id or name of the PDF set to check the converted grid with.
     #[arg(value_parser = helpers::parse_pdfset)]
     pdfset: String,
@@ -151,12 +678,34 @@ pub struct Opts {
     /// Rescale the factorization scale with this factor.
     #[arg(default_value_t = 1.0, long)]
     xif: f64,
+    /// Compute the evolution kernel operator with the built-in DGLAP evolution at the given
+    /// order, using the starting scale and quark masses of the PDF set.
+    #[arg(
+        long,
+        value_name = "ORDER",
+        value_parser = PossibleValuesParser::new(["LO", "NLO"])
+    )]
+    builtin: Option<String>,
+    /// Rotate the FK table into the particle basis `pdg_mc_ids` or `evol`, or into the custom
+    /// basis given by a YAML file.
+    #[arg(long, value_name = "BASIS")]
+    basis: Option<String>,
+    /// Evolve the second initial state with this evolution kernel operator instead of `EKO`.
+    #[arg(conflicts_with = "builtin", long, value_hint = ValueHint::FilePath, value_name = "EKO2")]
+    eko2: Option<PathBuf>,
 }
 
 impl Subcommand for Opts {
     fn run(&self, cfg: &GlobalConfiguration) -> Result<ExitCode> {
         use prettytable::row;
 
+        // clap makes sure that there are one or two paths, the last one being the output
+        let output = match (self.eko_output.as_slice(), &self.builtin) {
+            ([_, output], None) | ([output], Some(_)) => output,
+            (_, Some(_)) => bail!("an EKO must not be given together with `--builtin`"),
+            (_, None) => bail!("an EKO must be given without `--builtin`"),
+        };
+
         let grid = helpers::read_grid(&self.input)?;
         let mut pdf = helpers::create_pdf(&self.pdfset)?;
         let results = helpers::convolute_scales(
@@ -168,19 +717,70 @@ impl Subcommand for Opts {
             &[(self.xir, self.xif)],
             ConvoluteMode::Normal,
             cfg.force_positive,
-        );
+        )?;
 
-        let fk_table = evolve_grid(&grid, &self.eko, &pdf, &self.orders, self.xir, self.xif)?;
-        let evolved_results = helpers::convolute_scales(
-            fk_table.grid(),
-            &mut pdf,
-            &[],
-            &[],
-            &[],
-            &[(1.0, 1.0)],
-            ConvoluteMode::Normal,
-            cfg.force_positive,
-        );
+        let basis = self
+            .basis
+            .as_deref()
+            .map(helpers::basis_rotation)
+            .transpose()?;
+        let fk_table = self.builtin.as_deref().map_or_else(
+            || {
+                evolve_grid(
+                    &grid,
+                    &self.eko_output[0],
+                    self.eko2.as_deref(),
+                    &pdf,
+                    &self.orders,
+                    self.xir,
+                    self.xif,
+                    basis.as_ref(),
+                )
+            },
+            |order| {
+                evolve_grid_builtin(
+                    &grid,
+                    order,
+                    &pdf,
+                    &self.orders,
+                    self.xir,
+                    self.xif,
+                    basis.as_ref(),
+                )
+            },
+        )?;
+
+        // FK tables in a custom basis must be translated back into the PDG basis to compare them
+        let check_grid = match basis {
+            Some(basis) if !matches!(basis.lumi_id_types(), "pdg_mc_ids" | "evol") => {
+                let mut check_grid = fk_table.grid().clone();
+                check_grid.set_lumis(
+                    check_grid
+                        .lumi()
+                        .iter()
+                        .map(|entry| LumiEntry::translate(entry, &|pid| basis.to_pdg_mc_ids(pid)))
+                        .collect(),
+                );
+                check_grid.set_key_value("lumi_id_types", "pdg_mc_ids");
+                Cow::Owned(check_grid)
+            }
+            _ => Cow::Borrowed(fk_table.grid()),
+        };
+        let evolved_results = if self.eko2.is_some() {
+            // the initial states may have to be evaluated at different scales
+            convolute_per_initial_state(&check_grid, &mut pdf, fk_table.muf2_per_initial_state())?
+        } else {
+            helpers::convolute_scales(
+                &check_grid,
+                &mut pdf,
+                &[],
+                &[],
+                &[],
+                &[(1.0, 1.0)],
+                ConvoluteMode::Normal,
+                cfg.force_positive,
+            )?
+        };
 
         // if both grids don't have the same number of bins there's a bug in the program
         assert_eq!(results.len(), evolved_results.len());
@@ -215,7 +815,7 @@ impl Subcommand for Opts {
         if different {
             Err(anyhow!("grids are different"))
         } else {
-            helpers::write_grid(&self.output, fk_table.grid())
+            helpers::write_grid(output, fk_table.grid())
         }
     }
 }
@@ -0,0 +1,263 @@
+use super::helpers::{self, ConvoluteMode, GlobalConfiguration, Subcommand};
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, ValueHint};
+use lhapdf::Pdf;
+use pineappl::grid::Grid;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[cfg(feature = "applgrid")]
+mod applgrid;
+#[cfg(feature = "fktable")]
+mod fktable;
+
+#[cfg(feature = "applgrid")]
+fn convert_into_applgrid(
+    _: &Path,
+    grid: Grid,
+    _: &Path,
+    tmp_output: &Path,
+    pdfset: &str,
+    _: &mut Pdf,
+    _: bool,
+) -> Result<(&'static str, Vec<f64>)> {
+    use super::import;
+    use pineappl_applgrid::ffi;
+
+    // APPLgrid only accepts paths that are valid UTF-8
+    let tmp_output = tmp_output
+        .to_str()
+        .ok_or_else(|| anyhow!("the path '{}' is not valid UTF-8", tmp_output.display()))?;
+
+    applgrid::convert_into_applgrid(grid, tmp_output)?;
+
+    // read the APPLgrid back in to check the conversion
+    let mut appl = ffi::make_grid(tmp_output)?;
+    let results = import::applgrid::convolute_applgrid(appl.pin_mut(), pdfset, 0);
+
+    Ok(("APPLgrid", results))
+}
+
+#[cfg(not(feature = "applgrid"))]
+fn convert_into_applgrid(
+    _: &Path,
+    _: Grid,
+    _: &Path,
+    _: &Path,
+    _: &str,
+    _: &mut Pdf,
+    _: bool,
+) -> Result<(&'static str, Vec<f64>)> {
+    Err(anyhow!(
+        "you need to install `pineappl` with feature `applgrid`"
+    ))
+}
+
+#[cfg(feature = "fktable")]
+fn convert_into_fktable(
+    input: &Path,
+    grid: Grid,
+    output: &Path,
+    tmp_output: &Path,
+    _: &str,
+    pdf: &mut Pdf,
+    force_positive: bool,
+) -> Result<(&'static str, Vec<f64>)> {
+    let converted = fktable::convert_into_fktable(input, grid, output, tmp_output)?;
+    let results = helpers::convolute(
+        &converted,
+        pdf,
+        &[],
+        &[],
+        &[],
+        1,
+        ConvoluteMode::Normal,
+        force_positive,
+    )?;
+
+    Ok(("FastKernel", results))
+}
+
+#[cfg(not(feature = "fktable"))]
+fn convert_into_fktable(
+    _: &Path,
+    _: Grid,
+    _: &Path,
+    _: &Path,
+    _: &str,
+    _: &mut Pdf,
+    _: bool,
+) -> Result<(&'static str, Vec<f64>)> {
+    Err(anyhow!(
+        "you need to install `pineappl` with feature `fktable`"
+    ))
+}
+
+/// Converts `grid` into the format given by the extension of `output`, but writes it into
+/// `tmp_output`, and reads it back in to convolute it with `pdf`.
+fn convert_into_grid(
+    input: &Path,
+    grid: Grid,
+    output: &Path,
+    tmp_output: &Path,
+    pdfset: &str,
+    pdf: &mut Pdf,
+    force_positive: bool,
+) -> Result<(&'static str, Vec<f64>)> {
+    if let Some(extension) = output.extension() {
+        if extension == "appl" || extension == "root" {
+            return convert_into_applgrid(
+                input,
+                grid,
+                output,
+                tmp_output,
+                pdfset,
+                pdf,
+                force_positive,
+            );
+        } else if extension == "tab"
+            || (extension == "gz"
+                && output
+                    .with_extension("")
+                    .extension()
+                    .map_or(false, |ext| ext == "tab"))
+        {
+            // `pineappl_fastnlo` can only read fastNLO tables, not create them
+            bail!("exporting to fastNLO tables is not supported");
+        } else if extension == "dat" {
+            return convert_into_fktable(
+                input,
+                grid,
+                output,
+                tmp_output,
+                pdfset,
+                pdf,
+                force_positive,
+            );
+        }
+    }
+
+    Err(anyhow!("could not detect file format"))
+}
+
+/// Returns the path of a hidden file in the directory of `output` with the same extension.
+fn tmp_path(output: &Path) -> Result<PathBuf> {
+    let file_name = output
+        .file_name()
+        .ok_or_else(|| anyhow!("the path '{}' is not a file", output.display()))?;
+    let mut tmp_file_name = OsString::from(".tmp-");
+    tmp_file_name.push(file_name);
+
+    Ok(output.with_file_name(tmp_file_name))
+}
+
+/// Converts `PineAPPL` grids to APPLgrid/FastKernel files.
+#[derive(Parser)]
+pub struct Opts {
+    /// Path to the input grid.
+    #[arg(value_hint = ValueHint::FilePath)]
+    input: PathBuf,
+    /// Path to the converted grid.
+    #[arg(value_hint = ValueHint::FilePath)]
+    output: PathBuf,
+    /// LHAPDF id or name of the PDF set to check the converted grid with.
+    #[arg(value_parser = helpers::parse_pdfset)]
+    pdfset: String,
+    /// Relative threshold between the table and the converted grid when comparison fails.
+    #[arg(default_value = "1e-10", long)]
+    accuracy: f64,
+    /// Set the number of fractional digits shown for absolute numbers.
+    #[arg(default_value_t = 7, long, value_name = "ABS")]
+    digits_abs: usize,
+    /// Set the number of fractional digits shown for relative numbers.
+    #[arg(default_value_t = 7, long, value_name = "REL")]
+    digits_rel: usize,
+}
+
+impl Subcommand for Opts {
+    fn run(&self, cfg: &GlobalConfiguration) -> Result<ExitCode> {
+        use float_cmp::approx_eq;
+        use prettytable::row;
+        use scopeguard::guard;
+
+        // never overwrite an existing file, like `helpers::write_grid`
+        if self.output.exists() {
+            bail!(
+                "unable to write '{}', because it already exists",
+                self.output.display()
+            );
+        }
+
+        let grid = helpers::read_grid(&self.input)?;
+        let mut pdf = helpers::create_pdf(&self.pdfset)?;
+        let results = helpers::convolute(
+            &grid,
+            &mut pdf,
+            &[],
+            &[],
+            &[],
+            1,
+            ConvoluteMode::Normal,
+            cfg.force_positive,
+        )?;
+
+        // write the converted grid into a temporary file next to `output`, which is renamed to
+        // `output` if the conversion is successful and removed otherwise
+        let tmp_output = guard(tmp_path(&self.output)?, |tmp_output| {
+            // the file may not exist, if the conversion failed before writing it
+            let _ = fs::remove_file(tmp_output);
+        });
+
+        // the converted grid is read back in to check the conversion
+        let (grid_type, converted_results) = convert_into_grid(
+            &self.input,
+            grid,
+            &self.output,
+            &tmp_output,
+            &self.pdfset,
+            &mut pdf,
+            cfg.force_positive,
+        )?;
+
+        // if both grids don't have the same number of bins there's bug in the program
+        assert_eq!(results.len(), converted_results.len());
+
+        let mut table = helpers::create_table();
+        table.set_titles(row![c => "b", "PineAPPL", grid_type, "rel. diff"]);
+
+        let mut different = false;
+
+        for (bin, (one, two)) in results.iter().zip(&converted_results).enumerate() {
+            // catches the case where both results are zero
+            let rel_diff = if approx_eq!(f64, *one, *two, ulps = 4) {
+                0.0
+            } else {
+                two / one - 1.0
+            };
+
+            if rel_diff.abs() > self.accuracy {
+                different = true;
+            }
+
+            table.add_row(row![
+                bin.to_string(),
+                r->format!("{:.*e}", self.digits_abs, one),
+                r->format!("{:.*e}", self.digits_abs, two),
+                r->format!("{:.*e}", self.digits_rel, rel_diff)
+            ]);
+        }
+
+        table.printstd();
+
+        if different {
+            Err(anyhow!("grids are different"))
+        } else {
+            fs::rename(&*tmp_output, &self.output)
+                .context(format!("unable to write '{}'", self.output.display()))?;
+
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
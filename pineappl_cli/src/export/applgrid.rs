@@ -0,0 +1,342 @@
+use anyhow::{anyhow, bail, Result};
+use float_cmp::approx_eq;
+use pineappl::evolution::BasisRotation;
+use pineappl::grid::{Grid, Order};
+use pineappl::subgrid::{Subgrid, SubgridEnum};
+use pineappl_applgrid::ffi::{self, grid};
+use std::borrow::Cow;
+use std::f64::consts::TAU;
+use std::iter;
+use std::pin::Pin;
+
+// name under which the luminosity function is registered with APPLgrid
+const LUMI_NAME: &str = "PineAPPL-Lumi.config";
+
+// the transformations of APPLgrid must match the ones PineAPPL uses for its interpolation grids
+const TRANSFORM: &str = "f2";
+const QTRANSFORM: &str = "h0";
+
+fn fy(x: f64) -> f64 {
+    5.0f64.mul_add(1.0 - x, -x.ln())
+}
+
+fn ftau(q2: f64) -> f64 {
+    (q2 / 0.0625).ln().ln()
+}
+
+fn convert_from_pdg_id(pid: i32) -> Result<i32> {
+    match pid {
+        -6..=-1 | 1..=6 => Ok(pid),
+        21 => Ok(0),
+        22 => Ok(7),
+        _ => Err(anyhow!("parton {pid} is not supported by APPLgrid")),
+    }
+}
+
+/// Returns the number of nodes, the minimum and the maximum of an equidistant grid in the
+/// transformed variable `f`, which contains all `values`.
+fn lattice(values: &[f64], f: fn(f64) -> f64, single: f64) -> (i32, f64, f64) {
+    let mut values = values.to_vec();
+    values.sort_by(f64::total_cmp);
+    values.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = 64));
+
+    if values.len() == 1 {
+        let value = values[0];
+        let other = value * single;
+
+        return (2, value.min(other), value.max(other));
+    }
+
+    let transformed: Vec<_> = values.iter().map(|&value| f(value)).collect();
+    let range = (transformed[0] - transformed[transformed.len() - 1]).abs();
+    let spacing = transformed
+        .windows(2)
+        .map(|window| (window[1] - window[0]).abs())
+        .fold(f64::INFINITY, f64::min);
+
+    #[allow(clippy::cast_possible_truncation)]
+    let nodes = (range / spacing).round() as i32 + 1;
+
+    (nodes, values[0], values[values.len() - 1])
+}
+
+/// Returns the index of `value` in `nodes`, if there is one.
+fn find_node(nodes: &[f64], value: f64) -> Option<usize> {
+    nodes
+        .iter()
+        .position(|&node| (node / value - 1.0).abs() < 1e-8)
+}
+
+/// Returns the x values of the hadronic initial state of `subgrid`.
+fn x_grid(subgrid: &SubgridEnum, swap: bool) -> Cow<[f64]> {
+    if swap {
+        subgrid.x2_grid()
+    } else {
+        subgrid.x1_grid()
+    }
+}
+
+struct Channel {
+    lumi: usize,
+    pids: Vec<(i32, i32)>,
+    factor: f64,
+}
+
+fn channels(grid: &Grid, dis: bool, swap: bool) -> Result<Vec<Channel>> {
+    let mut channels = Vec::new();
+
+    for (lumi, entry) in grid.lumi().iter().enumerate() {
+        let entry = entry.entry();
+        let pids = |a: i32, b: i32| -> Result<(i32, i32)> {
+            let (a, b) = if swap { (b, a) } else { (a, b) };
+
+            // for DIS APPLgrid only looks at the first parton
+            Ok((
+                convert_from_pdg_id(a)?,
+                if dis { 0 } else { convert_from_pdg_id(b)? },
+            ))
+        };
+
+        // APPLgrid's luminosity functions don't have factors, so a channel whose entries have
+        // different factors is split into several channels
+        if entry
+            .iter()
+            .all(|&(_, _, factor)| approx_eq!(f64, factor, entry[0].2, ulps = 4))
+        {
+            channels.push(Channel {
+                lumi,
+                pids: entry
+                    .iter()
+                    .map(|&(a, b, _)| pids(a, b))
+                    .collect::<Result<_>>()?,
+                factor: entry[0].2,
+            });
+        } else {
+            for &(a, b, factor) in entry {
+                channels.push(Channel {
+                    lumi,
+                    pids: vec![pids(a, b)?],
+                    factor,
+                });
+            }
+        }
+    }
+
+    Ok(channels)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn convert_subgrids(
+    grid: &Grid,
+    appl: Pin<&mut grid>,
+    channels: &[Channel],
+    order: usize,
+    appl_order: i32,
+    bin: usize,
+    bin_factor: f64,
+    dis: bool,
+    swap: bool,
+) -> Result<()> {
+    let alphas_factor = TAU.powi(grid.orders()[order].alphas.try_into().unwrap());
+    let subgrids: Vec<_> = (0..grid.lumi().len())
+        .map(|lumi| grid.subgrid(order, bin, lumi))
+        .collect();
+
+    if subgrids.iter().all(|subgrid| subgrid.is_empty()) {
+        return Ok(());
+    }
+
+    let mut q2_values = Vec::new();
+    let mut x_values = Vec::new();
+
+    for subgrid in subgrids.iter().filter(|subgrid| !subgrid.is_empty()) {
+        for mu2 in subgrid.mu2_grid().iter() {
+            if !approx_eq!(f64, mu2.ren, mu2.fac, ulps = 4) {
+                bail!(
+                    "APPLgrid does not support different renormalization and factorization scales"
+                );
+            }
+
+            q2_values.push(mu2.fac);
+        }
+
+        x_values.extend_from_slice(&x_grid(subgrid, swap));
+
+        if !dis {
+            x_values.extend_from_slice(&x_grid(subgrid, !swap));
+        }
+    }
+
+    let (nq2, q2_min, q2_max) = lattice(&q2_values, ftau, 2.0);
+    let (nx, x_min, x_max) = lattice(&x_values, fy, 0.5);
+
+    let mut igrid = ffi::make_igrid(
+        nq2,
+        q2_min,
+        q2_max,
+        (nq2 - 1).min(3),
+        nx,
+        x_min,
+        x_max,
+        (nx - 1).min(3),
+        TRANSFORM,
+        QTRANSFORM,
+        channels.len().try_into().unwrap(),
+        dis,
+    )?;
+
+    let q2_nodes: Vec<_> = (0..igrid.Ntau()).map(|i| igrid.getQ2(i)).collect();
+    let x_nodes: Vec<_> = (0..igrid.Ny1()).map(|i| igrid.getx1(i)).collect();
+    let node_error =
+        || anyhow!("unable to map the interpolation grid of bin {bin} onto the one of APPLgrid");
+
+    for (index, channel) in channels.iter().enumerate() {
+        let subgrid = subgrids[channel.lumi];
+
+        if subgrid.is_empty() {
+            continue;
+        }
+
+        let mu2_grid = subgrid.mu2_grid();
+        let x1_grid = x_grid(subgrid, swap);
+        let x2_grid = x_grid(subgrid, !swap);
+        let mut matrix = ffi::igrid_weightgrid(igrid.pin_mut(), index);
+
+        for ((imu2, ix1, ix2), value) in subgrid.indexed_iter() {
+            let (ix1, ix2) = if swap { (ix2, ix1) } else { (ix1, ix2) };
+            let itau = find_node(&q2_nodes, mu2_grid[imu2].fac).ok_or_else(node_error)?;
+            let iy1 = find_node(&x_nodes, x1_grid[ix1]).ok_or_else(node_error)?;
+            let iy2 = if dis {
+                0
+            } else {
+                find_node(&x_nodes, x2_grid[ix2]).ok_or_else(node_error)?
+            };
+
+            ffi::sparse_matrix_set(
+                matrix.as_mut(),
+                itau.try_into().unwrap(),
+                iy1.try_into().unwrap(),
+                iy2.try_into().unwrap(),
+                value * channel.factor * alphas_factor * bin_factor,
+            );
+        }
+    }
+
+    ffi::grid_add_igrid(appl, bin.try_into().unwrap(), appl_order, igrid);
+
+    Ok(())
+}
+
+pub fn convert_into_applgrid(mut grid: Grid, output: &str) -> Result<()> {
+    if grid.lumi_id_types() != "pdg_mc_ids" {
+        grid.rotate_basis(&BasisRotation::pdg_mc_ids())?;
+    }
+
+    let (dis, swap) = match (grid.has_pdf1(), grid.has_pdf2()) {
+        (true, true) => (false, false),
+        (true, false) => (true, false),
+        (false, true) => (true, true),
+        (false, false) => bail!("grids without hadronic initial states are not supported"),
+    };
+
+    // APPLgrid only stores the perturbative orders in the strong coupling
+    let orders: Vec<_> = grid
+        .orders()
+        .iter()
+        .enumerate()
+        .filter(|(_, order)| order.logxir == 0 && order.logxif == 0 && order.logxia == 0)
+        .collect();
+
+    if orders.is_empty() {
+        bail!("the grid does not contain orders without scale logarithms");
+    }
+
+    if orders
+        .iter()
+        .any(|(_, &Order { alpha, .. })| alpha != orders[0].1.alpha)
+    {
+        bail!("APPLgrid does not support orders with different powers of alpha");
+    }
+
+    // UNWRAP: `orders` isn't empty
+    let leading_order = orders.iter().map(|(_, order)| order.alphas).min().unwrap();
+    let nloops = orders.iter().map(|(_, order)| order.alphas).max().unwrap() - leading_order;
+
+    let channels = channels(&grid, dis, swap)?;
+    // the luminosity function is given by the number of channels followed by the index, the
+    // number of parton pairs and the parton pairs of each channel
+    let mut combinations = vec![i32::try_from(channels.len()).unwrap()];
+
+    for (index, channel) in channels.iter().enumerate() {
+        combinations.push(index.try_into().unwrap());
+        combinations.push(channel.pids.len().try_into().unwrap());
+        combinations.extend(channel.pids.iter().flat_map(|&(a, b)| [a, b]));
+    }
+
+    ffi::make_lumi_pdf(LUMI_NAME, &combinations)?;
+
+    let bin_info = grid.bin_info();
+    let limits = bin_info.limits();
+    let normalizations = bin_info.normalizations();
+
+    // one-dimensional distributions without gaps keep their bin limits, otherwise the bins are
+    // enumerated
+    let contiguous = bin_info.dimensions() == 1
+        && limits
+            .windows(2)
+            .all(|window| approx_eq!(f64, window[0][0].1, window[1][0].0, ulps = 4));
+    let (obs, bin_factors): (Vec<_>, Vec<_>) = if contiguous {
+        (
+            limits
+                .iter()
+                .map(|limits| limits[0].0)
+                .chain(iter::once(limits[limits.len() - 1][0].1))
+                .collect(),
+            limits
+                .iter()
+                .zip(&normalizations)
+                .map(|(limits, normalization)| (limits[0].1 - limits[0].0) / normalization)
+                .collect(),
+        )
+    } else {
+        (
+            (0..=limits.len())
+                .map(|bin| f64::from(u32::try_from(bin).unwrap()))
+                .collect(),
+            normalizations
+                .iter()
+                .map(|normalization| 1.0 / normalization)
+                .collect(),
+        )
+    };
+
+    let mut appl = ffi::make_empty_grid(
+        &obs,
+        LUMI_NAME,
+        leading_order.try_into().unwrap(),
+        nloops.try_into().unwrap(),
+        TRANSFORM,
+        QTRANSFORM,
+        dis,
+    )?;
+
+    for &(order, &Order { alphas, .. }) in &orders {
+        for (bin, &bin_factor) in bin_factors.iter().enumerate() {
+            convert_subgrids(
+                &grid,
+                appl.pin_mut(),
+                &channels,
+                order,
+                (alphas - leading_order).try_into().unwrap(),
+                bin,
+                bin_factor,
+                dis,
+                swap,
+            )?;
+        }
+    }
+
+    ffi::grid_write(appl.pin_mut(), output)?;
+
+    Ok(())
+}
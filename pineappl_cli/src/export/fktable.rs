@@ -0,0 +1,233 @@
+use super::super::import::fktable;
+use anyhow::{anyhow, Context, Result};
+use ndarray::{s, Array4};
+use pineappl::fk_table::FkTable;
+use pineappl::grid::Grid;
+use pineappl::pids;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// FK tables are always in the flavor basis
+const BASIS: [i32; 14] = [
+    22, 100, 21, 200, 203, 208, 215, 224, 235, 103, 108, 115, 124, 135,
+];
+
+fn write_header(
+    fk_table: &FkTable,
+    setname: &str,
+    hadronic: bool,
+    x_grid: &[f64],
+    flavor_mask: &[bool],
+    writer: &mut impl Write,
+) -> Result<()> {
+    writeln!(
+        writer,
+        "{{GridDesc___________________________________________________"
+    )?;
+    writeln!(
+        writer,
+        "{}",
+        fk_table
+            .key_values()
+            .and_then(|key_values| key_values.get("description"))
+            .map_or(setname, String::as_str)
+    )?;
+    writeln!(
+        writer,
+        "_VersionInfo________________________________________________"
+    )?;
+    writeln!(writer, "*PINEAPPL: {}", env!("CARGO_PKG_VERSION"))?;
+    writeln!(
+        writer,
+        "_GridInfo___________________________________________________"
+    )?;
+    writeln!(writer, "*SETNAME: {setname}")?;
+    writeln!(writer, "*HADRONIC: {}", u8::from(hadronic))?;
+    writeln!(writer, "*NDATA: {}", fk_table.bins())?;
+    writeln!(writer, "*NX: {}", x_grid.len())?;
+    writeln!(
+        writer,
+        "{{FlavourMap_________________________________________________"
+    )?;
+
+    for row in flavor_mask.chunks(BASIS.len()) {
+        let row: Vec<_> = row.iter().map(|&mask| u8::from(mask).to_string()).collect();
+        writeln!(writer, "{}", row.join(" "))?;
+    }
+
+    writeln!(
+        writer,
+        "_TheoryInfo_________________________________________________"
+    )?;
+    writeln!(writer, "*Q0: {:e}", fk_table.muf2().sqrt())?;
+    writeln!(
+        writer,
+        "{{xGrid______________________________________________________"
+    )?;
+
+    for x in x_grid {
+        writeln!(writer, "{x:e}")?;
+    }
+
+    Ok(())
+}
+
+fn write_fktable(fk_table: &FkTable, setname: &str, writer: &mut impl Write) -> Result<()> {
+    let grid = fk_table.grid();
+    let has_pdfs = [grid.has_pdf1(), grid.has_pdf2()];
+    let hadronic = has_pdfs[0] && has_pdfs[1];
+
+    // the hadron of DIS FK tables is always the first initial state
+    let swap = match has_pdfs {
+        [true, _] => false,
+        [false, true] => true,
+        [false, false] => return Err(anyhow!("the FK table has no hadronic initial states")),
+    };
+
+    let translate = |pid: i32| -> Result<Vec<(i32, f64)>> {
+        match grid.lumi_id_types() {
+            "evol" => Ok(vec![(pid, 1.0)]),
+            "pdg_mc_ids" => Ok(pids::pdg_mc_ids_to_evol(pid)),
+            lumi_id_types => Err(anyhow!("particle basis `{lumi_id_types}` is not supported")),
+        }
+    };
+
+    let x_grid = fk_table.x_grid();
+    let nx2 = if hadronic { x_grid.len() } else { 1 };
+    let nflavors = if hadronic {
+        BASIS.len() * BASIS.len()
+    } else {
+        BASIS.len()
+    };
+    let table = fk_table.table();
+    let normalizations = fk_table.bin_normalizations();
+    let mut kernel = Array4::zeros((fk_table.bins(), x_grid.len(), nx2, nflavors));
+
+    for (lumi, &(a, b)) in fk_table.lumi().iter().enumerate() {
+        let (a, b) = if swap { (b, a) } else { (a, b) };
+        let mut lumi_table = table.slice(s![.., lumi, .., ..]).to_owned();
+
+        if swap {
+            lumi_table.swap_axes(1, 2);
+        }
+
+        for (pid_a, factor_a) in translate(a)? {
+            for (pid_b, factor_b) in if hadronic {
+                translate(b)?
+            } else {
+                vec![(b, 1.0)]
+            } {
+                let flavor_a = BASIS
+                    .iter()
+                    .position(|&pid| pid == pid_a)
+                    .ok_or_else(|| anyhow!("parton {pid_a} is not supported"))?;
+                let flavor = if hadronic {
+                    let flavor_b = BASIS
+                        .iter()
+                        .position(|&pid| pid == pid_b)
+                        .ok_or_else(|| anyhow!("parton {pid_b} is not supported"))?;
+
+                    flavor_a * BASIS.len() + flavor_b
+                } else {
+                    flavor_a
+                };
+
+                kernel
+                    .slice_mut(s![.., .., .., flavor])
+                    .scaled_add(factor_a * factor_b, &lumi_table);
+            }
+        }
+    }
+
+    let flavor_mask: Vec<_> = (0..nflavors)
+        .map(|flavor| {
+            kernel
+                .slice(s![.., .., .., flavor])
+                .iter()
+                .any(|&v| v != 0.0)
+        })
+        .collect();
+
+    write_header(fk_table, setname, hadronic, &x_grid, &flavor_mask, writer)?;
+
+    writeln!(
+        writer,
+        "{{FastKernel_________________________________________________"
+    )?;
+
+    for (bin, normalization) in normalizations.iter().enumerate() {
+        for x1 in 0..x_grid.len() {
+            for x2 in 0..nx2 {
+                let values = kernel.slice(s![bin, x1, x2, ..]);
+
+                if values.iter().all(|&value| value == 0.0) {
+                    continue;
+                }
+
+                // the importer multiplies with `x1` and `x2` and the bins of the imported FK table
+                // have unit normalizations
+                let x = x_grid[x1] * if hadronic { x_grid[x2] } else { 1.0 } * normalization;
+                let values: Vec<_> = values
+                    .iter()
+                    .map(|value| format!("{:e}", value / x))
+                    .collect();
+
+                if hadronic {
+                    writeln!(writer, "{bin} {x1} {x2} {}", values.join(" "))?;
+                } else {
+                    writeln!(writer, "{bin} {x1} {}", values.join(" "))?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts `grid` into an FK table with the set name given by `output`, writes it into
+/// `tmp_output` and reads it back in.
+pub fn convert_into_fktable(
+    input: &Path,
+    grid: Grid,
+    output: &Path,
+    tmp_output: &Path,
+) -> Result<Grid> {
+    let fk_table =
+        FkTable::try_from(grid).context(format!("'{}' is not an FK table", input.display()))?;
+
+    // the set name is given by the file name, for example `FK_<setname>.dat`
+    let stem = output
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| {
+            anyhow!(
+                "unable to determine the set name from '{}'",
+                output.display()
+            )
+        })?;
+    let setname = stem.strip_prefix("FK_").unwrap_or(stem);
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(tmp_output)
+        .context(format!("unable to write '{}'", tmp_output.display()))?;
+    let mut writer = BufWriter::new(file);
+
+    write_fktable(&fk_table, setname, &mut writer)?;
+    writer.flush()?;
+
+    let grid = fk_table.grid();
+    let dis_pid = if !grid.has_pdf2() {
+        grid.initial_state_2()
+    } else if !grid.has_pdf1() {
+        grid.initial_state_1()
+    } else {
+        // unused for hadronic FK tables
+        0
+    };
+
+    // read the FK table back in to check the conversion
+    fktable::convert_fktable(tmp_output, dis_pid)
+}
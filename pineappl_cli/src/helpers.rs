@@ -1,15 +1,18 @@
-use anyhow::{ensure, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use clap::Parser;
 use enum_dispatch::enum_dispatch;
 use lhapdf::{Pdf, PdfSet};
-use ndarray::Array3;
+use ndarray::{Array2, Array3};
+use pineappl::evolution::BasisRotation;
 use pineappl::grid::Grid;
 use pineappl::lumi::LumiCache;
+use pineappl::observable::Observables;
 use prettytable::format::{FormatBuilder, LinePosition, LineSeparator};
 use prettytable::Table;
+use serde::Deserialize;
 use std::fs::{File, OpenOptions};
 use std::iter;
-use std::ops::RangeInclusive;
+use std::ops::{Deref, RangeInclusive};
 use std::path::Path;
 use std::process::ExitCode;
 
@@ -101,6 +104,93 @@ pub const SCALES_VECTOR: [(f64, f64); 9] = [
     (0.5, 2.0),
 ];
 
+/// Scale variations, given as pairs of renormalization and factorization scale factors `(xir,
+/// xif)`. The first pair is the central prediction and all pairs together define the envelope.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScaleVariations(Vec<(f64, f64)>);
+
+impl Deref for ScaleVariations {
+    type Target = [(f64, f64)];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Parses a scale-variation prescription. The argument is either
+///
+/// - the number of points, `1`, `3`, `7` or `9`, of the usual prescriptions varying both scales,
+/// - `3-ren` or `3-fac`, which varies only the renormalization or only the factorization scale,
+/// - `31-corr`, which varies both scales together in 30 logarithmically equidistant steps between
+///   a half and twice the central scale,
+///
+/// optionally followed by `@FACTOR` to vary the scales by `FACTOR` instead of `2`, or an explicit
+/// list of comma-separated `xir:xif` pairs, for instance `1:1,1.5:1.5,0.75:0.75`.
+pub fn parse_scales(argument: &str) -> Result<ScaleVariations> {
+    if argument.contains(':') {
+        let scales = argument
+            .split(',')
+            .map(|pair| {
+                let (xir, xif) = pair
+                    .split_once(':')
+                    .context(format!("unable to parse scale pair '{pair}'; missing ':'"))?;
+                let xir = str::parse::<f64>(xir)
+                    .context(format!("unable to parse scale pair '{pair}'"))?;
+                let xif = str::parse::<f64>(xif)
+                    .context(format!("unable to parse scale pair '{pair}'"))?;
+
+                ensure!(
+                    xir.is_finite() && xif.is_finite() && (xir > 0.0) && (xif > 0.0),
+                    "scale factors in '{}' must be positive",
+                    pair
+                );
+
+                Ok((xir, xif))
+            })
+            .collect::<Result<_>>()?;
+
+        return Ok(ScaleVariations(scales));
+    }
+
+    let (prescription, factor) =
+        argument
+            .split_once('@')
+            .map_or(Ok((argument, 2.0)), |(prescription, factor)| {
+                str::parse::<f64>(factor)
+                    .context(format!("unable to parse scale factor '{factor}'"))
+                    .map(|factor| (prescription, factor))
+            })?;
+
+    ensure!(
+        factor.is_finite() && (factor > 0.0),
+        "scale factor '{}' must be positive",
+        factor
+    );
+
+    let scales: Vec<_> = match prescription {
+        "1" | "3" | "7" | "9" => SCALES_VECTOR[0..str::parse(prescription).unwrap()].to_vec(),
+        "3-ren" => vec![(1.0, 1.0), (2.0, 1.0), (0.5, 1.0)],
+        "3-fac" => vec![(1.0, 1.0), (1.0, 2.0), (1.0, 0.5)],
+        "31-corr" => iter::once(0)
+            .chain((1..=15).flat_map(|step| [step, -step]))
+            .map(|step| {
+                let scale = (f64::from(step) / 15.0).exp2();
+                (scale, scale)
+            })
+            .collect(),
+        _ => bail!("unknown scale-variation prescription '{}'", prescription),
+    };
+    // the prescriptions above are written for a factor of two, which we rescale
+    let exponent = factor.log2();
+
+    Ok(ScaleVariations(
+        scales
+            .into_iter()
+            .map(|(xir, xif)| (xir.powf(exponent), xif.powf(exponent)))
+            .collect(),
+    ))
+}
+
 pub fn labels_and_units(grid: &Grid, integrated: bool) -> (Vec<(String, &str)>, &str, &str) {
     let key_values = grid.key_values();
 
@@ -139,6 +229,7 @@ pub enum ConvoluteMode {
     Asymmetry,
     Integrated,
     Normal,
+    Observables,
 }
 
 pub fn convolute_scales(
@@ -150,7 +241,7 @@ pub fn convolute_scales(
     scales: &[(f64, f64)],
     mode: ConvoluteMode,
     force_positive: bool,
-) -> Vec<f64> {
+) -> Result<Vec<f64>> {
     let orders: Vec<_> = grid
         .orders()
         .iter()
@@ -184,9 +275,9 @@ pub fn convolute_scales(
     };
     let mut alphas = |q2| lhapdf.alphas_q2(q2);
     let mut cache = LumiCache::with_one(pdf_pdg_id, &mut pdf, &mut alphas);
-    let mut results = grid.convolute(&mut cache, &orders, bins, lumis, scales);
+    let mut results = grid.convolute(&mut cache, &orders, bins, lumis, scales)?;
 
-    match mode {
+    Ok(match mode {
         ConvoluteMode::Asymmetry => {
             let bin_count = grid.bin_info().bins();
 
@@ -223,7 +314,16 @@ pub fn convolute_scales(
             results
         }
         ConvoluteMode::Normal => results,
-    }
+        ConvoluteMode::Observables => {
+            // calculating the observables for a subset of bins doesn't work
+            ensure!(
+                bins.is_empty() || (bins.len() == grid.bin_info().bins()),
+                "observables can only be calculated for all bins"
+            );
+
+            check_observables(grid)?.evaluate(&results, scales.len())
+        }
+    })
 }
 
 pub fn convolute(
@@ -235,7 +335,7 @@ pub fn convolute(
     scales: usize,
     mode: ConvoluteMode,
     force_positive: bool,
-) -> Vec<f64> {
+) -> Result<Vec<f64>> {
     convolute_scales(
         grid,
         lhapdf,
@@ -248,7 +348,11 @@ pub fn convolute(
     )
 }
 
-pub fn convolute_limits(grid: &Grid, bins: &[usize], mode: ConvoluteMode) -> Vec<Vec<(f64, f64)>> {
+pub fn convolute_limits(
+    grid: &Grid,
+    bins: &[usize],
+    mode: ConvoluteMode,
+) -> Result<Vec<Vec<(f64, f64)>>> {
     let limits: Vec<_> = grid
         .bin_info()
         .limits()
@@ -257,9 +361,66 @@ pub fn convolute_limits(grid: &Grid, bins: &[usize], mode: ConvoluteMode) -> Vec
         .filter_map(|(index, limits)| (bins.is_empty() || bins.contains(&index)).then_some(limits))
         .collect();
 
-    match mode {
+    Ok(match mode {
         ConvoluteMode::Asymmetry => limits[limits.len() / 2..].to_vec(),
         ConvoluteMode::Integrated | ConvoluteMode::Normal => limits,
+        ConvoluteMode::Observables => {
+            ensure!(
+                bins.is_empty() || (bins.len() == grid.bin_info().bins()),
+                "observables can only be calculated for all bins"
+            );
+
+            check_observables(grid)?
+                .reference_bins()
+                .into_iter()
+                .map(|bin| limits[bin].clone())
+                .collect()
+        }
+    })
+}
+
+pub fn check_observables(grid: &Grid) -> Result<Observables> {
+    grid.observables()?.ok_or_else(|| {
+        anyhow!(
+            "the grid does not define observables, set them with `pineappl set --entry observables <EXPRESSIONS>`"
+        )
+    })
+}
+
+/// Custom particle basis, read from a YAML file.
+#[derive(Deserialize)]
+struct CustomBasis {
+    lumi_id_types: String,
+    pids: Vec<i32>,
+    pdg_mc_ids: Vec<i32>,
+    rotation: Vec<Vec<f64>>,
+}
+
+/// Returns the rotation into the particle basis `basis`, which is either `pdg_mc_ids`, `evol` or the
+/// path of a YAML file defining a custom basis.
+pub fn basis_rotation(basis: &str) -> Result<BasisRotation> {
+    match basis {
+        "pdg_mc_ids" => Ok(BasisRotation::pdg_mc_ids()),
+        "evol" => Ok(BasisRotation::evol()),
+        path => {
+            let basis: CustomBasis = serde_yaml::from_reader(
+                File::open(path).context(format!("unable to open basis file `{path}`"))?,
+            )
+            .context(format!("unable to read basis file `{path}`"))?;
+            let rows = basis.rotation.len();
+            let rotation = Array2::from_shape_vec(
+                (rows, basis.rotation.first().map_or(0, Vec::len)),
+                basis.rotation.into_iter().flatten().collect(),
+            )
+            .context("the rows of the basis rotation must have the same length")?;
+
+            Ok(BasisRotation::new(
+                &basis.lumi_id_types,
+                basis.pids,
+                basis.pdg_mc_ids,
+                rotation,
+            )?)
+        }
     }
 }
 
@@ -269,7 +430,7 @@ pub fn convolute_subgrid(
     order: usize,
     bin: usize,
     lumi: usize,
-) -> Array3<f64> {
+) -> Result<Array3<f64>> {
     // if the field 'Particle' is missing we assume it's a proton PDF
     let pdf_pdg_id = lhapdf
         .set()
@@ -289,7 +450,7 @@ pub fn convolute_subgrid(
     let mut alphas = |q2| lhapdf.alphas_q2(q2);
     let mut cache = LumiCache::with_one(pdf_pdg_id, &mut pdf, &mut alphas);
 
-    grid.convolute_subgrid(&mut cache, order, bin, lumi, 1.0, 1.0)
+    Ok(grid.convolute_subgrid(&mut cache, order, bin, lumi, 1.0, 1.0)?)
 }
 
 pub fn parse_pdfset(argument: &str) -> std::result::Result<String, String> {
@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::fs::File;
+use std::path::Path;
+
+/// A single table of a `HEPData` record, as it is stored in the YAML file of a submission.
+#[derive(Deserialize)]
+pub struct Table {
+    pub independent_variables: Vec<Variable>,
+    pub dependent_variables: Vec<Variable>,
+}
+
+#[derive(Deserialize)]
+pub struct Variable {
+    pub header: Header,
+    pub values: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+pub struct Header {
+    pub name: String,
+    #[serde(default)]
+    pub units: String,
+}
+
+#[derive(Deserialize)]
+pub struct Value {
+    pub low: Option<f64>,
+    pub high: Option<f64>,
+    #[serde(rename = "value")]
+    pub central: Option<serde_yaml::Value>,
+    #[serde(default)]
+    pub errors: Vec<Uncertainty>,
+}
+
+#[derive(Deserialize)]
+pub struct Uncertainty {
+    #[serde(default)]
+    pub label: String,
+    pub symerror: Option<serde_yaml::Value>,
+    pub asymerror: Option<AsymmetricUncertainty>,
+}
+
+#[derive(Deserialize)]
+pub struct AsymmetricUncertainty {
+    pub plus: serde_yaml::Value,
+    pub minus: serde_yaml::Value,
+}
+
+/// Converts a number of a `HEPData` table to `f64`. Strings with a trailing `%` are interpreted
+/// relative to `central`.
+pub fn number(value: &serde_yaml::Value, central: f64) -> Result<f64> {
+    match value {
+        serde_yaml::Value::Number(number) => number.as_f64(),
+        serde_yaml::Value::String(string) => {
+            let string = string.trim();
+
+            string.strip_suffix('%').map_or_else(
+                || string.parse().ok(),
+                |percentage| {
+                    percentage
+                        .trim()
+                        .parse::<f64>()
+                        .ok()
+                        .map(|percentage| percentage * central.abs() / 100.0)
+                },
+            )
+        }
+        _ => None,
+    }
+    .ok_or_else(|| anyhow!("unable to convert '{:?}' to a number", value))
+}
+
+pub fn read_table(path: &Path) -> Result<Table> {
+    serde_yaml::from_reader(File::open(path)?)
+        .with_context(|| format!("unable to read HEPData table '{}'", path.display()))
+}
+
+impl Table {
+    /// Returns the limits of each independent variable for every bin of the table.
+    pub fn limits(&self) -> Result<Vec<Vec<(f64, f64)>>> {
+        let bins = self
+            .independent_variables
+            .first()
+            .map_or(0, |variable| variable.values.len());
+
+        (0..bins)
+            .map(|bin| {
+                self.independent_variables
+                    .iter()
+                    .map(|variable| {
+                        let value = variable.values.get(bin).ok_or_else(|| {
+                            anyhow!(
+                                "independent variable '{}' has fewer than {} values",
+                                variable.header.name,
+                                bins
+                            )
+                        })?;
+
+                        value.low.zip(value.high).ok_or_else(|| {
+                            anyhow!(
+                                "bin {} of independent variable '{}' has no 'low' and 'high' limits",
+                                bin,
+                                variable.header.name
+                            )
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
@@ -10,11 +10,11 @@ use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
 #[cfg(feature = "applgrid")]
-mod applgrid;
+pub mod applgrid;
 #[cfg(feature = "fastnlo")]
 mod fastnlo;
 #[cfg(feature = "fktable")]
-mod fktable;
+pub mod fktable;
 
 #[cfg(feature = "applgrid")]
 fn convert_applgrid(
@@ -268,7 +268,7 @@ impl Subcommand for Opts {
                 scale_variations,
                 ConvoluteMode::Normal,
                 cfg.force_positive,
-            );
+            )?;
 
             // if both grids don't have the same number of bins there's bug in the program
             assert_eq!(results.len(), reference_results.len());
@@ -74,13 +74,13 @@ pub fn convert_applgrid(grid: Pin<&mut grid>, alpha: u32, dis_pid: i32) -> Resul
     if grid.calculation() == ffi::grid_CALCULATION::AMCATNLO {
         alphas_factor = 2.0 * TAU;
         orders = if grid.nloops() == 0 {
-            vec![Order::new(leading_order, alpha, 0, 0)]
+            vec![Order::new(leading_order, alpha, 0, 0, 0)]
         } else if grid.nloops() == 1 {
             vec![
-                Order::new(leading_order + 1, alpha, 0, 0), // NLO
-                Order::new(leading_order + 1, alpha, 1, 0), // NLO mur
-                Order::new(leading_order + 1, alpha, 0, 1), // NLO muf
-                Order::new(leading_order, alpha, 0, 0),     // LO
+                Order::new(leading_order + 1, alpha, 0, 0, 0), // NLO
+                Order::new(leading_order + 1, alpha, 1, 0, 0), // NLO mur
+                Order::new(leading_order + 1, alpha, 0, 1, 0), // NLO muf
+                Order::new(leading_order, alpha, 0, 0, 0),     // LO
             ]
         } else {
             unimplemented!("nloops = {} is not supported", grid.nloops());
@@ -88,7 +88,15 @@ pub fn convert_applgrid(grid: Pin<&mut grid>, alpha: u32, dis_pid: i32) -> Resul
     } else if grid.calculation() == ffi::grid_CALCULATION::STANDARD {
         alphas_factor = 1.0 / TAU;
         orders = (0..=grid.nloops())
-            .map(|power| Order::new(leading_order + u32::try_from(power).unwrap(), alpha, 0, 0))
+            .map(|power| {
+                Order::new(
+                    leading_order + u32::try_from(power).unwrap(),
+                    alpha,
+                    0,
+                    0,
+                    0,
+                )
+            })
             .collect();
     } else {
         unimplemented!("calculation is not supported");
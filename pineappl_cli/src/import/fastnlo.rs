@@ -104,6 +104,7 @@ fn convert_coeff_add_fix(
             alpha,
             logxir: 0,
             logxif: 0,
+            logxia: 0,
         }],
         (0..=bins)
             .map(|limit| u16::try_from(limit).unwrap().into())
@@ -220,12 +221,12 @@ fn convert_coeff_add_flex(
 
     let alphas = table_as_add_base.GetNpow().try_into().unwrap();
     let orders: Vec<_> = [
-        Order::new(alphas, alpha, 0, 0),
-        Order::new(alphas, alpha, 1, 0),
-        Order::new(alphas, alpha, 0, 1),
-        Order::new(alphas, alpha, 2, 0),
-        Order::new(alphas, alpha, 0, 2),
-        Order::new(alphas, alpha, 1, 1),
+        Order::new(alphas, alpha, 0, 0, 0),
+        Order::new(alphas, alpha, 1, 0, 0),
+        Order::new(alphas, alpha, 0, 1, 0),
+        Order::new(alphas, alpha, 2, 0, 0),
+        Order::new(alphas, alpha, 0, 2, 0),
+        Order::new(alphas, alpha, 1, 1, 0),
     ]
     .into_iter()
     .take(match table.GetNScaleDep() {
@@ -103,6 +103,7 @@ fn read_fktable(reader: impl BufRead, dis_pid: i32) -> Result<Grid> {
                         alpha: 0,
                         logxir: 0,
                         logxif: 0,
+                        logxia: 0,
                     }],
                     (0..=ndata).map(Into::into).collect(),
                     SubgridParams::default(),
@@ -256,9 +257,14 @@ fn read_fktable(reader: impl BufRead, dis_pid: i32) -> Result<Grid> {
 }
 
 pub fn convert_fktable(input: &Path, dis_pid: i32) -> Result<Grid> {
-    let reader = GzDecoder::new(File::open(input)?);
+    let mut reader = BufReader::new(File::open(input)?);
 
-    let mut archive = Archive::new(reader);
+    // FK tables are either plain text files or gzip-compressed tarballs containing them
+    if !reader.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        return read_fktable(reader, dis_pid);
+    }
+
+    let mut archive = Archive::new(GzDecoder::new(reader));
 
     for entry in archive.entries()? {
         let file = entry.unwrap();
@@ -1,7 +1,10 @@
 use super::helpers::{self, GlobalConfiguration, Subcommand};
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::{ArgGroup, Parser, ValueHint};
 use itertools::Itertools;
+use pineappl::fk_table::{FkAssumptions, FkConditionCheck, FkTable};
+use pineappl::lumi::LumiCache;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
@@ -12,6 +15,24 @@ pub struct Opts {
     /// Path to the input grid.
     #[arg(value_hint = ValueHint::FilePath)]
     input: PathBuf,
+    /// Check that the PDF set satisfies the assumptions the FK table was optimized with, or the
+    /// ones given with `--assumptions` for an unoptimized FK table, for which also the resulting
+    /// bias of the predictions is shown.
+    #[arg(
+        group = "mode",
+        long,
+        value_name = "PDFSET",
+        value_parser = helpers::parse_pdfset
+    )]
+    check_fk_assumptions: Option<String>,
+    /// Assumptions checked by `--check-fk-assumptions` for an unoptimized FK table.
+    #[arg(
+        long,
+        requires = "check_fk_assumptions",
+        value_parser = PossibleValuesParser::new(["Nf6Ind", "Nf6Sym", "Nf5Ind", "Nf5Sym", "Nf4Ind", "Nf4Sym", "Nf3Ind", "Nf3Sym"])
+            .map(|assumptions| assumptions.parse::<FkAssumptions>().unwrap())
+    )]
+    assumptions: Option<FkAssumptions>,
     /// For each order print a list of the largest EW order.
     #[arg(group = "mode", long)]
     ew: bool,
@@ -33,11 +54,16 @@ impl Subcommand for Opts {
     fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
         let mut grid = helpers::read_grid(&self.input)?;
 
-        if self.ew || self.qcd {
+        if let Some(pdfset) = &self.check_fk_assumptions {
+            let fk_table = FkTable::try_from(grid)
+                .context(format!("'{}' is not an FK table", self.input.display()))?;
+
+            return check_fk_assumptions(&fk_table, pdfset, self.assumptions);
+        } else if self.ew || self.qcd {
             let mut sorted_grid_orders: Vec<_> = grid
                 .orders()
                 .iter()
-                .filter(|order| (order.logxir == 0) && (order.logxif == 0))
+                .filter(|order| (order.logxir == 0) && (order.logxif == 0) && (order.logxia == 0))
                 .collect();
             sorted_grid_orders.sort();
 
@@ -109,3 +135,88 @@ impl Subcommand for Opts {
         Ok(ExitCode::SUCCESS)
     }
 }
+
+fn check_fk_assumptions(
+    fk_table: &FkTable,
+    pdfset: &str,
+    assumptions: Option<FkAssumptions>,
+) -> Result<ExitCode> {
+    use float_cmp::approx_eq;
+    use prettytable::row;
+
+    let assumptions = match (fk_table.assumptions(), assumptions) {
+        (Some(optimized), Some(_)) => {
+            bail!("the FK table was already optimized with `{optimized}`, `--assumptions` requires an unoptimized FK table")
+        }
+        (Some(assumptions), None) | (None, Some(assumptions)) => assumptions,
+        (None, None) => {
+            bail!("the FK table was not optimized with any assumptions, they must be given with `--assumptions`")
+        }
+    };
+    let pdf = helpers::create_pdf(pdfset)?;
+    // if the field 'Particle' is missing we assume it's a proton PDF
+    let pdf_pdg_id = pdf
+        .set()
+        .entry("Particle")
+        .map_or(Ok(2212), |string| string.parse::<i32>())?;
+    let mut xfx = |id, x, q2| pdf.xfx_q2(id, x, q2);
+    let checks = fk_table.check_assumptions(assumptions, &mut xfx);
+
+    let mut table = helpers::create_table();
+    table.set_titles(row![c => "condition", "x", "violation"]);
+
+    for check in &checks {
+        table.add_row(row![
+            check.condition.to_string(),
+            r->format!("{:.7e}", check.x),
+            r->format!("{:.7e}", check.violation)
+        ]);
+    }
+
+    table.printstd();
+
+    // the predictions of an optimized FK table without the assumptions are unknown
+    if fk_table.assumptions().is_none() {
+        // FK tables don't depend on the strong coupling
+        let mut alphas = |_| 0.0;
+        let results = fk_table.convolute(
+            &mut LumiCache::with_one(pdf_pdg_id, &mut xfx, &mut alphas),
+            &[],
+            &[],
+        )?;
+        let mut xfx_assumed = |id, x, q2| assumptions.apply(&mut xfx, id, x, q2);
+        let assumed_results = fk_table.convolute(
+            &mut LumiCache::with_one(pdf_pdg_id, &mut xfx_assumed, &mut alphas),
+            &[],
+            &[],
+        )?;
+
+        let mut table = helpers::create_table();
+        table.set_titles(row![c => "b", "PDF", "assumptions", "rel. diff"]);
+
+        for (bin, (result, assumed)) in results.iter().zip(&assumed_results).enumerate() {
+            // catches the case where both results are zero
+            let rel_diff = if approx_eq!(f64, *result, *assumed, ulps = 4) {
+                0.0
+            } else {
+                assumed / result - 1.0
+            };
+
+            table.add_row(row![
+                bin.to_string(),
+                r->format!("{result:.7e}"),
+                r->format!("{assumed:.7e}"),
+                r->format!("{rel_diff:.7e}")
+            ]);
+        }
+
+        println!();
+        table.printstd();
+    }
+
+    if checks.iter().all(FkConditionCheck::is_satisfied) {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
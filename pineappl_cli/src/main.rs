@@ -1,13 +1,17 @@
 #![warn(clippy::all, clippy::cargo, clippy::nursery, clippy::pedantic)]
 
 mod analyze;
+mod asunc;
 mod channels;
+mod chi2;
 mod convolute;
 mod delete;
 mod diff;
 mod evolve;
+mod export;
 mod help;
 mod helpers;
+mod hepdata;
 mod import;
 mod info;
 mod merge;
@@ -18,6 +22,7 @@ mod orders;
 mod pdfunc;
 mod plot;
 mod pull;
+mod rebin;
 mod remap;
 mod set;
 mod subgrids;
@@ -55,11 +60,14 @@ struct Opts {
 #[derive(Parser)]
 enum SubcommandEnum {
     Analyze(analyze::Opts),
+    Asunc(asunc::Opts),
     Channels(channels::Opts),
+    Chi2(chi2::Opts),
     Convolute(convolute::Opts),
     Delete(delete::Opts),
     Diff(diff::Opts),
     Evolve(evolve::Opts),
+    Export(export::Opts),
     Help(help::Opts),
     Import(import::Opts),
     Info(info::Opts),
@@ -71,6 +79,7 @@ enum SubcommandEnum {
     Pdfunc(pdfunc::Opts),
     Plot(plot::Opts),
     Pull(pull::Opts),
+    Rebin(rebin::Opts),
     Remap(remap::Opts),
     Set(set::Opts),
     Subgrids(subgrids::Opts),
@@ -1,7 +1,8 @@
 use super::helpers::{self, GlobalConfiguration, Subcommand};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, ValueHint};
-use std::path::PathBuf;
+use pineappl::fk_table::FkTable;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
 /// Merges one or more PineAPPL grids together.
@@ -25,11 +26,34 @@ pub struct Opts {
         value_name = "ALPHAS,ALPHA,LOGXIR,LOGXIF,GLOBAL"
     )]
     scale_by_order: Vec<f64>,
+    /// Merge FK tables by appending their bins, checking that they are compatible.
+    #[arg(conflicts_with_all = ["scale", "scale_by_order"], long)]
+    fktable: bool,
+    /// Interpolate FK tables with different x grids onto the x grid of the first one.
+    #[arg(long, requires = "fktable")]
+    interpolate: bool,
 }
 
 impl Subcommand for Opts {
     fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
         let (input0, input_rest) = self.input.split_first().unwrap();
+
+        if self.fktable {
+            let read_fk_table = |input: &Path| {
+                FkTable::try_from(helpers::read_grid(input)?)
+                    .context(format!("'{}' is not an FK table", input.display()))
+            };
+            let mut fk_table0 = read_fk_table(input0)?;
+
+            for i in input_rest {
+                fk_table0
+                    .merge(read_fk_table(i)?, self.interpolate)
+                    .context(format!("unable to merge '{}'", i.display()))?;
+            }
+
+            return helpers::write_grid(&self.output, fk_table0.grid());
+        }
+
         let mut grid0 = helpers::read_grid(input0)?;
 
         for i in input_rest {
@@ -1,7 +1,8 @@
 use super::helpers::{self, GlobalConfiguration, Subcommand};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{ArgGroup, Parser, ValueHint};
 use itertools::Itertools;
+use pineappl::bin::BinRemapper;
 use pineappl::fk_table::FkTable;
 use pineappl::grid::Order;
 use prettytable::{cell, row, Row};
@@ -27,6 +28,9 @@ pub struct Opts {
     /// Show the bins of a grid.
     #[arg(group = "mode", long, short)]
     bins: bool,
+    /// Show the bins as a remapping string, which can be used with `pineappl remap`.
+    #[arg(long, requires = "bins")]
+    remapping: bool,
     /// Show the luminsities a grid.
     #[arg(group = "mode", long, short)]
     lumis: bool,
@@ -41,7 +45,23 @@ impl Subcommand for Opts {
 
         let mut table = helpers::create_table();
 
-        if self.bins {
+        if self.bins && self.remapping {
+            let bin_info = grid.bin_info();
+            let remapper = BinRemapper::new(
+                bin_info.normalizations(),
+                bin_info.limits().into_iter().flatten().collect(),
+            )
+            .unwrap();
+
+            println!(
+                "{}",
+                remapper
+                    .to_remapping_string()
+                    .context("the bins can not be represented as a remapping string")?
+            );
+
+            return Ok(ExitCode::SUCCESS);
+        } else if self.bins {
             let mut titles = Row::empty();
             titles.add_cell(cell!(c->"b"));
 
@@ -106,6 +126,10 @@ impl Subcommand for Opts {
         } else {
             table.set_titles(row![c => "o", "order"]);
 
+            // only show the powers of the additional logarithm if there are any or if requested
+            let show_logxia =
+                self.orders_long || grid.orders().iter().any(|order| order.logxia > 0);
+
             for (index, order) in grid.orders().iter().enumerate() {
                 let row = table.add_empty_row();
 
@@ -114,11 +138,13 @@ impl Subcommand for Opts {
                     alpha,
                     logxir,
                     logxif,
+                    logxia,
                 } = order;
 
-                let order_string = [alphas, alpha, logxir, logxif]
+                let order_string = [alphas, alpha, logxir, logxif, logxia]
                     .iter()
-                    .zip(["as^", "a^", "lr^", "lf^"].iter())
+                    .zip(["as^", "a^", "lr^", "lf^", "la^"].iter())
+                    .take(if show_logxia { 5 } else { 4 })
                     .filter_map(|(num, string)| {
                         if **num == 0 && self.orders {
                             None
@@ -1,9 +1,10 @@
 use super::helpers::{self, GlobalConfiguration, Subcommand};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::{Parser, ValueHint};
+use pineappl::grid::Grid;
 use pineappl::lumi::LumiEntry;
 use pineappl::pids;
-use std::ops::Deref;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
@@ -22,6 +23,28 @@ pub struct Opts {
     /// Charge conjugate the second initial state.
     #[arg(long)]
     cc2: bool,
+    /// Rotate the luminosity function into the particle basis `pdg_mc_ids` or `evol`, or into the
+    /// custom basis given by a YAML file.
+    #[arg(long, value_name = "BASIS")]
+    rotate_basis: Option<String>,
+    /// Reorder the bins such that the i-th bin is the original bin with the i-th given index.
+    #[arg(
+        long,
+        num_args = 1,
+        value_delimiter = ',',
+        value_name = "BIN1,BIN2,...",
+        value_parser = helpers::parse_integer_range
+    )]
+    reorder_bins: Vec<RangeInclusive<usize>>,
+    /// Keep only the given bins, in the given order, allowing repetitions.
+    #[arg(
+        long,
+        num_args = 1,
+        value_delimiter = ',',
+        value_name = "BIN1,BIN2,...",
+        value_parser = helpers::parse_integer_range
+    )]
+    select_bins: Vec<RangeInclusive<usize>>,
     /// Scale each bin with a different factor.
     #[arg(
         long,
@@ -30,6 +53,10 @@ pub struct Opts {
         value_name = "BIN1,BIN2,..."
     )]
     scale_by_bin: Vec<f64>,
+    /// Add the renormalization and factorization scale logarithms, using the beta and splitting
+    /// functions with NF active flavours.
+    #[arg(long, value_name = "NF")]
+    add_scale_logs: Option<u32>,
 }
 
 impl Subcommand for Opts {
@@ -37,34 +64,33 @@ impl Subcommand for Opts {
         let mut grid = helpers::read_grid(&self.input)?;
 
         if self.cc1 || self.cc2 {
-            let lumi_id_types = grid.key_values().map_or("pdg_mc_ids", |kv| {
-                kv.get("lumi_id_types").map_or("pdg_mc_ids", Deref::deref)
-            });
+            let lumi_id_types = grid.lumi_id_types();
+            let charge_conjugate = |pid, cc| {
+                if cc {
+                    pids::charge_conjugate(lumi_id_types, pid).ok_or_else(|| {
+                        anyhow!("can not charge conjugate particles of the basis `{lumi_id_types}`")
+                    })
+                } else {
+                    Ok((pid, 1.0))
+                }
+            };
             let lumis = grid
                 .lumi()
                 .iter()
                 .map(|entry| {
-                    LumiEntry::new(
+                    Ok(LumiEntry::new(
                         entry
                             .entry()
                             .iter()
                             .map(|&(a, b, f)| {
-                                let (ap, f1) = if self.cc1 {
-                                    pids::charge_conjugate(lumi_id_types, a)
-                                } else {
-                                    (a, 1.0)
-                                };
-                                let (bp, f2) = if self.cc2 {
-                                    pids::charge_conjugate(lumi_id_types, b)
-                                } else {
-                                    (b, 1.0)
-                                };
-                                (ap, bp, f * f1 * f2)
+                                let (ap, f1) = charge_conjugate(a, self.cc1)?;
+                                let (bp, f2) = charge_conjugate(b, self.cc2)?;
+                                Ok((ap, bp, f * f1 * f2))
                             })
-                            .collect(),
-                    )
+                            .collect::<Result<_>>()?,
+                    ))
                 })
-                .collect();
+                .collect::<Result<_>>()?;
 
             let mut initial_state_1: i32 = grid
                 .key_values()
@@ -87,10 +113,114 @@ impl Subcommand for Opts {
             grid.set_lumis(lumis);
         }
 
+        if let Some(basis) = &self.rotate_basis {
+            grid.rotate_basis(&helpers::basis_rotation(basis)?)?;
+        }
+
+        if !self.reorder_bins.is_empty() {
+            let bins: Vec<usize> = self
+                .reorder_bins
+                .iter()
+                .flat_map(|range| range.clone().collect::<Vec<_>>())
+                .collect();
+
+            grid.reorder_bins(&bins)?;
+        }
+
+        if !self.select_bins.is_empty() {
+            let bins: Vec<usize> = self
+                .select_bins
+                .iter()
+                .flat_map(|range| range.clone().collect::<Vec<_>>())
+                .collect();
+
+            grid.select_bins(&bins)?;
+        }
+
         if !self.scale_by_bin.is_empty() {
             grid.scale_by_bin(&self.scale_by_bin);
         }
 
+        if let Some(nf) = self.add_scale_logs {
+            add_scale_logs(&mut grid, nf)?;
+        }
+
         helpers::write_grid(&self.output, &grid)
     }
 }
+
+/// Adds the scale logarithms to `grid` using the built-in beta and splitting functions with `nf`
+/// active flavours.
+#[cfg(feature = "dglap")]
+fn add_scale_logs(grid: &mut Grid, nf: u32) -> Result<()> {
+    use anyhow::bail;
+    use pineappl::dglap::{self, PerturbativeOrder};
+    use pineappl::grid::SplittingKernel;
+    use std::collections::HashMap;
+    use std::f64::consts::PI;
+
+    if grid.lumi_id_types() != "pdg_mc_ids" {
+        bail!("adding scale logarithms requires a luminosity function with PDG MC ids");
+    }
+
+    // the lowest and highest power of the strong coupling for each power of `alpha`
+    let mut alphas_range: HashMap<u32, (u32, u32)> = HashMap::new();
+
+    for order in grid.orders() {
+        let (min, max) = alphas_range
+            .entry(order.alpha)
+            .or_insert((order.alphas, order.alphas));
+        *min = (*min).min(order.alphas);
+        *max = (*max).max(order.alphas);
+    }
+
+    if alphas_range.values().any(|&(min, max)| max - min > 2) {
+        bail!("adding scale logarithms is only supported up to two orders beyond the lowest");
+    }
+
+    // some grids use `0` instead of `21` for the gluon, keep the convention of the grid
+    let gluon = if grid
+        .lumi()
+        .iter()
+        .flat_map(LumiEntry::entry)
+        .any(|&(a, b, _)| a == 0 || b == 0)
+    {
+        0
+    } else {
+        21
+    };
+    let kernels: Vec<_> = [PerturbativeOrder::Lo, PerturbativeOrder::Nlo]
+        .into_iter()
+        .map(|order| {
+            let kernel = dglap::splitting_kernel(order, nf.try_into().unwrap());
+
+            move |pid, x: &[f64]| {
+                kernel(pid, x)
+                    .into_iter()
+                    .map(|(pid, matrix)| (if pid == 21 { gluon } else { pid }, matrix))
+                    .collect::<Vec<_>>()
+            }
+        })
+        .collect();
+    let kernels: Vec<&SplittingKernel> = kernels
+        .iter()
+        .map(|kernel| kernel as &SplittingKernel)
+        .collect();
+
+    let nf = f64::from(nf);
+    let beta = [
+        2.0_f64.mul_add(-nf, 33.0) / (12.0 * PI),
+        19.0_f64.mul_add(-nf, 153.0) / (24.0 * PI.powi(2)),
+        (325.0 / 27.0_f64).mul_add(nf.powi(2), (5033.0 / 9.0_f64).mul_add(-nf, 2857.0))
+            / (128.0 * PI.powi(3)),
+    ];
+
+    Ok(grid.add_scale_logs(&beta, &kernels)?)
+}
+
+#[cfg(not(feature = "dglap"))]
+fn add_scale_logs(_: &mut Grid, _: u32) -> Result<()> {
+    Err(anyhow!(
+        "you need to install `pineappl` with feature `dglap`"
+    ))
+}
@@ -1,8 +1,9 @@
 use super::helpers::{self, GlobalConfiguration, Subcommand};
-use anyhow::Result;
-use clap::builder::{PossibleValuesParser, TypedValueParser};
+use anyhow::{bail, Result};
+use clap::builder::PossibleValuesParser;
 use clap::{Parser, ValueHint};
 use pineappl::fk_table::{FkAssumptions, FkTable};
+use std::fs;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
@@ -15,22 +16,67 @@ pub struct Opts {
     /// Path to the optimized PineAPPL file.
     #[arg(value_hint = ValueHint::FilePath)]
     output: PathBuf,
+    /// Optimize an FK table with the given assumptions, or with the most restrictive assumptions
+    /// satisfied by the PDF sets given with `--pdfsets` if the value is `auto`.
     #[arg(
         long,
         value_name = "ASSUMPTIONS",
-        value_parser = PossibleValuesParser::new(["Nf6Ind", "Nf6Sym", "Nf5Ind", "Nf5Sym", "Nf4Ind", "Nf4Sym", "Nf3Ind", "Nf3Sym"]).try_map(|s| s.parse::<FkAssumptions>())
+        value_parser = PossibleValuesParser::new(["auto", "Nf6Ind", "Nf6Sym", "Nf5Ind", "Nf5Sym", "Nf4Ind", "Nf4Sym", "Nf3Ind", "Nf3Sym"])
     )]
-    fk_table: Option<FkAssumptions>,
+    fk_table: Option<String>,
+    /// LHAPDF ids or names of the PDF sets used to detect the assumptions of `--fk-table auto`.
+    #[arg(
+        long,
+        required_if_eq("fk_table", "auto"),
+        requires = "fk_table",
+        value_delimiter = ',',
+        value_name = "PDFSETS",
+        value_parser = helpers::parse_pdfset
+    )]
+    pdfsets: Vec<String>,
 }
 
 impl Subcommand for Opts {
     fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
         let mut grid = helpers::read_grid(&self.input)?;
 
-        if let Some(assumptions) = self.fk_table {
+        if let Some(assumptions) = &self.fk_table {
+            if (assumptions != "auto") && !self.pdfsets.is_empty() {
+                bail!("`--pdfsets` can only be used together with `--fk-table auto`");
+            }
+
             let mut fk_table = FkTable::try_from(grid)?;
-            fk_table.optimize(assumptions);
-            helpers::write_grid(&self.output, fk_table.grid())
+
+            if assumptions == "auto" {
+                let pdfs = self
+                    .pdfsets
+                    .iter()
+                    .map(|pdfset| helpers::create_pdf(pdfset))
+                    .collect::<Result<Vec<_>>>()?;
+                let mut xfxs: Vec<_> = pdfs
+                    .iter()
+                    .map(|pdf| move |id, x, q2| pdf.xfx_q2(id, x, q2))
+                    .collect();
+                let mut xfxs: Vec<&mut dyn FnMut(i32, f64, f64) -> f64> =
+                    xfxs.iter_mut().map(|xfx| xfx as _).collect();
+                let assumptions = fk_table.detect_assumptions(&mut xfxs);
+                let channels = fk_table.grid().lumi().len();
+
+                fk_table.optimize(assumptions);
+                let exit_code = helpers::write_grid(&self.output, fk_table.grid())?;
+
+                println!(
+                    "optimized with {assumptions}: {channels} -> {} channels, {} -> {} bytes",
+                    fk_table.grid().lumi().len(),
+                    fs::metadata(&self.input)?.len(),
+                    fs::metadata(&self.output)?.len()
+                );
+
+                Ok(exit_code)
+            } else {
+                fk_table.optimize(assumptions.parse::<FkAssumptions>()?);
+                helpers::write_grid(&self.output, fk_table.grid())
+            }
         } else {
             grid.optimize();
             helpers::write_grid(&self.output, &grid)
@@ -46,7 +46,7 @@ impl Subcommand for Opts {
         let mut orders: Vec<_> = grid
             .orders()
             .iter()
-            .filter(|order| (order.logxir == 0) && (order.logxif == 0))
+            .filter(|order| (order.logxir == 0) && (order.logxif == 0) && (order.logxia == 0))
             .collect();
         orders.sort();
         let orders = orders;
@@ -59,7 +59,7 @@ impl Subcommand for Opts {
             } else {
                 ConvoluteMode::Normal
             },
-        );
+        )?;
         let results: Vec<Vec<f64>> = orders
             .iter()
             .map(|order| {
@@ -78,7 +78,7 @@ impl Subcommand for Opts {
                     cfg.force_positive,
                 )
             })
-            .collect();
+            .collect::<Result<_>>()?;
 
         let lo_power = {
             let order = orders.first().unwrap();
@@ -1,6 +1,8 @@
-use super::helpers::{self, ConvoluteMode, GlobalConfiguration, Subcommand};
-use anyhow::Result;
+use super::helpers::{self, ConvoluteMode, GlobalConfiguration, ScaleVariations, Subcommand};
+use anyhow::{bail, ensure, Result};
 use clap::{Parser, ValueHint};
+use lhapdf::PdfSet;
+use ndarray::Array2;
 use prettytable::{cell, Row};
 use rayon::{prelude::*, ThreadPoolBuilder};
 use std::num::NonZeroUsize;
@@ -23,6 +25,9 @@ pub struct Opts {
     /// Show integrated numbers (without bin widths) instead of differential ones.
     #[arg(long, short)]
     integrated: bool,
+    /// Show the observables defined in the grid instead of its bins.
+    #[arg(conflicts_with = "integrated", long)]
+    observables: bool,
     /// Select orders manually.
     #[arg(
         long,
@@ -32,6 +37,22 @@ pub struct Opts {
         value_parser = helpers::parse_order
     )]
     orders: Vec<(u32, u32)>,
+    /// Additionally show the scale uncertainty using the given prescription: 1, 3, 7, 9, 3-ren,
+    /// 3-fac or 31-corr, optionally followed by @FACTOR, or a list of xir:xif pairs.
+    #[arg(default_value = "1", long, short, value_parser = helpers::parse_scales)]
+    scales: ScaleVariations,
+    /// Print the bin-by-bin covariance matrix of the PDF uncertainty instead of the table, readable
+    /// with `numpy.loadtxt`.
+    #[arg(long)]
+    covariance: bool,
+    /// Print the bin-by-bin covariance matrix of the scale uncertainty instead of the table. If
+    /// given together with `--covariance`, both matrices are added.
+    #[arg(long)]
+    scale_covariance: bool,
+    /// Print the correlation matrix instead of the covariance matrix, by default for the PDF
+    /// uncertainty. Bins without uncertainty are uncorrelated with all other bins.
+    #[arg(long)]
+    correlation: bool,
     /// Number of threads to utilize.
     #[arg(default_value_t = thread::available_parallelism().map_or(1, NonZeroUsize::get), long)]
     threads: usize,
@@ -45,7 +66,20 @@ pub struct Opts {
 
 impl Subcommand for Opts {
     fn run(&self, cfg: &GlobalConfiguration) -> Result<ExitCode> {
+        ensure!(
+            !self.scale_covariance || (self.scales.len() > 1),
+            "the scale covariance needs more than one scale variation, use `--scales`"
+        );
+
         let grid = helpers::read_grid(&self.input)?;
+        let mode = if self.observables {
+            helpers::check_observables(&grid)?;
+            ConvoluteMode::Observables
+        } else if self.integrated {
+            ConvoluteMode::Integrated
+        } else {
+            ConvoluteMode::Normal
+        };
         let (set, member) = helpers::create_pdfset(&self.pdfset)?;
         let pdfs = set.mk_pdfs();
 
@@ -54,18 +88,10 @@ impl Subcommand for Opts {
             .build_global()
             .unwrap();
 
-        let limits = helpers::convolute_limits(
-            &grid,
-            &[],
-            if self.integrated {
-                ConvoluteMode::Integrated
-            } else {
-                ConvoluteMode::Normal
-            },
-        );
+        let limits = helpers::convolute_limits(&grid, &[], mode)?;
         let results: Vec<_> = pdfs
             .into_par_iter()
-            .flat_map(|mut pdf| {
+            .map(|mut pdf| {
                 helpers::convolute(
                     &grid,
                     &mut pdf,
@@ -73,15 +99,69 @@ impl Subcommand for Opts {
                     &[],
                     &[],
                     1,
-                    if self.integrated {
-                        ConvoluteMode::Integrated
-                    } else {
-                        ConvoluteMode::Normal
-                    },
+                    mode,
                     cfg.force_positive,
                 )
             })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
             .collect();
+        let scale_results = if self.scales.len() > 1 {
+            helpers::convolute_scales(
+                &grid,
+                &mut helpers::create_pdf(&self.pdfset)?,
+                &self.orders,
+                &[],
+                &[],
+                &self.scales,
+                mode,
+                cfg.force_positive,
+            )?
+        } else {
+            vec![]
+        };
+
+        if self.covariance || self.scale_covariance || self.correlation {
+            let mut matrix = Array2::zeros((limits.len(), limits.len()));
+
+            if self.covariance || !self.scale_covariance {
+                matrix += &pdf_covariance(&set, &results, limits.len(), self.cl)?;
+            }
+
+            if self.scale_covariance {
+                matrix += &scale_covariance(&self.scales, &scale_results);
+            }
+
+            if self.correlation {
+                let sigmas: Vec<_> = matrix.diag().mapv(f64::sqrt).to_vec();
+
+                for ((i, j), value) in matrix.indexed_iter_mut() {
+                    let norm = sigmas[i] * sigmas[j];
+
+                    // bins without uncertainty are only correlated with themselves
+                    *value = if norm > 0.0 {
+                        *value / norm
+                    } else if i == j {
+                        1.0
+                    } else {
+                        0.0
+                    };
+                }
+            }
+
+            for row in matrix.rows() {
+                println!(
+                    "{}",
+                    row.iter()
+                        .map(|value| format!("{:.*e}", self.digits_abs, value))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                );
+            }
+
+            return Ok(ExitCode::SUCCESS);
+        }
 
         let (x, y_label, y_unit) = helpers::labels_and_units(&grid, self.integrated);
         let mut title = Row::empty();
@@ -94,6 +174,10 @@ impl Subcommand for Opts {
         title.add_cell(cell!(c->format!("{y_label}\n[{y_unit}]")));
         title.add_cell(cell!(c->"PDF uncertainty\n[%]").with_hspan(2));
 
+        if self.scales.len() > 1 {
+            title.add_cell(cell!(c->"scale uncertainty\n[%]").with_hspan(2));
+        }
+
         let mut table = helpers::create_table();
         table.set_titles(title);
 
@@ -120,6 +204,20 @@ impl Subcommand for Opts {
             row.add_cell(
                 cell!(r->format!("{:.*}", self.digits_rel, (uncertainty.errplus / uncertainty.central) * 100.0)),
             );
+
+            if let Some(values) = scale_results.chunks_exact(self.scales.len()).nth(bin) {
+                let min_value = values
+                    .iter()
+                    .min_by(|left, right| left.partial_cmp(right).unwrap())
+                    .unwrap();
+                let max_value = values
+                    .iter()
+                    .max_by(|left, right| left.partial_cmp(right).unwrap())
+                    .unwrap();
+
+                row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, (min_value / values[0] - 1.0) * 100.0)));
+                row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, (max_value / values[0] - 1.0) * 100.0)));
+            }
         }
 
         table.printstd();
@@ -127,3 +225,105 @@ impl Subcommand for Opts {
         Ok(ExitCode::SUCCESS)
     }
 }
+
+/// Computes the covariance matrix of the PDF uncertainty from the `results` of all members, which
+/// are ordered by member first and by bin second.
+fn pdf_covariance(set: &PdfSet, results: &[f64], bins: usize, cl: f64) -> Result<Array2<f64>> {
+    let error_type = set.error_type();
+    // each additional parameter, for instance `+as`, is varied using the last two members
+    let (pdf_error_type, parameters) = error_type
+        .split_once('+')
+        .map_or((error_type.as_str(), 0), |(pdf_error_type, parameters)| {
+            (pdf_error_type, parameters.split('+').count())
+        });
+    let members = results.len() / bins - 2 * parameters;
+    let values = |member: usize| &results[member * bins..(member + 1) * bins];
+    let central = values(0);
+
+    // the differences to the central value whose outer products sum up to the covariance
+    let deltas: Vec<Vec<f64>> = match pdf_error_type {
+        "replicas" => {
+            let replicas = f64::from(u32::try_from(members - 1)?);
+            let mean: Vec<_> = (0..bins)
+                .map(|bin| (1..members).map(|member| values(member)[bin]).sum::<f64>() / replicas)
+                .collect();
+            let norm = (replicas - 1.0).sqrt();
+
+            (1..members)
+                .map(|member| {
+                    values(member)
+                        .iter()
+                        .zip(&mean)
+                        .map(|(value, mean)| (value - mean) / norm)
+                        .collect()
+                })
+                .collect()
+        }
+        "symmhessian" => (1..members)
+            .map(|member| {
+                values(member)
+                    .iter()
+                    .zip(central)
+                    .map(|(value, central)| value - central)
+                    .collect()
+            })
+            .collect(),
+        "hessian" => (1..members)
+            .step_by(2)
+            .map(|member| {
+                values(member)
+                    .iter()
+                    .zip(values(member + 1))
+                    .map(|(plus, minus)| 0.5 * (plus - minus))
+                    .collect()
+            })
+            .collect(),
+        _ => bail!("unsupported PDF error type '{}'", error_type),
+    };
+
+    // rescale to the requested confidence level in the same way as LHAPDF does
+    let scale = set
+        .uncertainty(
+            &(0..results.len() / bins)
+                .map(|member| values(member)[0])
+                .collect::<Vec<_>>(),
+            cl,
+            false,
+        )?
+        .scale;
+
+    Ok(outer_product_sum(&deltas, bins) * scale.powi(2))
+}
+
+/// Computes the covariance matrix of the scale uncertainty from the `results` of all scale
+/// variations, which are ordered by bin first and by scale variation second. The normalization
+/// is the one of the single-process prescriptions of arXiv:1906.10698, i.e. the number of
+/// independently varied scales divided by the number of variations.
+fn scale_covariance(scales: &[(f64, f64)], results: &[f64]) -> Array2<f64> {
+    let bins = results.len() / scales.len();
+    let varied = |scale: f64| (scale - 1.0).abs() > f64::EPSILON;
+    let ren = scales.iter().any(|&(xir, _)| varied(xir));
+    let fac = scales.iter().any(|&(_, xif)| varied(xif));
+    let correlated = scales
+        .iter()
+        .all(|&(xir, xif)| (xir - xif).abs() <= f64::EPSILON);
+    let independent_scales = if ren && fac && !correlated { 2.0 } else { 1.0 };
+    let norm = (independent_scales / f64::from(u32::try_from(scales.len() - 1).unwrap())).sqrt();
+
+    let deltas: Vec<Vec<f64>> = (1..scales.len())
+        .map(|scale| {
+            results
+                .chunks_exact(scales.len())
+                .map(|values| norm * (values[scale] - values[0]))
+                .collect()
+        })
+        .collect();
+
+    outer_product_sum(&deltas, bins)
+}
+
+fn outer_product_sum(deltas: &[Vec<f64>], bins: usize) -> Array2<f64> {
+    Array2::from_shape_fn((bins, bins), |(i, j)| {
+        deltas.iter().map(|delta| delta[i] * delta[j]).sum()
+    })
+}
@@ -133,9 +133,9 @@ def plot_rel_ewonoff(axis, **kwargs):
     pdf_max = abs(percent_diff(kwargs['pdf_results'][0][3], kwargs['pdf_results'][0][1]))[:-1]
 
     axis.step(x, qcd_y, 'red', label='NLO QCD', linewidth=1.0, where='post')
-    #axis.fill_between(x, qcd_ymin, qcd_ymax, alpha=0.4, color='red', label='7-p.\ scale var.', linewidth=0.5, step='post')
+    #axis.fill_between(x, qcd_ymin, qcd_ymax, alpha=0.4, color='red', label='{scales}-p.\ scale var.', linewidth=0.5, step='post')
     axis.step(x, y, 'royalblue', label='NLO QCD+EW', linewidth=1.0, where='post')
-    axis.fill_between(x, ymin, ymax, alpha=0.4, color='royalblue', label='7-p.\ scale var.', linewidth=0.5, step='post')
+    axis.fill_between(x, ymin, ymax, alpha=0.4, color='royalblue', label='{scales}-p.\ scale var.', linewidth=0.5, step='post')
     axis.errorbar(kwargs['mid'], y[:-1], yerr=(pdf_min, pdf_max), color='royalblue', label='PDF uncertainty', fmt='.', capsize=1, markersize=0, linewidth=1)
     axis.set_ylabel('NLO EW on/off [\si{{\percent}}]')
     axis.legend(bbox_to_anchor=(0,1.03,1,0.2), loc='lower left', mode='expand', borderaxespad=0, ncol=4)
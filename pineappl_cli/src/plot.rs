@@ -1,6 +1,5 @@
-use super::helpers::{self, ConvoluteMode, GlobalConfiguration, Subcommand};
+use super::helpers::{self, ConvoluteMode, GlobalConfiguration, ScaleVariations, Subcommand};
 use anyhow::Result;
-use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::{Parser, ValueHint};
 use itertools::Itertools;
 use ndarray::Axis;
@@ -22,14 +21,10 @@ pub struct Opts {
     /// LHAPDF id(s) or name of the PDF set(s).
     #[arg(required = true, value_parser = helpers::parse_pdfset)]
     pdfsets: Vec<String>,
-    /// Set the number of scale variations.
-    #[arg(
-        default_value_t = 7,
-        long,
-        short,
-        value_parser = PossibleValuesParser::new(["1", "3", "7", "9"]).try_map(|s| s.parse::<usize>())
-    )]
-    scales: usize,
+    /// Set the scale-variation prescription: 1, 3, 7, 9, 3-ren, 3-fac or 31-corr, optionally
+    /// followed by @FACTOR, or a list of xir:xif pairs.
+    #[arg(default_value = "7", long, short, value_parser = helpers::parse_scales)]
+    scales: ScaleVariations,
     /// Show the pull for a specific grid three-dimensionally.
     #[arg(
         conflicts_with = "scales",
@@ -42,6 +37,9 @@ pub struct Opts {
     /// Plot the asymmetry.
     #[arg(conflicts_with = "subgrid_pull", long)]
     asymmetry: bool,
+    /// Plot the observables defined in the grid instead of its bins.
+    #[arg(conflicts_with_all = ["asymmetry", "subgrid_pull"], long)]
+    observables: bool,
     /// Number of threads to utilize.
     #[arg(default_value_t = thread::available_parallelism().map_or(1, NonZeroUsize::get), long)]
     threads: usize,
@@ -160,15 +158,23 @@ impl Subcommand for Opts {
             .unwrap();
 
         if self.subgrid_pull.is_empty() {
+            let grid = helpers::read_grid(&self.input)?;
             let mode = if self.asymmetry {
                 ConvoluteMode::Asymmetry
+            } else if self.observables {
+                helpers::check_observables(&grid)?;
+                ConvoluteMode::Observables
             } else {
                 ConvoluteMode::Normal
             };
 
-            let grid = helpers::read_grid(&self.input)?;
             let mut pdf = helpers::create_pdf(&self.pdfsets[0])?;
-            let slices = grid.bin_info().slices();
+            // observables may combine bins of different slices, so they are shown in a single one
+            let slices = if self.observables {
+                vec![(0, grid.bin_info().bins())]
+            } else {
+                grid.bin_info().slices()
+            };
             let mut data_string = String::new();
 
             data_string.push_str("[\n");
@@ -195,16 +201,16 @@ impl Subcommand for Opts {
             })) {
                 let bins: Vec<_> = (slice.0..slice.1).collect();
 
-                let results = helpers::convolute(
+                let results = helpers::convolute_scales(
                     &grid,
                     &mut pdf,
                     &[],
                     &bins,
                     &[],
-                    self.scales,
+                    &self.scales,
                     mode,
                     cfg.force_positive,
-                );
+                )?;
 
                 let qcd_results = {
                     let mut orders = grid.orders().to_vec();
@@ -221,19 +227,19 @@ impl Subcommand for Opts {
                         })
                         .collect();
 
-                    helpers::convolute(
+                    helpers::convolute_scales(
                         &grid,
                         &mut pdf,
                         &qcd_orders,
                         &bins,
                         &[],
-                        self.scales,
+                        &self.scales,
                         mode,
                         cfg.force_positive,
-                    )
+                    )?
                 };
 
-                let bin_limits: Vec<_> = helpers::convolute_limits(&grid, &bins, mode)
+                let bin_limits: Vec<_> = helpers::convolute_limits(&grid, &bins, mode)?
                     .into_iter()
                     .map(|limits| limits.last().copied().unwrap())
                     .collect();
@@ -257,7 +263,7 @@ impl Subcommand for Opts {
                         let pdf_results: Vec<_> = set
                             .mk_pdfs()
                             .into_par_iter()
-                            .flat_map(|mut pdf| {
+                            .map(|mut pdf| {
                                 helpers::convolute(
                                     &grid,
                                     &mut pdf,
@@ -269,6 +275,9 @@ impl Subcommand for Opts {
                                     cfg.force_positive,
                                 )
                             })
+                            .collect::<Result<Vec<_>>>()?
+                            .into_iter()
+                            .flatten()
                             .collect();
 
                         let bins = mid.len();
@@ -293,13 +302,13 @@ impl Subcommand for Opts {
                             max.push(uncertainty.central + uncertainty.errplus);
                         }
 
-                        vec![central, min, max]
+                        Ok(vec![central, min, max])
                     })
-                    .collect();
+                    .collect::<Result<_>>()?;
 
-                let central: Vec<_> = results.iter().step_by(self.scales).copied().collect();
+                let central: Vec<_> = results.iter().step_by(self.scales.len()).copied().collect();
                 let min: Vec<_> = results
-                    .chunks_exact(self.scales)
+                    .chunks_exact(self.scales.len())
                     .map(|variations| {
                         variations
                             .iter()
@@ -309,7 +318,7 @@ impl Subcommand for Opts {
                     })
                     .collect();
                 let max: Vec<_> = results
-                    .chunks_exact(self.scales)
+                    .chunks_exact(self.scales.len())
                     .map(|variations| {
                         variations
                             .iter()
@@ -319,10 +328,13 @@ impl Subcommand for Opts {
                     })
                     .collect();
 
-                let qcd_central: Vec<_> =
-                    qcd_results.iter().step_by(self.scales).copied().collect();
+                let qcd_central: Vec<_> = qcd_results
+                    .iter()
+                    .step_by(self.scales.len())
+                    .copied()
+                    .collect();
                 let qcd_min: Vec<_> = qcd_results
-                    .chunks_exact(self.scales)
+                    .chunks_exact(self.scales.len())
                     .map(|variations| {
                         variations
                             .iter()
@@ -332,7 +344,7 @@ impl Subcommand for Opts {
                     })
                     .collect();
                 let qcd_max: Vec<_> = qcd_results
-                    .chunks_exact(self.scales)
+                    .chunks_exact(self.scales.len())
                     .map(|variations| {
                         variations
                             .iter()
@@ -342,14 +354,14 @@ impl Subcommand for Opts {
                     })
                     .collect();
 
-                let channels = if matches!(mode, ConvoluteMode::Asymmetry) {
+                let channels = if self.asymmetry || self.observables {
                     vec![]
                 } else {
                     let mut channels: Vec<_> = (0..grid.lumi().len())
                         .map(|lumi| {
                             let mut lumi_mask = vec![false; grid.lumi().len()];
                             lumi_mask[lumi] = true;
-                            (
+                            Ok((
                                 map_format_lumi(&grid.lumi()[lumi]),
                                 helpers::convolute(
                                     &grid,
@@ -360,10 +372,10 @@ impl Subcommand for Opts {
                                     1,
                                     mode,
                                     cfg.force_positive,
-                                ),
-                            )
+                                )?,
+                            ))
                         })
-                        .collect();
+                        .collect::<Result<_>>()?;
 
                     // sort channels by importance
                     channels.sort_by(|(_, lhs), (_, rhs)| {
@@ -485,6 +497,7 @@ impl Subcommand for Opts {
                 output = output.to_str().unwrap(),
                 data = data_string,
                 metadata = format_metadata(&vector),
+                scales = self.scales.len(),
             );
         } else {
             let (pdfset1, pdfset2) = self.pdfsets.iter().collect_tuple().unwrap();
@@ -515,14 +528,14 @@ impl Subcommand for Opts {
                         1,
                         ConvoluteMode::Normal,
                         cfg.force_positive,
-                    )
+                    )?
                     .as_slice()
                     {
-                        [value] => *value,
+                        [value] => Ok(*value),
                         _ => unreachable!(),
                     }
                 })
-                .collect();
+                .collect::<Result<_>>()?;
             let values2: Vec<_> = pdfset2
                 .par_iter_mut()
                 .map(|pdf| {
@@ -535,14 +548,14 @@ impl Subcommand for Opts {
                         1,
                         ConvoluteMode::Normal,
                         cfg.force_positive,
-                    )
+                    )?
                     .as_slice()
                     {
-                        [value] => *value,
+                        [value] => Ok(*value),
                         _ => unreachable!(),
                     }
                 })
-                .collect();
+                .collect::<Result<_>>()?;
 
             let uncertainty1 = set1.uncertainty(&values1, cl, false)?;
             let uncertainty2 = set2.uncertainty(&values2, cl, false)?;
@@ -565,9 +578,9 @@ impl Subcommand for Opts {
                 unc1.hypot(unc2)
             };
 
-            let res1 = helpers::convolute_subgrid(&grid, &mut pdfset1[0], order, bin, lumi)
+            let res1 = helpers::convolute_subgrid(&grid, &mut pdfset1[0], order, bin, lumi)?
                 .sum_axis(Axis(0));
-            let res2 = helpers::convolute_subgrid(&grid, &mut pdfset2[0], order, bin, lumi)
+            let res2 = helpers::convolute_subgrid(&grid, &mut pdfset2[0], order, bin, lumi)?
                 .sum_axis(Axis(0));
 
             let subgrid = grid.subgrid(order, bin, lumi);
@@ -61,10 +61,10 @@ impl Subcommand for Opts {
             .unwrap();
 
         let limit = grid.lumi().len().min(self.limit);
-        let bin_limits = helpers::convolute_limits(&grid, &[], ConvoluteMode::Normal);
+        let bin_limits = helpers::convolute_limits(&grid, &[], ConvoluteMode::Normal)?;
         let results1: Vec<_> = pdfset1
             .par_iter_mut()
-            .flat_map(|pdf| {
+            .map(|pdf| {
                 helpers::convolute(
                     &grid,
                     pdf,
@@ -76,10 +76,13 @@ impl Subcommand for Opts {
                     cfg.force_positive,
                 )
             })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
             .collect();
         let results2: Vec<_> = pdfset2
             .par_iter_mut()
-            .flat_map(|pdf| {
+            .map(|pdf| {
                 helpers::convolute(
                     &grid,
                     pdf,
@@ -91,6 +94,9 @@ impl Subcommand for Opts {
                     cfg.force_positive,
                 )
             })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
             .collect();
 
         let mut title = Row::empty();
@@ -140,7 +146,7 @@ impl Subcommand for Opts {
             };
 
             let lumi_results =
-                |member: Option<usize>, pdfset: &mut Vec<Pdf>, set: &PdfSet| -> Vec<f64> {
+                |member: Option<usize>, pdfset: &mut Vec<Pdf>, set: &PdfSet| -> Result<Vec<f64>> {
                     if let Some(member) = member {
                         (0..grid.lumi().len())
                             .map(|lumi| {
@@ -155,10 +161,10 @@ impl Subcommand for Opts {
                                     1,
                                     ConvoluteMode::Normal,
                                     cfg.force_positive,
-                                )
+                                )?
                                 .as_slice()
                                 {
-                                    [value] => *value,
+                                    [value] => Ok(*value),
                                     _ => unreachable!(),
                                 }
                             })
@@ -166,7 +172,7 @@ impl Subcommand for Opts {
                     } else {
                         let results: Vec<_> = pdfset
                             .iter_mut()
-                            .flat_map(|pdf| {
+                            .map(|pdf| {
                                 (0..grid.lumi().len())
                                     .map(|lumi| {
                                         let mut lumi_mask = vec![false; grid.lumi().len()];
@@ -180,18 +186,21 @@ impl Subcommand for Opts {
                                             1,
                                             ConvoluteMode::Normal,
                                             cfg.force_positive,
-                                        )
+                                        )?
                                         .as_slice()
                                         {
-                                            [value] => *value,
+                                            [value] => Ok(*value),
                                             _ => unreachable!(),
                                         }
                                     })
-                                    .collect::<Vec<_>>()
+                                    .collect::<Result<Vec<_>>>()
                             })
+                            .collect::<Result<Vec<_>>>()?
+                            .into_iter()
+                            .flatten()
                             .collect();
 
-                        (0..grid.lumi().len())
+                        Ok((0..grid.lumi().len())
                             .map(|lumi| {
                                 let central: Vec<_> = results
                                     .iter()
@@ -201,15 +210,15 @@ impl Subcommand for Opts {
                                     .collect();
                                 set.uncertainty(&central, self.cl, false).unwrap().central
                             })
-                            .collect()
+                            .collect())
                     }
                 };
 
             let mut pull_tuples = if self.limit == 0 {
                 vec![]
             } else {
-                let lumi_results1 = lumi_results(member1, &mut pdfset1, &set1);
-                let lumi_results2 = lumi_results(member2, &mut pdfset2, &set2);
+                let lumi_results1 = lumi_results(member1, &mut pdfset1, &set1)?;
+                let lumi_results2 = lumi_results(member2, &mut pdfset2, &set2)?;
 
                 let pull_tuples: Vec<_> = lumi_results2
                     .iter()
@@ -0,0 +1,93 @@
+use super::helpers::{self, GlobalConfiguration, Subcommand};
+use super::hepdata;
+use anyhow::{anyhow, bail, Result};
+use clap::{ArgGroup, Parser, ValueHint};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Merges the bins of a grid such that they have the given edges.
+#[derive(Parser)]
+#[command(group = ArgGroup::new("source").required(true))]
+pub struct Opts {
+    /// Path to the input grid.
+    #[arg(value_hint = ValueHint::FilePath)]
+    input: PathBuf,
+    /// Path to the modified `PineAPPL` file.
+    #[arg(value_hint = ValueHint::FilePath)]
+    output: PathBuf,
+    /// New bin edges of the last dimension.
+    #[arg(
+        allow_hyphen_values = true,
+        group = "source",
+        long,
+        num_args = 1,
+        value_delimiter = ',',
+        value_name = "EDGE1,EDGE2,..."
+    )]
+    edges: Vec<f64>,
+    /// Read the new bin edges from the last independent variable of a `HEPData` table, which must
+    /// be the same for all slices of a multi-dimensional table.
+    #[arg(group = "source", long, value_hint = ValueHint::FilePath, value_name = "YAML")]
+    hepdata: Option<PathBuf>,
+}
+
+impl Subcommand for Opts {
+    fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
+        let mut grid = helpers::read_grid(&self.input)?;
+
+        let edges = if let Some(path) = &self.hepdata {
+            hepdata_edges(&hepdata::read_table(path)?)?
+        } else {
+            self.edges.clone()
+        };
+
+        grid.rebin(&edges)?;
+
+        helpers::write_grid(&self.output, &grid)
+    }
+}
+
+/// Returns the bin edges of the last independent variable of `table`. Multi-dimensional tables
+/// consist of slices, whose bins have the same limits in all other variables, and the edges must
+/// be the same for every slice, since they are used for every slice of the grid.
+fn hepdata_edges(table: &hepdata::Table) -> Result<Vec<f64>> {
+    // the limits of the other variables and the edges of the last variable of each slice
+    let mut slices: Vec<(&[_], Vec<_>)> = Vec::new();
+
+    let limits = table.limits()?;
+
+    for limits in &limits {
+        let (&(low, high), others) = limits
+            .split_last()
+            .ok_or_else(|| anyhow!("the HEPData table does not have independent variables"))?;
+
+        match slices.last_mut() {
+            Some((slice, edges)) if *slice == others => {
+                // UNWRAP: every slice is created with two edges
+                let last = *edges.last().unwrap();
+
+                if low < last {
+                    bail!("the bins of the HEPData table overlap or are not sorted at {low}");
+                } else if low > last {
+                    bail!("the bins of the HEPData table have a gap between {last} and {low}");
+                }
+
+                edges.push(high);
+            }
+            _ => slices.push((others, vec![low, high])),
+        }
+    }
+
+    let (_, edges) = slices
+        .first()
+        .ok_or_else(|| anyhow!("the HEPData table does not have bins"))?;
+
+    if let Some((slice, other_edges)) = slices.iter().find(|(_, other)| other != edges) {
+        bail!(
+            "the bin edges {other_edges:?} of the HEPData slice {slice:?} differ from the edges \
+             {edges:?} of the first slice"
+        );
+    }
+
+    Ok(edges.clone())
+}
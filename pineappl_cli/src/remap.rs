@@ -1,14 +1,17 @@
 use super::helpers::{self, GlobalConfiguration, Subcommand};
-use anyhow::{bail, ensure, Context, Result};
-use clap::{Parser, ValueHint};
-use itertools::izip;
-use itertools::Itertools;
+use super::hepdata::{self, Table};
+use anyhow::{bail, ensure, Result};
+use clap::{ArgGroup, Parser, ValueHint};
+use float_cmp::approx_eq;
 use pineappl::bin::BinRemapper;
+use pineappl::grid::Grid;
+use std::iter;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
 /// Modifies the bin dimensions, widths and normalizations.
 #[derive(Parser)]
+#[command(group = ArgGroup::new("source").required(true))]
 pub struct Opts {
     /// Path to the input grid.
     #[arg(value_hint = ValueHint::FilePath)]
@@ -18,7 +21,11 @@ pub struct Opts {
     output: PathBuf,
     /// Remapping string. See <https://nnpdf.github.io/pineappl/docs/cli-reference.html> for full
     /// reference.
-    remapping: String,
+    #[arg(group = "source")]
+    remapping: Option<String>,
+    /// Read the bins, labels and units from a `HEPData` table instead of a remapping string.
+    #[arg(group = "source", long, value_hint = ValueHint::FilePath, value_name = "YAML")]
+    from_hepdata: Option<PathBuf>,
     /// Ignore the given observables for differential normalization.
     #[arg(
         long,
@@ -32,208 +39,126 @@ pub struct Opts {
     norm: f64,
 }
 
-fn parse_remapping_string(
-    remapping: &str,
-    ignore_obs_norm: &[usize],
-    norm: f64,
-) -> Result<(Vec<f64>, Vec<(f64, f64)>)> {
-    let remaps: Result<Vec<Vec<Vec<_>>>> = remapping
-        .split(';')
-        .map(|string| {
-            string
-                .split('|')
-                .map(|string| {
-                    string
-                        .find(':')
-                        .map_or(string, |index| {
-                            let (lhs, rhs) = string.split_at(index);
-                            let rhs = &rhs[1..]; // remove ':' which is contained with `split_at`
-
-                            // extract the part that doesn't belong to the ':' specification
-                            match (lhs.trim().parse::<usize>(), rhs.trim().parse::<usize>()) {
-                                (Err(_), Ok(_)) => lhs,
-                                (Ok(_), Err(_)) => rhs,
-                                _ => "",
-                            }
-                        })
-                        .split(',')
-                        .filter_map(|string| {
-                            let string = string.trim();
-                            if string.is_empty() {
-                                None
-                            } else {
-                                Some(
-                                    string
-                                        .parse::<f64>()
-                                        .context(format!("unable to parse limit '{string}'")),
-                                )
-                            }
-                        })
-                        .collect()
-                })
-                .collect()
-        })
-        .collect();
-    let mut remaps = remaps?;
-
-    ensure!(
-        remaps[0].len() == 1,
-        "'|' syntax not meaningful for first dimension"
-    );
-
-    // go over `remaps` again, and repeat previous entries as requested with the `|` syntax
-    for vec in &mut remaps {
-        for i in 1..vec.len() {
-            if vec[i].is_empty() {
-                ensure!(!vec[i - 1].is_empty(), "empty repetition with '|'");
-                vec[i] = vec[i - 1].clone();
-            }
-        }
-    }
-
-    // go over `remaps` again, this time remove bin as requested with the `:N` or `N:` syntax
-    for (vec, string) in remaps.iter_mut().zip(remapping.split(';')) {
-        for (vec, string) in vec.iter_mut().zip(string.split('|')) {
-            let (lhs, rhs) = {
-                let split: Vec<_> = string.split(':').collect();
-
-                if split.len() == 1 {
-                    // there's no colon
-                    continue;
-                }
-
-                ensure!(split.len() == 2, "too many ':' found: '{}'", string);
-
-                (split[0].parse::<usize>(), split[1].parse::<usize>())
-            };
-
-            if let Ok(num) = rhs {
-                vec.truncate(vec.len() - num);
-            }
-
-            if let Ok(num) = lhs {
-                vec.drain(0..num);
-            }
-
-            if lhs.is_err() && rhs.is_err() {
-                bail!("unable to parse ':' syntax from: '{}'", string);
-            }
+impl Subcommand for Opts {
+    fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
+        let mut grid = helpers::read_grid(&self.input)?;
 
-            if vec.len() <= 1 {
-                bail!("no limits due to ':' syntax");
-            }
+        if let Some(remapping) = &self.remapping {
+            grid.set_remapper(BinRemapper::parse(
+                remapping,
+                &self.ignore_obs_norm,
+                self.norm,
+            )?)?;
+        } else if let Some(path) = &self.from_hepdata {
+            let table = hepdata::read_table(path)?;
+            self.remap_from_hepdata(&mut grid, &table)?;
+        } else {
+            unreachable!();
         }
+
+        helpers::write_grid(&self.output, &grid)
     }
+}
 
-    let dimensions = remaps.len();
-    let mut normalizations = Vec::new();
-    let mut limits = Vec::new();
-    let mut buffer = Vec::with_capacity(dimensions);
-    let mut pipe_indices = vec![0; dimensions];
-    let mut last_indices = vec![0; dimensions];
-
-    'looop: for indices in remaps
-        .iter()
-        .map(|vec| 0..vec.iter().map(|vec| vec.len() - 1).max().unwrap())
-        .multi_cartesian_product()
-    {
-        // calculate `pipe_indices`, which stores the indices for the second dimension of `remaps`
-        for d in 0..dimensions - 1 {
-            if indices[d] > last_indices[d] {
-                for dp in d + 1..dimensions {
-                    if remaps[dp].len() != 1 {
-                        pipe_indices[dp] += 1;
-                    }
+impl Opts {
+    fn remap_from_hepdata(&self, grid: &mut Grid, table: &Table) -> Result<()> {
+        let limits = table.limits()?;
+        let bin_info = grid.bin_info();
+
+        ensure!(
+            limits.len() == bin_info.bins(),
+            "the HEPData table has {} bins, but the grid has {}",
+            limits.len(),
+            bin_info.bins()
+        );
+
+        // grids whose bins are simply numbered can be given any limits, otherwise the limits must
+        // be the same as in the table
+        let numbered = bin_info
+            .limits()
+            .iter()
+            .zip(iter::successors(Some(0.0), |x| Some(x + 1.0)))
+            .all(|(limits, index)| {
+                limits.len() == 1
+                    && approx_eq!(f64, limits[0].0, index, ulps = 4)
+                    && approx_eq!(f64, limits[0].1, index + 1.0, ulps = 4)
+            });
+
+        if !numbered {
+            ensure!(
+                bin_info.dimensions() == table.independent_variables.len(),
+                "the HEPData table has {} independent variables, but the bins of the grid have {} \
+                 dimensions",
+                table.independent_variables.len(),
+                bin_info.dimensions()
+            );
+
+            for (bin, (lhs, rhs)) in bin_info.limits().iter().zip(&limits).enumerate() {
+                if !lhs.iter().zip(rhs).all(|(&(a, b), &(c, d))| {
+                    approx_eq!(f64, a, c, ulps = 64) && approx_eq!(f64, b, d, ulps = 64)
+                }) {
+                    bail!(
+                        "the limits {:?} of bin {} of the HEPData table differ from the limits {:?} of the grid",
+                        rhs,
+                        bin,
+                        lhs
+                    );
                 }
             }
         }
 
-        last_indices = indices.clone();
-
-        let mut normalization = 1.0;
-
-        for (d, (remap, &pipe_index, &i)) in izip!(&remaps, &pipe_indices, &indices).enumerate() {
-            if let Some(r) = remap.get(pipe_index) {
-                if r.len() <= (i + 1) {
-                    buffer.clear();
-
-                    // this index doesn't exist
-                    continue 'looop;
-                }
-
-                let left = r[i];
-                let right = r[i + 1];
+        let old_dimensions = bin_info.dimensions();
+        let normalizations = limits
+            .iter()
+            .map(|limits| {
+                self.norm
+                    * limits
+                        .iter()
+                        .enumerate()
+                        .filter(|(dim, _)| !self.ignore_obs_norm.contains(&(dim + 1)))
+                        .map(|(_, (left, right))| right - left)
+                        .product::<f64>()
+            })
+            .collect();
+
+        grid.set_remapper(BinRemapper::new(
+            normalizations,
+            limits.into_iter().flatten().collect(),
+        )?)?;
+
+        let key_values = grid.key_values_mut();
+
+        for dim in 1..=old_dimensions.max(table.independent_variables.len()) {
+            key_values.remove(&format!("x{dim}_label"));
+            key_values.remove(&format!("x{dim}_label_tex"));
+            key_values.remove(&format!("x{dim}_unit"));
+        }
 
-                buffer.push((left, right));
+        key_values.remove("y_label");
+        key_values.remove("y_label_tex");
+        key_values.remove("y_unit");
 
-                if !ignore_obs_norm.iter().any(|dim| *dim == (d + 1)) {
-                    normalization *= right - left;
-                }
-            } else {
-                bail!("missing '|' specification: number of variants too small");
-            }
+        for (dim, variable) in table.independent_variables.iter().enumerate() {
+            set_label_and_unit(grid, &format!("x{}", dim + 1), &variable.header);
         }
 
-        limits.append(&mut buffer);
-        normalizations.push(norm * normalization);
-    }
-
-    Ok((normalizations, limits))
-}
+        if let Some(variable) = table.dependent_variables.first() {
+            set_label_and_unit(grid, "y", &variable.header);
+        }
 
-impl Subcommand for Opts {
-    fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
-        let mut grid = helpers::read_grid(&self.input)?;
-        let (normalizations, limits) =
-            parse_remapping_string(&self.remapping, &self.ignore_obs_norm, self.norm)?;
-        grid.set_remapper(BinRemapper::new(normalizations, limits).unwrap())?;
-        helpers::write_grid(&self.output, &grid)
+        Ok(())
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    #[should_panic(expected = "'|' syntax not meaningful for first dimension")]
-    fn pipe_syntax_first_dimension() {
-        parse_remapping_string("|0,1,2", &[], 1.0).unwrap();
-    }
-
-    #[test]
-    #[should_panic(expected = "empty repetition with '|'")]
-    fn pipe_syntax_first_empty() {
-        parse_remapping_string("0,1,2;0,2,4;||", &[], 1.0).unwrap();
-    }
-
-    #[test]
-    #[should_panic(expected = "too many ':' found: '::'")]
-    fn colon_syntax_too_many_colons() {
-        parse_remapping_string("0,1,2;0,2,4;1,2,3,4,5|::", &[], 1.0).unwrap();
-    }
-
-    #[test]
-    #[should_panic(expected = "unable to parse ':' syntax from: '2.5:'")]
-    fn colon_syntax_bad_lhs() {
-        parse_remapping_string("0,1,2;0,2,4;1,2,3,4,5|2.5:|:3|:3", &[], 1.0).unwrap();
-    }
-
-    #[test]
-    #[should_panic(expected = "unable to parse ':' syntax from: ':2.5'")]
-    fn colon_syntax_bad_rhs() {
-        parse_remapping_string("0,1,2;0,2,4;1,2,3,4,5|:2.5|:3|:3", &[], 1.0).unwrap();
-    }
+fn set_label_and_unit(grid: &mut Grid, prefix: &str, header: &hepdata::Header) {
+    grid.set_key_value(&format!("{prefix}_label"), &header.name);
 
-    #[test]
-    #[should_panic(expected = "no limits due to ':' syntax")]
-    fn colon_syntax_no_limits() {
-        parse_remapping_string("0,1,2;0,2,4;1,2,3,4,5|:4|:3|:3", &[], 1.0).unwrap();
+    // HEPData uses LaTeX for mathematical symbols
+    if header.name.contains('$') {
+        grid.set_key_value(&format!("{prefix}_label_tex"), &header.name);
     }
 
-    #[test]
-    #[should_panic(expected = "missing '|' specification: number of variants too small")]
-    fn pipe_syntax_too_few_pipes() {
-        parse_remapping_string("0,1,2;0,2,4;1,2,3|4,5,6|7,8,9", &[], 1.0).unwrap();
+    if !header.units.is_empty() {
+        grid.set_key_value(&format!("{prefix}_unit"), &header.units);
     }
 }
@@ -0,0 +1,209 @@
+use assert_cmd::Command;
+use std::num::NonZeroUsize;
+use std::thread;
+
+const HELP_STR: &str = "Calculates strong coupling uncertainties
+
+Usage: pineappl asunc [OPTIONS] <INPUT> <PDFSET> <PDFSET_DOWN> <PDFSET_UP>
+
+Arguments:
+  <INPUT>        Path to the input grid
+  <PDFSET>       LHAPDF id or name of the PDF set with the central value of the strong coupling
+  <PDFSET_DOWN>  LHAPDF id or name of the PDF set with the downwards-varied value of the strong coupling
+  <PDFSET_UP>    LHAPDF id or name of the PDF set with the upwards-varied value of the strong coupling
+
+Options:
+      --scale <FACTOR>     Rescale the uncertainty by the given factor, for instance to translate the variation of the strong coupling contained in the sets into the desired one [default: 1]
+      --symmetric          Symmetrize the strong coupling uncertainty
+      --pdf                Combine the strong coupling uncertainty in quadrature with the PDF uncertainty of the central set
+      --cl <CL>            Confidence level of the PDF uncertainty in per cent [default: 68.26894921370858]
+  -i, --integrated         Show integrated numbers (without bin widths) instead of differential ones
+  -o, --orders <ORDERS>    Select orders manually
+      --threads <THREADS>  Number of threads to utilize [default: {}]
+      --digits-abs <ABS>   Set the number of fractional digits shown for absolute numbers [default: 7]
+      --digits-rel <REL>   Set the number of fractional digits shown for relative numbers [default: 2]
+  -h, --help               Print help information
+";
+
+const PDF_STR: &str =
+    "b   etal    disg/detal  alphas uncertainty  PDF uncertainty total uncertainty
+     []        [pb]             [%]               [%]              [%]       
+-+----+----+-----------+---------+---------+-------+-------+--------+--------
+0    2 2.25 3.7528868e2     -0.00      0.00   -1.14    1.14    -1.14     1.14
+1 2.25  2.5 3.4521365e2     -0.00      0.00   -1.16    1.16    -1.16     1.16
+2  2.5 2.75 3.0000102e2     -0.00      0.00   -1.18    1.18    -1.18     1.18
+3 2.75    3 2.4255656e2     -0.00      0.00   -1.22    1.22    -1.22     1.22
+4    3 3.25 1.8091118e2     -0.00      0.00   -1.27    1.27    -1.27     1.27
+5 3.25  3.5 1.2289094e2     -0.00      0.00   -1.35    1.35    -1.35     1.35
+6  3.5    4 5.7837137e1     -0.00      0.00   -1.50    1.50    -1.50     1.50
+7    4  4.5 1.3765722e1     -0.00      0.00   -2.76    2.76    -2.76     2.76
+";
+
+const ASYMMETRIC_STR: &str = "b   etal    disg/detal  alphas uncertainty 
+     []        [pb]             [%]        
+-+----+----+-----------+---------+---------
+0    2 2.25 3.7527620e2     -0.39      0.38
+1 2.25  2.5 3.4521553e2     -0.60      0.22
+2  2.5 2.75 3.0001406e2     -0.74      0.08
+3 2.75    3 2.4257663e2     -0.82      0.01
+4    3 3.25 1.8093343e2     -0.84      0.01
+5 3.25  3.5 1.2291115e2     -0.78      0.14
+6  3.5    4 5.7851018e1     -0.52      0.65
+7    4  4.5 1.3772029e1     -0.95      3.36
+";
+
+const SYMMETRIC_STR: &str = "b   etal    disg/detal  alphas uncertainty 
+     []        [pb]             [%]        
+-+----+----+-----------+---------+---------
+0    2 2.25 3.7527620e2     -0.39      0.39
+1 2.25  2.5 3.4521553e2     -0.41      0.41
+2  2.5 2.75 3.0001406e2     -0.41      0.41
+3 2.75    3 2.4257663e2     -0.41      0.41
+4    3 3.25 1.8093343e2     -0.43      0.43
+5 3.25  3.5 1.2291115e2     -0.46      0.46
+6  3.5    4 5.7851018e1     -0.59      0.59
+7    4  4.5 1.3772029e1     -2.15      2.15
+";
+
+const SCALE_STR: &str = "b   etal    disg/detal  alphas uncertainty 
+     []        [pb]             [%]        
+-+----+----+-----------+---------+---------
+0    2 2.25 3.7527620e2     -0.20      0.19
+1 2.25  2.5 3.4521553e2     -0.30      0.11
+2  2.5 2.75 3.0001406e2     -0.37      0.04
+3 2.75    3 2.4257663e2     -0.41      0.00
+4    3 3.25 1.8093343e2     -0.42      0.01
+5 3.25  3.5 1.2291115e2     -0.39      0.07
+6  3.5    4 5.7851018e1     -0.26      0.33
+7    4  4.5 1.3772029e1     -0.48      1.68
+";
+
+const PDF_MEMBERS_STR: &str =
+    "b   etal    disg/detal  alphas uncertainty  PDF uncertainty total uncertainty
+     []        [pb]             [%]               [%]              [%]       
+-+----+----+-----------+---------+---------+-------+-------+--------+--------
+0    2 2.25 3.7528868e2     -0.40      0.38   -0.01    0.01    -0.40     0.38
+1 2.25  2.5 3.4521365e2     -0.59      0.22   -0.01    0.01    -0.60     0.22
+2  2.5 2.75 3.0000102e2     -0.73      0.08   -0.01    0.01    -0.73     0.08
+3 2.75    3 2.4255656e2     -0.81      0.02   -0.02    0.02    -0.81     0.02
+4    3 3.25 1.8091118e2     -0.83      0.03   -0.02    0.02    -0.83     0.03
+5 3.25  3.5 1.2289094e2     -0.76      0.16   -0.02    0.02    -0.76     0.16
+6  3.5    4 5.7837137e1     -0.49      0.68   -0.02    0.02    -0.49     0.68
+7    4  4.5 1.3765722e1     -0.91      3.40   -0.03    0.03    -0.91     3.40
+";
+
+#[test]
+fn help() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&["asunc", "--help"])
+        .assert()
+        .success()
+        .stdout(
+            HELP_STR.replace(
+                "{}",
+                &thread::available_parallelism()
+                    .map_or(1, NonZeroUsize::get)
+                    .to_string(),
+            ),
+        );
+}
+
+#[test]
+fn pdf() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "asunc",
+            "--pdf",
+            "--threads=1",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+            "NNPDF31_nlo_as_0118_luxqed",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(PDF_STR);
+}
+
+#[test]
+fn asymmetric() {
+    // the replicas of a single set stand in for sets with different values of the strong coupling
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "asunc",
+            "--threads=1",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+            "NNPDF31_nlo_as_0118_luxqed/1",
+            "NNPDF31_nlo_as_0118_luxqed/2",
+        ])
+        .assert()
+        .success()
+        .stdout(ASYMMETRIC_STR);
+}
+
+#[test]
+fn symmetric() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "asunc",
+            "--symmetric",
+            "--threads=1",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+            "NNPDF31_nlo_as_0118_luxqed/1",
+            "NNPDF31_nlo_as_0118_luxqed/2",
+        ])
+        .assert()
+        .success()
+        .stdout(SYMMETRIC_STR);
+}
+
+#[test]
+fn scale() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "asunc",
+            "--scale=0.5",
+            "--threads=1",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+            "NNPDF31_nlo_as_0118_luxqed/1",
+            "NNPDF31_nlo_as_0118_luxqed/2",
+        ])
+        .assert()
+        .success()
+        .stdout(SCALE_STR);
+}
+
+#[test]
+fn pdf_members() {
+    // the uncertainties are relative to the central value of the PDF set, which differs from the
+    // prediction of its first member, and the small confidence level makes the total uncertainty
+    // sensitive to the strong coupling uncertainty
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "asunc",
+            "--pdf",
+            "--cl=1",
+            "--threads=1",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+            "NNPDF31_nlo_as_0118_luxqed/1",
+            "NNPDF31_nlo_as_0118_luxqed/2",
+        ])
+        .assert()
+        .success()
+        .stdout(PDF_MEMBERS_STR);
+}
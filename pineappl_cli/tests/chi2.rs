@@ -0,0 +1,189 @@
+use assert_cmd::Command;
+use assert_fs::{fixture::FileWriteStr, NamedTempFile};
+
+const HELP_STR: &str = "Compares the predictions of a grid with experimental data using a chi-square
+
+Usage: pineappl chi2 [OPTIONS] <INPUT> <DATA> <PDFSET>
+
+Arguments:
+  <INPUT>   Path to the input grid
+  <DATA>    Path to the experimental data, either a HEPData table in YAML format or a CSV file
+  <PDFSET>  LHAPDF id or name of the PDF set
+
+Options:
+      --t0 <PDFSET>       Use the t0 prescription with the given PDF set for the correlated uncertainties
+      --members           Additionally show the chi-square for each member of the PDF set
+  -o, --orders <ORDERS>   Select orders manually
+      --digits-abs <ABS>  Set the number of fractional digits shown for absolute numbers [default: 7]
+      --digits-rel <REL>  Set the number of fractional digits shown for relative numbers [default: 2]
+  -h, --help              Print help information
+";
+
+const DEFAULT_STR: &str = "b   etal       data     disg/detal     sigma    pull 
+     []        [pb]        [pb]        [pb]      [\u{3c3}] 
+-+----+----+-----------+-----------+-----------+-----
+0    2 2.25 3.8527620e2 3.7527620e2 1.0000000e1 -1.00
+1 2.25  2.5 3.5521553e2 3.4521553e2 1.0000000e1 -1.00
+2  2.5 2.75 3.1001406e2 3.0001406e2 1.0000000e1 -1.00
+3 2.75    3 2.5257663e2 2.4257663e2 1.0000000e1 -1.00
+4    3 3.25 1.9093343e2 1.8093343e2 1.0000000e1 -1.00
+5 3.25  3.5 1.3291115e2 1.2291115e2 1.0000000e1 -1.00
+6  3.5    4 6.7851018e1 5.7851018e1 1.0000000e1 -1.00
+7    4  4.5 2.3772029e1 1.3772029e1 1.0000000e1 -1.00
+\u{3c7}\u{b2} = 8.00 for 8 data points
+";
+
+const DATA_CSV: &str = "# data shifted by +10 pb with respect to the predictions
+value,stat
+385.27620,10
+355.21553,10
+310.01406,10
+252.57663,10
+190.93343,10
+132.91115,10
+67.851018,10
+23.772029,10
+";
+
+const DATA_YAML: &str = "independent_variables:
+- header: {name: 'etal'}
+  values:
+  - {low: 2.0, high: 2.25}
+  - {low: 2.25, high: 2.5}
+  - {low: 2.5, high: 2.75}
+  - {low: 2.75, high: 3.0}
+  - {low: 3.0, high: 3.25}
+  - {low: 3.25, high: 3.5}
+  - {low: 3.5, high: 4.0}
+  - {low: 4.0, high: 4.5}
+dependent_variables:
+- header: {name: 'd$\\sigma$/d$\\eta_l$', units: 'pb'}
+  values:
+  - value: 385.27620
+    errors:
+    - {symerror: 6, label: stat}
+    - {asymerror: {plus: 8, minus: -8}, label: 'sys,uncorr'}
+  - value: 355.21553
+    errors:
+    - {symerror: 6, label: stat}
+    - {asymerror: {plus: 8, minus: -8}, label: 'sys,uncorr'}
+  - value: 310.01406
+    errors:
+    - {symerror: 6, label: stat}
+    - {asymerror: {plus: 8, minus: -8}, label: 'sys,uncorr'}
+  - value: 252.57663
+    errors:
+    - {symerror: 6, label: stat}
+    - {asymerror: {plus: 8, minus: -8}, label: 'sys,uncorr'}
+  - value: 190.93343
+    errors:
+    - {symerror: 6, label: stat}
+    - {asymerror: {plus: 8, minus: -8}, label: 'sys,uncorr'}
+  - value: 132.91115
+    errors:
+    - {symerror: 6, label: stat}
+    - {asymerror: {plus: 8, minus: -8}, label: 'sys,uncorr'}
+  - value: 67.851018
+    errors:
+    - {symerror: 6, label: stat}
+    - {asymerror: {plus: 8, minus: -8}, label: 'sys,uncorr'}
+  - value: 23.772029
+    errors:
+    - {symerror: 6, label: stat}
+    - {asymerror: {plus: 8, minus: -8}, label: 'sys,uncorr'}
+";
+
+#[test]
+fn help() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&["chi2", "--help"])
+        .assert()
+        .success()
+        .stdout(HELP_STR);
+}
+
+#[test]
+fn default() {
+    let data = NamedTempFile::new("data.csv").unwrap();
+    data.write_str(DATA_CSV).unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "chi2",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            data.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn hepdata() {
+    let data = NamedTempFile::new("data.yaml").unwrap();
+    data.write_str(DATA_YAML).unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "chi2",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            data.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn bin_mismatch() {
+    let data = NamedTempFile::new("data.csv").unwrap();
+    data.write_str("value,stat\n1,1\n").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "chi2",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            data.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: the data has 1 bins, but the grid has 8\n");
+}
+
+#[test]
+fn t0_zero_data() {
+    let data = NamedTempFile::new("data.csv").unwrap();
+    data.write_str(
+        &DATA_CSV
+            .replace("value,stat\n385.27620,10", "value,stat,sys\n0,10,1%")
+            .replace(",10\n", ",10,1%\n"),
+    )
+    .unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "chi2",
+            "--t0=NNPDF31_nlo_as_0118_luxqed",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            data.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            "Error: the t0 prescription can not rescale the uncertainties of bin 0, whose data is \
+             zero\n",
+        );
+}
@@ -0,0 +1,65 @@
+use pineappl::fk_table::FkTable;
+use pineappl::grid::{Grid, Order};
+use pineappl::import_only_subgrid::ImportOnlySubgridV2;
+use pineappl::lumi::LumiEntry;
+use pineappl::sparse_array3::SparseArray3;
+use pineappl::subgrid::{Mu2, SubgridParams};
+
+/// Returns an FK table with the channels `lumis` in the basis `lumi_id_types`, the bins given by
+/// `bin_limits` and subgrids using `x_grid`, which are filled with consecutive integers starting at
+/// one. If the second initial state of every channel is an electron, the FK table is one for DIS.
+pub fn fk_table(
+    lumis: Vec<LumiEntry>,
+    lumi_id_types: &str,
+    bin_limits: Vec<f64>,
+    x_grid: &[f64],
+) -> FkTable {
+    let hadronic = lumis
+        .iter()
+        .any(|lumi| lumi.entry().iter().all(|&(_, b, _)| b != 11));
+    let bins = bin_limits.len() - 1;
+    let mut grid = Grid::new(
+        lumis.clone(),
+        vec![Order::new(0, 0, 0, 0, 0)],
+        bin_limits,
+        SubgridParams::default(),
+    );
+    let mut values = (1..).map(f64::from);
+
+    for bin in 0..bins {
+        for lumi in 0..lumis.len() {
+            let nx2 = if hadronic { x_grid.len() } else { 1 };
+            let mut array = SparseArray3::new(1, x_grid.len(), nx2);
+
+            for ix1 in 0..x_grid.len() {
+                for ix2 in 0..nx2 {
+                    array[[0, ix1, ix2]] = values.next().unwrap();
+                }
+            }
+
+            grid.set_subgrid(
+                0,
+                bin,
+                lumi,
+                ImportOnlySubgridV2::new(
+                    array,
+                    vec![Mu2 {
+                        ren: 2.7225,
+                        fac: 2.7225,
+                    }],
+                    x_grid.to_vec(),
+                    if hadronic { x_grid.to_vec() } else { vec![1.0] },
+                )
+                .into(),
+            );
+        }
+    }
+
+    grid.set_key_value("lumi_id_types", lumi_id_types);
+
+    if !hadronic {
+        grid.set_key_value("initial_state_2", "11");
+    }
+
+    FkTable::try_from(grid).unwrap()
+}
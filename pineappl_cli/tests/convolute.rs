@@ -1,4 +1,5 @@
 use assert_cmd::Command;
+use assert_fs::NamedTempFile;
 
 const HELP_STR: &str = "Convolutes a PineAPPL grid with a PDF set
 
@@ -12,8 +13,9 @@ Options:
   -a, --absolute          Show absolute numbers of the scale variation
   -b, --bins <BINS>       Selects a subset of bins
   -i, --integrated        Show integrated numbers (without bin widths) instead of differential ones
+      --observables       Show the observables defined in the grid instead of its bins
   -o, --orders <ORDERS>   Select orders manually
-  -s, --scales <SCALES>   Set the number of scale variations [default: 7] [possible values: 1, 3, 7, 9]
+  -s, --scales <SCALES>   Set the scale-variation prescription: 1, 3, 7, 9, 3-ren, 3-fac or 31-corr, optionally followed by @FACTOR, or a list of xir:xif pairs [default: 7]
       --digits-abs <ABS>  Set the number of fractional digits shown for absolute numbers [default: 7]
       --digits-rel <REL>  Set the number of fractional digits shown for relative numbers [default: 2]
   -h, --help              Print help information
@@ -204,11 +206,28 @@ const ORDERS_A2_A3_STR: &str = "b   etal    disg/detal  scale uncertainty
 7    4  4.5 1.1075878e1    -4.60     3.35
 ";
 
+const OBSERVABLES_STR: &str = "b   etal    disg/detal  scale uncertainty
+     []        [pb]            [%]       
+-+----+----+-----------+--------+--------
+0    4  4.5 1.3772029e1    -3.46     2.85
+1    2 2.25 3.7527620e2    -3.77     2.71
+2 2.25  2.5 3.4521553e2    -3.79     2.80
+";
+
+const OBSERVABLES_UNDEFINED_STR: &str = "Error: the grid does not define observables, set them with `pineappl set --entry observables <EXPRESSIONS>`
+";
+
 const WRONG_ORDERS_STR: &str = "error: Invalid value 'a2a2as2' for '--orders <ORDERS>': unable to parse order; too many couplings in 'a2a2as2'
 
 For more information try '--help'
 ";
 
+const WRONG_SCALES_STR: &str =
+    "error: Invalid value '5' for '--scales <SCALES>': unknown scale-variation prescription '5'
+
+For more information try '--help'
+";
+
 const SCALES_9_STR: &str = "b   etal    disg/detal  scale uncertainty
      []        [pb]            [%]       
 -+----+----+-----------+--------+--------
@@ -222,6 +241,61 @@ const SCALES_9_STR: &str = "b   etal    disg/detal  scale uncertainty
 7    4  4.5 1.3772029e1    -5.36     5.22
 ";
 
+const SCALES_31_CORR_STR: &str = "b   etal    disg/detal  scale uncertainty
+     []        [pb]            [%]       
+-+----+----+-----------+--------+--------
+0    2 2.25 3.7527620e2    -1.54     1.71
+1 2.25  2.5 3.4521553e2    -1.59     1.72
+2  2.5 2.75 3.0001406e2    -1.61     1.70
+3 2.75    3 2.4257663e2    -1.63     1.68
+4    3 3.25 1.8093343e2    -1.62     1.63
+5 3.25  3.5 1.2291115e2    -1.57     1.55
+6  3.5    4 5.7851018e1    -1.46     1.38
+7    4  4.5 1.3772029e1    -1.08     0.96
+";
+
+const ABSOLUTE_SCALES_3_REN_STR: &str =
+    "b   etal    disg/detal     (1,1)       (2,1)      (0.5,1)  
+     []        [pb]        [pb]        [pb]        [pb]    
+-+----+----+-----------+-----------+-----------+-----------
+0    2 2.25 3.7527620e2 3.7527620e2 3.7004750e2 3.8178087e2
+1 2.25  2.5 3.4521553e2 3.4521553e2 3.4031501e2 3.5131193e2
+2  2.5 2.75 3.0001406e2 3.0001406e2 2.9567460e2 3.0541248e2
+3 2.75    3 2.4257663e2 2.4257663e2 2.3902145e2 2.4699938e2
+4    3 3.25 1.8093343e2 1.8093343e2 1.7821415e2 1.8431630e2
+5 3.25  3.5 1.2291115e2 1.2291115e2 1.2099928e2 1.2528958e2
+6  3.5    4 5.7851018e1 5.7851018e1 5.6897537e1 5.9037178e1
+7    4  4.5 1.3772029e1 1.3772029e1 1.3512675e1 1.4094674e1
+";
+
+const ABSOLUTE_SCALES_3_FAC_STR: &str =
+    "b   etal    disg/detal     (1,1)       (1,2)      (1,0.5)  
+     []        [pb]        [pb]        [pb]        [pb]    
+-+----+----+-----------+-----------+-----------+-----------
+0    2 2.25 3.7527620e2 3.7527620e2 3.8546011e2 3.6114679e2
+1 2.25  2.5 3.4521553e2 3.4521553e2 3.5487551e2 3.3214336e2
+2  2.5 2.75 3.0001406e2 3.0001406e2 3.0860377e2 2.8866119e2
+3 2.75    3 2.4257663e2 2.4257663e2 2.4965490e2 2.3342681e2
+4    3 3.25 1.8093343e2 1.8093343e2 1.8627534e2 1.7416314e2
+5 3.25  3.5 1.2291115e2 1.2291115e2 1.2657016e2 1.1835555e2
+6  3.5    4 5.7851018e1 5.7851018e1 5.9567473e1 5.5752518e1
+7    4  4.5 1.3772029e1 1.3772029e1 1.4165115e1 1.3296051e1
+";
+
+const ABSOLUTE_SCALES_7_FACTOR_STR: &str =
+"b   etal    disg/detal     (1,1)     (0.5,0.5)     (2,2)      (0.5,1)     (1,0.5)      (2,1)       (1,2)   
+     []        [pb]        [pb]        [pb]        [pb]        [pb]        [pb]        [pb]        [pb]    
+-+----+----+-----------+-----------+-----------+-----------+-----------+-----------+-----------+-----------
+0    2 2.25 3.7527620e2 3.7527620e2 3.6948620e2 3.8169721e2 3.8178087e2 3.6114679e2 3.7004750e2 3.8546011e2
+1 2.25  2.5 3.4521553e2 3.4521553e2 3.3973093e2 3.5114200e2 3.5131193e2 3.3214336e2 3.4031501e2 3.5487551e2
+2  2.5 2.75 3.0001406e2 3.0001406e2 2.9517901e2 3.0511561e2 3.0541248e2 2.8866119e2 2.9567460e2 3.0860377e2
+3 2.75    3 2.4257663e2 2.4257663e2 2.3861256e2 2.4665730e2 2.4699938e2 2.3342681e2 2.3902145e2 2.4965490e2
+4    3 3.25 1.8093343e2 1.8093343e2 1.7800964e2 1.8387616e2 1.8431630e2 1.7416314e2 1.7821415e2 1.8627534e2
+5 3.25  3.5 1.2291115e2 1.2291115e2 1.2097578e2 1.2481060e2 1.2528958e2 1.1835555e2 1.2099928e2 1.2657016e2
+6  3.5    4 5.7851018e1 5.7851018e1 5.7008512e1 5.8647563e1 5.9037178e1 5.5752518e1 5.6897537e1 5.9567473e1
+7    4  4.5 1.3772029e1 1.3772029e1 1.3622752e1 1.3903642e1 1.4094674e1 1.3296051e1 1.3512675e1 1.4165115e1
+";
+
 #[test]
 fn help() {
     Command::cargo_bin("pineappl")
@@ -473,6 +547,54 @@ fn orders_a2_a3() {
         .stdout(ORDERS_A2_A3_STR);
 }
 
+#[test]
+fn observables() {
+    let output = NamedTempFile::new("observables.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "set",
+            "--entry",
+            "observables",
+            "b7; b0; b1",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            "--observables",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(OBSERVABLES_STR);
+}
+
+#[test]
+fn observables_undefined() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            "--observables",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(OBSERVABLES_UNDEFINED_STR);
+}
+
 #[test]
 fn wrong_orders() {
     Command::cargo_bin("pineappl")
@@ -504,3 +626,105 @@ fn scales_9() {
         .success()
         .stdout(SCALES_9_STR);
 }
+
+#[test]
+fn scales_9_list() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            "--scales=1:1,2:2,0.5:0.5,2:1,1:2,0.5:1,1:0.5,2:0.5,0.5:2",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(SCALES_9_STR);
+}
+
+#[test]
+fn wrong_scales() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            "--scales=5",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(WRONG_SCALES_STR);
+}
+
+#[test]
+fn scales_31_corr() {
+    // the predictions change monotonically with the scales, and therefore the envelope is the one
+    // of the three-point prescription
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            "--scales=31-corr",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(SCALES_31_CORR_STR);
+}
+
+#[test]
+fn absolute_scales_3_ren() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            "--absolute",
+            "--scales=3-ren",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(ABSOLUTE_SCALES_3_REN_STR);
+}
+
+#[test]
+fn absolute_scales_3_fac() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            "--absolute",
+            "--scales=3-fac",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(ABSOLUTE_SCALES_3_FAC_STR);
+}
+
+#[test]
+fn absolute_scales_7_factor() {
+    // a factor of `0.5` swaps the scales `2` and `0.5` of the seven-point prescription
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            "--absolute",
+            "--scales=7@0.5",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(ABSOLUTE_SCALES_7_FACTOR_STR);
+}
@@ -3,15 +3,121 @@
 use assert_cmd::Command;
 use assert_fs::NamedTempFile;
 
+#[cfg(feature = "evolve")]
+mod eko {
+    use lz4_flex::frame::FrameEncoder;
+    use ndarray::{Array4, Array5};
+    use ndarray_npy::{NpzWriter, WriteNpyExt};
+    use pineappl::grid::Grid;
+    use std::fs::File;
+    use std::io::{Cursor, Write};
+    use std::path::Path;
+    use tar::{Builder, Header};
+
+    fn append(builder: &mut Builder<File>, path: &str, data: &[u8]) {
+        let mut header = Header::new_gnu();
+        header.set_size(data.len().try_into().unwrap());
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, data).unwrap();
+    }
+
+    fn lz4(data: &[u8]) -> Vec<u8> {
+        let mut encoder = FrameEncoder::new(Vec::new());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// Writes an EKO to `eko` that evolves `grid` with the identity operator. If `legacy` is
+    /// `true` the legacy format is used, otherwise each scale is stored in a separate file, in
+    /// descending order of the scales if `reverse` is `true`. The operator file for the scale with
+    /// index `skip` is left out. The fitting scale is `fac0`, or the first scale of `grid` if it is
+    /// `None`.
+    pub fn identity(
+        grid: &str,
+        eko: &Path,
+        legacy: bool,
+        reverse: bool,
+        skip: Option<usize>,
+        fac0: Option<f64>,
+    ) {
+        let info = Grid::read(File::open(grid).unwrap())
+            .unwrap()
+            .evolve_info(&[]);
+        let (pids, x) = (&info.pids1, &info.x1);
+        let slice = Array4::from_shape_fn(
+            (pids.len(), x.len(), pids.len(), x.len()),
+            |(pid1, x1, pid0, x0)| {
+                if (pid1 == pid0) && (x1 == x0) {
+                    1.0
+                } else {
+                    0.0
+                }
+            },
+        );
+        let fac0 = fac0.unwrap_or(info.fac1[0]);
+        let mut builder = Builder::new(File::create(eko).unwrap());
+
+        if legacy {
+            let metadata = format!(
+                "Q2grid: {:?}\ninputgrid: {:?}\ninputpids: {:?}\nq2_ref: {:?}\ntargetgrid: {:?}\ntargetpids: {:?}\n",
+                info.fac1, x, pids, fac0, x, pids
+            );
+            let operator = Array5::from_shape_fn(
+                (info.fac1.len(), pids.len(), x.len(), pids.len(), x.len()),
+                |(_, pid1, x1, pid0, x0)| slice[[pid1, x1, pid0, x0]],
+            );
+            let mut npy = Vec::new();
+            operator.write_npy(&mut npy).unwrap();
+
+            append(&mut builder, "./metadata.yaml", metadata.as_bytes());
+            append(&mut builder, "./operators.npy.lz4", &lz4(&npy));
+        } else {
+            let metadata = format!(
+                "mu20: {:?}\nbases:\n  xgrid: {:?}\n  pids: {:?}\n",
+                fac0, x, pids
+            );
+            append(&mut builder, "./metadata.yaml", metadata.as_bytes());
+
+            let mut scales: Vec<_> = info.fac1.iter().enumerate().collect();
+
+            if reverse {
+                scales.reverse();
+            }
+
+            for (index, fac1) in scales {
+                append(
+                    &mut builder,
+                    &format!("./operators/{index}.yaml"),
+                    format!("scale: {fac1:?}\n").as_bytes(),
+                );
+
+                if skip != Some(index) {
+                    let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+                    npz.add_array("operator.npy", &slice).unwrap();
+                    let npz = npz.finish().unwrap().into_inner();
+                    append(
+                        &mut builder,
+                        &format!("./operators/{index}.npz.lz4"),
+                        &lz4(&npz),
+                    );
+                }
+            }
+        }
+
+        builder.finish().unwrap();
+    }
+}
+
 const HELP_STR: &str = "Evolve a grid with an evolution kernel operator to an FK table
 
 Usage: pineappl evolve [OPTIONS] <INPUT> <EKO> <OUTPUT> <PDFSET>
+       pineappl evolve [OPTIONS] --builtin <ORDER> <INPUT> <OUTPUT> <PDFSET>
 
 Arguments:
-  <INPUT>   Path to the input grid
-  <EKO>     Path to the evolution kernel operator
-  <OUTPUT>  Path to the converted grid
-  <PDFSET>  LHAPDF id or name of the PDF set to check the converted grid with
+  <INPUT>            Path to the input grid
+  <EKO> <OUTPUT>...  Path to the evolution kernel operator, which must be omitted with `--builtin`, followed by the path to the converted grid
+  <PDFSET>           LHAPDF id or name of the PDF set to check the converted grid with
 
 Options:
       --accuracy <ACCURACY>  Relative threshold between the table and the converted grid when comparison fails [default: 1e-3]
@@ -20,9 +126,20 @@ Options:
   -o, --orders <ORDERS>      Select which orders to evolve
       --xir <XIR>            Rescale the renormalization scale with this factor [default: 1]
       --xif <XIF>            Rescale the factorization scale with this factor [default: 1]
+      --builtin <ORDER>      Compute the evolution kernel operator with the built-in DGLAP evolution at the given order, using the starting scale and quark masses of the PDF set [possible values: LO, NLO]
+      --basis <BASIS>        Rotate the FK table into the particle basis `pdg_mc_ids` or `evol`, or into the custom basis given by a YAML file
+      --eko2 <EKO2>          Evolve the second initial state with this evolution kernel operator instead of `EKO`
   -h, --help                 Print help information
 ";
 
+const BUILTIN_UNKNOWN_ORDER_STR: &str = "error: 'NNLO' isn't a valid value for '--builtin <ORDER>'
+  [possible values: LO, NLO]
+
+  Did you mean 'NLO'?
+
+For more information try '--help'
+";
+
 const E906NLO_BIN_00_STR: &str = "b     Grid       FkTable      rel. diff
 -+------------+------------+-------------
 0 1.0659807e-1 1.0657904e-1 -1.7851986e-4
@@ -249,3 +366,394 @@ fn lhcb_dy_8tev() {
         .success()
         .stdout(LHCB_DY_8TEV_STR);
 }
+
+#[cfg(feature = "evolve")]
+#[test]
+fn identity_legacy() {
+    let eko = NamedTempFile::new("eko1.tar").unwrap();
+    let output = NamedTempFile::new("fktable5.lz4").unwrap();
+
+    eko::identity(
+        "data/LHCB_WP_7TEV.pineappl.lz4",
+        eko.path(),
+        true,
+        false,
+        None,
+        None,
+    );
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            eko.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success();
+}
+
+#[cfg(feature = "evolve")]
+#[test]
+fn identity_multi_file() {
+    let eko = NamedTempFile::new("eko2.tar").unwrap();
+    let output = NamedTempFile::new("fktable6.lz4").unwrap();
+
+    eko::identity(
+        "data/LHCB_WP_7TEV.pineappl.lz4",
+        eko.path(),
+        false,
+        false,
+        None,
+        None,
+    );
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            eko.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success();
+}
+
+#[cfg(feature = "evolve")]
+#[test]
+fn missing_operator() {
+    let eko = NamedTempFile::new("eko3.tar").unwrap();
+    let output = NamedTempFile::new("fktable7.lz4").unwrap();
+
+    eko::identity(
+        "data/LHCB_WP_7TEV.pineappl.lz4",
+        eko.path(),
+        false,
+        false,
+        Some(0),
+        None,
+    );
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            eko.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: the EKO does not contain the operator `operators/0.npz.lz4`\n");
+}
+
+#[cfg(feature = "evolve")]
+#[test]
+fn builtin_unknown_order() {
+    let output = NamedTempFile::new("fktable8.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            "--builtin=NNLO",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(BUILTIN_UNKNOWN_ORDER_STR);
+}
+
+#[cfg(feature = "evolve")]
+#[test]
+fn builtin_with_eko() {
+    let eko = NamedTempFile::new("eko6.tar").unwrap();
+    let output = NamedTempFile::new("fktable12.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            "--builtin=LO",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            eko.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: an EKO must not be given together with `--builtin`\n");
+}
+
+#[cfg(feature = "evolve")]
+#[test]
+fn eko_missing() {
+    let output = NamedTempFile::new("fktable16.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: an EKO must be given without `--builtin`\n");
+}
+
+#[cfg(feature = "dglap")]
+#[test]
+fn builtin_lo() {
+    use pineappl::fk_table::FkTable;
+    use pineappl::grid::Grid;
+    use std::fs::File;
+
+    let output = NamedTempFile::new("fktable13.lz4").unwrap();
+
+    // the PDF set is evolved at NLO, so the LO evolution doesn't reproduce the grid exactly
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            "--accuracy=1",
+            "--builtin=LO",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success();
+
+    let grid = Grid::read(File::open(output.path()).unwrap()).unwrap();
+    let fk_table = FkTable::try_from(grid).unwrap();
+    let pdf = lhapdf::Pdf::with_setname_and_member("NNPDF31_nlo_as_0118_luxqed", 0).unwrap();
+    let qmin: f64 = pdf.set().entry("QMin").unwrap().parse().unwrap();
+
+    assert_eq!(fk_table.bins(), 8);
+    assert!((fk_table.muf2() / (qmin * qmin) - 1.0).abs() < 1e-12);
+}
+
+#[cfg(feature = "evolve")]
+#[test]
+fn identity_eko2() {
+    use pineappl::fk_table::FkTable;
+    use pineappl::grid::Grid;
+    use std::fs::File;
+
+    let eko = NamedTempFile::new("eko7.tar").unwrap();
+    let eko2 = NamedTempFile::new("eko8.tar").unwrap();
+    let output = NamedTempFile::new("fktable14.lz4").unwrap();
+
+    eko::identity(
+        "data/LHCB_WP_7TEV.pineappl.lz4",
+        eko.path(),
+        false,
+        false,
+        None,
+        None,
+    );
+    eko::identity(
+        "data/LHCB_WP_7TEV.pineappl.lz4",
+        eko2.path(),
+        true,
+        false,
+        None,
+        Some(10000.0),
+    );
+
+    // the second initial state isn't evolved to its fitting scale, so the check can't succeed
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            "--accuracy=1",
+            &format!("--eko2={}", eko2.path().to_str().unwrap()),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            eko.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success();
+
+    let grid = Grid::read(File::open("data/LHCB_WP_7TEV.pineappl.lz4").unwrap()).unwrap();
+    let fac0 = grid.evolve_info(&[]).fac1[0];
+    let fk_table =
+        FkTable::try_from(Grid::read(File::open(output.path()).unwrap()).unwrap()).unwrap();
+
+    let [muf2_1, muf2_2] = fk_table.muf2_per_initial_state();
+
+    assert!((muf2_1 / fac0 - 1.0).abs() < 1e-12);
+    assert!((muf2_2 / 10000.0 - 1.0).abs() < 1e-12);
+    assert!((fk_table.muf2() / fac0 - 1.0).abs() < 1e-12);
+}
+
+#[cfg(feature = "evolve")]
+#[test]
+fn identity_eko2_reversed() {
+    let eko = NamedTempFile::new("eko9.tar").unwrap();
+    let eko2 = NamedTempFile::new("eko10.tar").unwrap();
+    let output = NamedTempFile::new("fktable15.lz4").unwrap();
+
+    eko::identity(
+        "data/LHCB_WP_7TEV.pineappl.lz4",
+        eko.path(),
+        false,
+        false,
+        None,
+        None,
+    );
+    // the operators of both EKOs are stored in a different order, but must be combined by scale
+    eko::identity(
+        "data/LHCB_WP_7TEV.pineappl.lz4",
+        eko2.path(),
+        false,
+        true,
+        None,
+        None,
+    );
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            &format!("--eko2={}", eko2.path().to_str().unwrap()),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            eko.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success();
+}
+
+#[cfg(feature = "evolve")]
+#[test]
+fn identity_evol_basis() {
+    let eko = NamedTempFile::new("eko4.tar").unwrap();
+    let output = NamedTempFile::new("fktable9.lz4").unwrap();
+
+    eko::identity(
+        "data/LHCB_WP_7TEV.pineappl.lz4",
+        eko.path(),
+        false,
+        false,
+        None,
+        None,
+    );
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            "--basis=evol",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            eko.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success();
+}
+
+#[cfg(feature = "evolve")]
+#[test]
+fn identity_custom_basis() {
+    use assert_fs::fixture::FileWriteStr;
+
+    let eko = NamedTempFile::new("eko5.tar").unwrap();
+    let basis = NamedTempFile::new("basis.yaml").unwrap();
+    let output = NamedTempFile::new("fktable10.lz4").unwrap();
+
+    eko::identity(
+        "data/LHCB_WP_7TEV.pineappl.lz4",
+        eko.path(),
+        false,
+        false,
+        None,
+        None,
+    );
+
+    // replaces the down and up quark with their sum and difference
+    let rotation: String = (0..14)
+        .map(|row| {
+            let row: Vec<_> = (0..14)
+                .map(|col| match (row, col) {
+                    (8, 9) | (9, 8) => 1.0,
+                    (9, 9) => -1.0,
+                    _ if row == col => 1.0,
+                    _ => 0.0,
+                })
+                .collect();
+            format!("  - {row:?}\n")
+        })
+        .collect();
+    basis
+        .write_str(&format!(
+            "lumi_id_types: custom\n\
+             pids: [22, -6, -5, -4, -3, -2, -1, 21, 1001, 1002, 3, 4, 5, 6]\n\
+             pdg_mc_ids: [22, -6, -5, -4, -3, -2, -1, 21, 1, 2, 3, 4, 5, 6]\n\
+             rotation:\n{rotation}"
+        ))
+        .unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            &format!("--basis={}", basis.path().to_str().unwrap()),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            eko.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success();
+}
+
+#[cfg(feature = "evolve")]
+#[test]
+fn missing_basis_file() {
+    let output = NamedTempFile::new("fktable11.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "evolve",
+            "--basis=basis.yaml",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "LO",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            "Error: unable to open basis file `basis.yaml`
+
+Caused by:
+    No such file or directory (os error 2)
+",
+        );
+}
@@ -0,0 +1,269 @@
+use assert_cmd::Command;
+
+#[cfg(any(feature = "applgrid", feature = "fktable"))]
+use assert_fs::NamedTempFile;
+#[cfg(feature = "fktable")]
+use pineappl::fk_table::FkTable;
+#[cfg(feature = "fktable")]
+use pineappl::grid::Grid;
+
+#[cfg(feature = "fktable")]
+mod common;
+
+const HELP_STR: &str = "Converts `PineAPPL` grids to APPLgrid/FastKernel files
+
+Usage: pineappl export [OPTIONS] <INPUT> <OUTPUT> <PDFSET>
+
+Arguments:
+  <INPUT>   Path to the input grid
+  <OUTPUT>  Path to the converted grid
+  <PDFSET>  LHAPDF id or name of the PDF set to check the converted grid with
+
+Options:
+      --accuracy <ACCURACY>  Relative threshold between the table and the converted grid when comparison fails [default: 1e-10]
+      --digits-abs <ABS>     Set the number of fractional digits shown for absolute numbers [default: 7]
+      --digits-rel <REL>     Set the number of fractional digits shown for relative numbers [default: 7]
+  -h, --help                 Print help information
+";
+
+#[cfg(feature = "fktable")]
+fn round_trip(fk_table: &FkTable, name: &str) {
+    use pineappl::lumi::LumiCache;
+    use std::fs::File;
+
+    let input = NamedTempFile::new(format!("{name}.pineappl.lz4")).unwrap();
+    let output = NamedTempFile::new(format!("FK_{name}.dat")).unwrap();
+    let imported = NamedTempFile::new(format!("{name}-imported.pineappl.lz4")).unwrap();
+
+    fk_table
+        .write_lz4(File::create(input.path()).unwrap())
+        .unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "export",
+            input.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "import",
+            output.path().to_str().unwrap(),
+            imported.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout("file was converted, but we cannot check the conversion for this type\n");
+
+    let imported =
+        FkTable::try_from(Grid::read(File::open(imported.path()).unwrap()).unwrap()).unwrap();
+
+    // PDF with different values for all partons, which are given in the PDG basis
+    let mut xfx = |pid: i32, x: f64, _| x * (1.0 - x) * f64::from(pid + 30);
+    let mut alphas = |_| 0.0;
+
+    let results = fk_table
+        .convolute(
+            &mut LumiCache::with_one(2212, &mut xfx, &mut alphas),
+            &[],
+            &[],
+        )
+        .unwrap();
+    let imported_results = imported
+        .convolute(
+            &mut LumiCache::with_one(2212, &mut xfx, &mut alphas),
+            &[],
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(imported.bins(), fk_table.bins());
+    assert_eq!(imported.x_grid(), fk_table.x_grid());
+
+    for (result, imported_result) in results.iter().zip(&imported_results) {
+        assert!((imported_result / result - 1.0).abs() < 1e-14);
+    }
+}
+
+#[test]
+fn help() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&["export", "--help"])
+        .assert()
+        .success()
+        .stdout(HELP_STR);
+}
+
+#[test]
+#[cfg(feature = "fktable")]
+fn not_an_fk_table() {
+    let output = NamedTempFile::new("FK_LHCB_WP_7TEV.dat").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "export",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            "Error: 'data/LHCB_WP_7TEV.pineappl.lz4' is not an FK table
+
+Caused by:
+    multiple orders detected
+",
+        );
+}
+
+#[test]
+#[cfg(feature = "fktable")]
+fn export_dis_fktable() {
+    use pineappl::lumi_entry;
+
+    let fk_table = common::fk_table(
+        vec![
+            lumi_entry![100, 11, 1.0],
+            lumi_entry![21, 11, 1.0],
+            lumi_entry![235, 11, 1.0],
+        ],
+        "evol",
+        vec![0.0, 1.0, 3.0],
+        &[0.01, 0.1, 0.3, 0.6],
+    );
+
+    round_trip(&fk_table, "DIS");
+}
+
+#[test]
+#[cfg(feature = "fktable")]
+fn export_hadronic_fktable() {
+    use pineappl::lumi_entry;
+
+    // the FK table is in the PDG basis and must be rotated into the evolution basis
+    let fk_table = common::fk_table(
+        vec![
+            lumi_entry![2, 2, 1.0],
+            lumi_entry![21, -2, 1.0],
+            lumi_entry![1, 22, 1.0],
+        ],
+        "pdg_mc_ids",
+        vec![0.0, 1.0, 3.0],
+        &[0.01, 0.1, 0.3, 0.6],
+    );
+
+    round_trip(&fk_table, "HADRONIC");
+}
+
+#[test]
+#[cfg(feature = "fktable")]
+fn export_different_fktable() {
+    use pineappl::lumi_entry;
+    use std::fs::File;
+
+    let input = NamedTempFile::new("DIFFERENT.pineappl.lz4").unwrap();
+    let output = NamedTempFile::new("FK_DIFFERENT.dat").unwrap();
+
+    common::fk_table(
+        vec![lumi_entry![100, 11, 1.0]],
+        "evol",
+        vec![0.0, 1.0, 3.0],
+        &[0.01, 0.1, 0.3, 0.6],
+    )
+    .write_lz4(File::create(input.path()).unwrap())
+    .unwrap();
+
+    // a negative accuracy fails every comparison
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "export",
+            "--accuracy=-1",
+            input.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: grids are different\n");
+
+    // neither the FK table nor the temporary file must be left behind in the directory
+    assert_eq!(
+        output.path().parent().unwrap().read_dir().unwrap().count(),
+        0
+    );
+}
+
+#[test]
+#[cfg(feature = "fktable")]
+fn export_existing_output() {
+    use assert_fs::fixture::FileWriteStr;
+
+    let output = NamedTempFile::new("FK_EXISTING.dat").unwrap();
+
+    output.write_str("existing").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "export",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(format!(
+            "Error: unable to write '{}', because it already exists\n",
+            output.path().display()
+        ));
+
+    assert_eq!(std::fs::read_to_string(output.path()).unwrap(), "existing");
+}
+
+#[test]
+fn export_fastnlo() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "export",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "LHCB_WP_7TEV.tab.gz",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: exporting to fastNLO tables is not supported\n");
+}
+
+#[test]
+#[cfg(feature = "applgrid")]
+fn export_applgrid() {
+    let output = NamedTempFile::new("LHCB_WP_7TEV.appl").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "export",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success();
+}
@@ -422,7 +422,9 @@ fn import_hadronic_fktable() {
     let mut xfx = |id, x, q2| pdf.xfx_q2(id, x, q2);
     let mut alphas = |_| 0.0;
     let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
-    let results = grid.convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+    let results = grid
+        .convolute(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])
+        .unwrap();
 
     let mut fk_table = FkTable::try_from(grid).unwrap();
     let table = fk_table.table();
@@ -549,14 +551,17 @@ fn import_hadronic_fktable() {
         ]
     );
 
-    assert_eq!(results, fk_table.convolute(&mut lumi_cache, &[], &[]));
+    assert_eq!(
+        results,
+        fk_table.convolute(&mut lumi_cache, &[], &[]).unwrap()
+    );
 
     fk_table.optimize(FkAssumptions::Nf6Ind);
     assert_eq!(fk_table.lumi(), lumi);
     assert_approx_eq!(
         f64,
         results[0],
-        fk_table.convolute(&mut lumi_cache, &[], &[])[0],
+        fk_table.convolute(&mut lumi_cache, &[], &[]).unwrap()[0],
         ulps = 4
     );
     fk_table.optimize(FkAssumptions::Nf6Sym);
@@ -564,7 +569,7 @@ fn import_hadronic_fktable() {
     assert_approx_eq!(
         f64,
         results[0],
-        fk_table.convolute(&mut lumi_cache, &[], &[])[0],
+        fk_table.convolute(&mut lumi_cache, &[], &[]).unwrap()[0],
         ulps = 4
     );
     fk_table.optimize(FkAssumptions::Nf5Ind);
@@ -572,21 +577,21 @@ fn import_hadronic_fktable() {
     assert_approx_eq!(
         f64,
         results[0],
-        fk_table.convolute(&mut lumi_cache, &[], &[])[0]
+        fk_table.convolute(&mut lumi_cache, &[], &[]).unwrap()[0]
     );
     fk_table.optimize(FkAssumptions::Nf5Sym);
     assert_eq!(fk_table.lumi(), lumi);
     assert_approx_eq!(
         f64,
         results[0],
-        fk_table.convolute(&mut lumi_cache, &[], &[])[0]
+        fk_table.convolute(&mut lumi_cache, &[], &[]).unwrap()[0]
     );
     fk_table.optimize(FkAssumptions::Nf4Ind);
     assert_eq!(fk_table.lumi(), lumi);
     assert_approx_eq!(
         f64,
         results[0],
-        fk_table.convolute(&mut lumi_cache, &[], &[])[0]
+        fk_table.convolute(&mut lumi_cache, &[], &[]).unwrap()[0]
     );
 
     fk_table.optimize(FkAssumptions::Nf4Sym);
@@ -1,19 +1,23 @@
 use assert_cmd::Command;
 
+mod common;
+
 const HELP_STR: &str = "Shows information about the grid
 
-Usage: pineappl info <--ew|--get <key>|--keys|--qcd|--show> <INPUT>
+Usage: pineappl info [OPTIONS] <--check-fk-assumptions <PDFSET>|--ew|--get <key>|--keys|--qcd|--show> <INPUT>
 
 Arguments:
   <INPUT>  Path to the input grid
 
 Options:
-      --ew         For each order print a list of the largest EW order
-      --get <key>  Gets an internal key-value pair
-      --keys       Show all keys stored in the grid
-      --qcd        For each order print a list of the largest QCD order
-      --show       Shows all key-value pairs stored in the grid
-  -h, --help       Print help information
+      --check-fk-assumptions <PDFSET>  Check that the PDF set satisfies the assumptions the FK table was optimized with, or the ones given with `--assumptions` for an unoptimized FK table, for which also the resulting bias of the predictions is shown
+      --assumptions <ASSUMPTIONS>      Assumptions checked by `--check-fk-assumptions` for an unoptimized FK table [possible values: Nf6Ind, Nf6Sym, Nf5Ind, Nf5Sym, Nf4Ind, Nf4Sym, Nf3Ind, Nf3Sym]
+      --ew                             For each order print a list of the largest EW order
+      --get <key>                      Gets an internal key-value pair
+      --keys                           Show all keys stored in the grid
+      --qcd                            For each order print a list of the largest QCD order
+      --show                           Shows all key-value pairs stored in the grid
+  -h, --help                           Print help information
 ";
 
 const KEYS_STR: &str = "arxiv
@@ -486,6 +490,26 @@ fn help() {
         .stdout(HELP_STR);
 }
 
+#[test]
+fn check_fk_assumptions_not_an_fk_table() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "info",
+            "--check-fk-assumptions=NNPDF31_nlo_as_0118_luxqed",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            "Error: 'data/LHCB_WP_7TEV.pineappl.lz4' is not an FK table
+
+Caused by:
+    multiple orders detected
+",
+        );
+}
+
 #[test]
 fn ew() {
     Command::cargo_bin("pineappl")
@@ -535,3 +559,132 @@ fn show() {
         .success()
         .stdout(SHOW_STR);
 }
+
+/// Writes an unoptimized FK table to `path`, whose channels contain all quarks up to the bottom
+/// quark, and returns it.
+fn unoptimized_fk_table(path: &std::path::Path) -> pineappl::fk_table::FkTable {
+    use pineappl::lumi_entry;
+    use std::fs::File;
+
+    let fk_table = common::fk_table(
+        vec![
+            lumi_entry![2, -2, 1.0],
+            lumi_entry![3, 21, 1.0],
+            lumi_entry![-3, 21, 1.0],
+            lumi_entry![4, 21, 1.0],
+            lumi_entry![5, 21, 1.0],
+        ],
+        "pdg_mc_ids",
+        vec![0.0, 1.0, 3.0],
+        &[0.01, 0.1, 0.3, 0.6],
+    );
+    fk_table.write_lz4(File::create(path).unwrap()).unwrap();
+
+    fk_table
+}
+
+/// Checks the predictions shown by `pineappl info --check-fk-assumptions` for an unoptimized FK
+/// table against the ones of `FkTable::convolute_tabulated_with_one`, and returns the exit code.
+fn check_fk_assumptions(assumptions: &str) -> i32 {
+    use assert_fs::NamedTempFile;
+    use pineappl::fk_table::FkAssumptions;
+
+    let input = NamedTempFile::new(format!("fktable-{assumptions}.pineappl.lz4")).unwrap();
+    let fk_table = unoptimized_fk_table(input.path());
+    let output = Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "info",
+            "--check-fk-assumptions=NNPDF31_nlo_as_0118_luxqed",
+            &format!("--assumptions={assumptions}"),
+            input.path().to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let pdf = lhapdf::Pdf::with_setname_and_member("NNPDF31_nlo_as_0118_luxqed", 0).unwrap();
+    let assumptions: FkAssumptions = assumptions.parse().unwrap();
+    let pids = [-5, -4, -3, -2, -1, 21, 1, 2, 3, 4, 5];
+    let x_grid = fk_table.x_grid();
+    let mut xfx = |pid, x, q2| pdf.xfx_q2(pid, x, q2);
+    let convolute = |xfx: &mut dyn FnMut(i32, f64, f64) -> f64| {
+        let xfx = ndarray::Array2::from_shape_fn((pids.len(), x_grid.len()), |(pid, x)| {
+            xfx(pids[pid], x_grid[x], fk_table.muf2())
+        });
+
+        fk_table
+            .convolute_tabulated_with_one(2212, &pids, xfx.view(), &[], &[])
+            .unwrap()
+    };
+    let results = convolute(&mut xfx);
+    let assumed_results = convolute(&mut |pid, x, q2| assumptions.apply(&mut xfx, pid, x, q2));
+
+    // the predictions follow the table with the violated conditions after an empty line
+    let (conditions, predictions) = stdout.split_once("\n\n").unwrap();
+    let rows: Vec<Vec<f64>> = predictions
+        .lines()
+        .skip(2)
+        .map(|line| {
+            line.split_whitespace()
+                .skip(1)
+                .map(|number| number.parse().unwrap())
+                .collect()
+        })
+        .collect();
+
+    assert_eq!(
+        conditions.lines().count(),
+        2 + assumptions.conditions().len()
+    );
+    assert_eq!(rows.len(), 2);
+
+    for ((row, result), assumed) in rows.iter().zip(&results).zip(&assumed_results) {
+        let rel_diff = assumed / result - 1.0;
+
+        assert!((row[0] / result - 1.0).abs() < 1e-7);
+        assert!((row[1] / assumed - 1.0).abs() < 1e-7);
+        assert!((row[2] - rel_diff).abs() <= 1e-7 * rel_diff.abs());
+    }
+
+    output.status.code().unwrap()
+}
+
+#[test]
+fn check_fk_assumptions_satisfied() {
+    // bottom and top quarks vanish at the fitting scale of NNPDF3.1
+    assert_eq!(check_fk_assumptions("Nf4Ind"), 0);
+}
+
+#[test]
+fn check_fk_assumptions_violated() {
+    // NNPDF3.1 fits the charm PDF, which therefore doesn't vanish
+    assert_eq!(check_fk_assumptions("Nf3Sym"), 1);
+}
+
+#[test]
+fn check_fk_assumptions_optimized() {
+    use assert_fs::NamedTempFile;
+    use std::fs::File;
+
+    let input = NamedTempFile::new("fktable-optimized.pineappl.lz4").unwrap();
+    let mut fk_table = unoptimized_fk_table(input.path());
+
+    fk_table.optimize(pineappl::fk_table::FkAssumptions::Nf4Ind);
+    fk_table
+        .write_lz4(File::create(input.path()).unwrap())
+        .unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "info",
+            "--check-fk-assumptions=NNPDF31_nlo_as_0118_luxqed",
+            "--assumptions=Nf4Ind",
+            input.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: the FK table was already optimized with `Nf4Ind`, `--assumptions` requires an unoptimized FK table\n");
+}
@@ -6,11 +6,14 @@ Usage: pineappl [OPTIONS] <COMMAND>
 
 Commands:
   analyze    Perform various analyses with grids
+  asunc      Calculates strong coupling uncertainties
   channels   Shows the contribution for each partonic channel
+  chi2       Compares the predictions of a grid with experimental data using a chi-square
   convolute  Convolutes a PineAPPL grid with a PDF set
   delete     Deletes parts from a PineAPPL grid
   diff       Compares the numerical content of two grids with each other
   evolve     Evolve a grid with an evolution kernel operator to an FK table
+  export     Converts `PineAPPL` grids to APPLgrid/FastKernel files
   help       Display a manpage for selected subcommands
   import     Converts APPLgrid/fastNLO/FastKernel files to PineAPPL grids
   info       Shows information about the grid
@@ -22,6 +25,7 @@ Commands:
   pdfunc     Calculates PDF uncertainties
   plot       Creates a matplotlib script plotting the contents of the grid
   pull       Calculates the pull between two different PDF sets
+  rebin      Merges the bins of a grid such that they have the given edges
   remap      Modifies the bin dimensions, widths and normalizations
   set        Modifies the internal key-value storage
   subgrids   Print information about the internal subgrid types
@@ -1,5 +1,10 @@
 use assert_cmd::Command;
 use assert_fs::NamedTempFile;
+use pineappl::fk_table::FkTable;
+use pineappl::grid::Grid;
+use std::fs::File;
+
+mod common;
 
 const HELP_STR: &str = "Merges one or more PineAPPL grids together
 
@@ -14,6 +19,10 @@ Options:
           Scales all grids with the given factor
       --scale-by-order <ALPHAS,ALPHA,LOGXIR,LOGXIF,GLOBAL>
           Scales all grids with order-dependent factors
+      --fktable
+          Merge FK tables by appending their bins, checking that they are compatible
+      --interpolate
+          Interpolate FK tables with different x grids onto the x grid of the first one
   -h, --help
           Print help information
 ";
@@ -112,3 +121,114 @@ fn scale_by_order() {
         .success()
         .stdout(SCALE_BY_ORDER_STR);
 }
+
+#[test]
+fn fktable_not_an_fk_table() {
+    let output = NamedTempFile::new("merged.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "merge",
+            "--fktable",
+            output.path().to_str().unwrap(),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            "Error: 'data/LHCB_WP_7TEV.pineappl.lz4' is not an FK table
+
+Caused by:
+    multiple orders detected
+",
+        );
+}
+
+/// Returns an FK table with two bins starting at `bin_offset` and two channels, whose subgrids use
+/// `x_grid` for both initial states.
+fn fk_table(x_grid: &[f64], bin_offset: f64) -> FkTable {
+    use pineappl::lumi_entry;
+
+    common::fk_table(
+        vec![lumi_entry![2, -2, 1.0], lumi_entry![21, 21, 1.0]],
+        "pdg_mc_ids",
+        vec![bin_offset, bin_offset + 1.0, bin_offset + 2.0],
+        x_grid,
+    )
+}
+
+/// Convolutes `fk_table` with PDFs that are linear in `ln(x)`, which are interpolated exactly when
+/// FK tables are merged with `--interpolate`.
+fn convolute(fk_table: &FkTable) -> Vec<f64> {
+    let pids = [-2, 2, 21];
+    let x_grid = fk_table.x_grid();
+    let xfx = ndarray::Array2::from_shape_fn((pids.len(), x_grid.len()), |(pid, x)| {
+        x_grid[x] * f64::from(pids[pid] + 30) * (1.0 - 0.1 * x_grid[x].ln())
+    });
+
+    fk_table
+        .convolute_tabulated_with_one(2212, &pids, xfx.view(), &[], &[])
+        .unwrap()
+}
+
+fn merge_fk_tables(lhs: &FkTable, rhs: &FkTable, interpolate: bool) -> FkTable {
+    let input1 = NamedTempFile::new("fktable1.pineappl.lz4").unwrap();
+    let input2 = NamedTempFile::new("fktable2.pineappl.lz4").unwrap();
+    let output = NamedTempFile::new("merged.pineappl.lz4").unwrap();
+
+    lhs.write_lz4(File::create(input1.path()).unwrap()).unwrap();
+    rhs.write_lz4(File::create(input2.path()).unwrap()).unwrap();
+
+    let mut args = vec!["merge", "--fktable"];
+
+    if interpolate {
+        args.push("--interpolate");
+    }
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&args)
+        .args(&[
+            output.path().to_str().unwrap(),
+            input1.path().to_str().unwrap(),
+            input2.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    FkTable::try_from(Grid::read(File::open(output.path()).unwrap()).unwrap()).unwrap()
+}
+
+#[test]
+fn fktable() {
+    let x_grid = [0.01, 0.1, 0.3, 0.6];
+    let lhs = fk_table(&x_grid, 0.0);
+    let rhs = fk_table(&x_grid, 2.0);
+    let merged = merge_fk_tables(&lhs, &rhs, false);
+
+    assert_eq!(merged.bins(), 4);
+    assert_eq!(merged.bin_left(0), [0.0, 1.0, 2.0, 3.0]);
+    assert_eq!(merged.x_grid(), x_grid);
+    assert_eq!(
+        convolute(&merged),
+        [convolute(&lhs), convolute(&rhs)].concat()
+    );
+}
+
+#[test]
+fn fktable_interpolate() {
+    let lhs = fk_table(&[0.01, 0.03, 0.1, 0.3, 0.6], 0.0);
+    let rhs = fk_table(&[0.02, 0.2, 0.5], 2.0);
+    let merged = merge_fk_tables(&lhs, &rhs, true);
+    let reference = [convolute(&lhs), convolute(&rhs)].concat();
+
+    assert_eq!(merged.bins(), 4);
+    assert_eq!(merged.x_grid(), [0.01, 0.03, 0.1, 0.3, 0.6]);
+
+    for (result, reference) in convolute(&merged).iter().zip(&reference) {
+        assert!((result / reference - 1.0).abs() < 1e-12);
+    }
+}
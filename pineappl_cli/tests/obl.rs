@@ -2,7 +2,7 @@ use assert_cmd::Command;
 
 const HELP_STR: &str = "Shows information about orders (o), bins (b), or luminosities (l) of a grid
 
-Usage: pineappl obl <--orders|--orders-spaces|--orders-long|--bins|--lumis|--fktable> <INPUT>
+Usage: pineappl obl [OPTIONS] <--orders|--orders-spaces|--orders-long|--bins|--lumis|--fktable> <INPUT>
 
 Arguments:
   <INPUT>  Path to the input grid
@@ -12,6 +12,7 @@ Options:
       --orders-spaces  Show the orders of a grid, replacing zero powers with spaces
       --orders-long    Show the orders of a grid, including zero powers
   -b, --bins           Show the bins of a grid
+      --remapping      Show the bins as a remapping string, which can be used with `pineappl remap`
   -l, --lumis          Show the luminsities a grid
       --fktable        Check if input is an FK table
   -h, --help           Print help information
@@ -29,6 +30,9 @@ const BINS_STR: &str = "b   etal    norm
 7    4  4.5  0.5
 ";
 
+const BINS_REMAPPING_STR: &str = "2,2.25,2.5,2.75,3,3.25,3.5,4,4.5
+";
+
 const LUMIS_STR: &str = "l    entry        entry
 -+------------+------------
 0 1 × ( 2, -1) 1 × ( 4, -3)
@@ -49,15 +53,15 @@ const ORDERS_STR: &str = "o      order
 6 O(a^3 lf^1)
 ";
 
-const ORDERS_LONG_STR: &str = "o         order
--+---------------------
-0 O(as^0 a^2 lr^0 lf^0)
-1 O(as^1 a^2 lr^0 lf^0)
-2 O(as^1 a^2 lr^1 lf^0)
-3 O(as^1 a^2 lr^0 lf^1)
-4 O(as^0 a^3 lr^0 lf^0)
-5 O(as^0 a^3 lr^1 lf^0)
-6 O(as^0 a^3 lr^0 lf^1)
+const ORDERS_LONG_STR: &str = "o           order
+-+--------------------------
+0 O(as^0 a^2 lr^0 lf^0 la^0)
+1 O(as^1 a^2 lr^0 lf^0 la^0)
+2 O(as^1 a^2 lr^1 lf^0 la^0)
+3 O(as^1 a^2 lr^0 lf^1 la^0)
+4 O(as^0 a^3 lr^0 lf^0 la^0)
+5 O(as^0 a^3 lr^1 lf^0 la^0)
+6 O(as^0 a^3 lr^0 lf^1 la^0)
 ";
 
 const ORDERS_SPACES_STR: &str = "o         order
@@ -95,6 +99,21 @@ fn bins() {
         .stdout(BINS_STR);
 }
 
+#[test]
+fn bins_remapping() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "obl",
+            "--bins",
+            "--remapping",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout(BINS_REMAPPING_STR);
+}
+
 #[test]
 fn lumis() {
     Command::cargo_bin("pineappl")
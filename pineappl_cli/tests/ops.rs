@@ -12,7 +12,11 @@ Arguments:
 Options:
       --cc1                           Charge conjugate the first initial state
       --cc2                           Charge conjugate the second initial state
+      --rotate-basis <BASIS>          Rotate the luminosity function into the particle basis `pdg_mc_ids` or `evol`, or into the custom basis given by a YAML file
+      --reorder-bins <BIN1,BIN2,...>  Reorder the bins such that the i-th bin is the original bin with the i-th given index
+      --select-bins <BIN1,BIN2,...>   Keep only the given bins, in the given order, allowing repetitions
       --scale-by-bin <BIN1,BIN2,...>  Scale each bin with a different factor
+      --add-scale-logs <NF>           Add the renormalization and factorization scale logarithms, using the beta and splitting functions with NF active flavours
   -h, --help                          Print help information
 ";
 
@@ -29,6 +33,28 @@ const DEFAULT_STR: &str = "b   etal    disg/detal  scale uncertainty
 7    4  4.5 1.3772029e1    -3.46     2.85
 ";
 
+const REORDER_BINS_STR: &str = "b   etal    disg/detal  scale uncertainty
+     []        [pb]            [%]       
+-+----+----+-----------+--------+--------
+0    3 3.25 1.8093343e2    -3.74     2.95
+1 3.25  3.5 1.2291115e2    -3.71     2.98
+2  3.5    4 5.7851018e1    -3.63     2.97
+3    4  4.5 1.3772029e1    -3.46     2.85
+4    2 2.25 3.7527620e2    -3.77     2.71
+5 2.25  2.5 3.4521553e2    -3.79     2.80
+6  2.5 2.75 3.0001406e2    -3.78     2.86
+7 2.75    3 2.4257663e2    -3.77     2.92
+";
+
+const SELECT_BINS_STR: &str = "b   etal    disg/detal  scale uncertainty
+     []        [pb]            [%]       
+-+----+----+-----------+--------+--------
+0    4  4.5 1.3772029e1    -3.46     2.85
+1    2 2.25 3.7527620e2    -3.77     2.71
+2 2.25  2.5 3.4521553e2    -3.79     2.80
+3    2 2.25 3.7527620e2    -3.77     2.71
+";
+
 const SCALE_BY_BIN_STR: &str = "b   etal    disg/detal  scale uncertainty
      []        [pb]            [%]       
 -+----+----+-----------+--------+--------
@@ -42,6 +68,22 @@ const SCALE_BY_BIN_STR: &str = "b   etal    disg/detal  scale uncertainty
 7    4  4.5 1.1017623e2    -3.46     2.85
 ";
 
+// the regenerated logarithms reproduce the original ones only up to interpolation errors, and
+// therefore the scale uncertainties are shown without decimals and without the last bin, which
+// is too close to a rounding boundary
+#[cfg(feature = "dglap")]
+const ADD_SCALE_LOGS_STR: &str = "b   etal    disg/detal  scale uncertainty
+     []        [pb]            [%]       
+-+----+----+-----------+--------+--------
+0    2 2.25 3.7527620e2       -4        3
+1 2.25  2.5 3.4521553e2       -4        3
+2  2.5 2.75 3.0001406e2       -4        3
+3 2.75    3 2.4257663e2       -4        3
+4    3 3.25 1.8093343e2       -4        3
+5 3.25  3.5 1.2291115e2       -4        3
+6  3.5    4 5.7851018e1       -4        3
+";
+
 #[test]
 fn help() {
     Command::cargo_bin("pineappl")
@@ -138,3 +180,260 @@ fn scale_by_bin() {
         .success()
         .stdout(SCALE_BY_BIN_STR);
 }
+
+#[test]
+fn reorder_bins() {
+    let output = NamedTempFile::new("reorder_bins.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "ops",
+            "--reorder-bins=4-7,0-3",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(REORDER_BINS_STR);
+}
+
+#[test]
+fn select_bins() {
+    let output = NamedTempFile::new("select_bins.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "ops",
+            "--select-bins=7,0-1,0",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(SELECT_BINS_STR);
+}
+
+#[cfg(feature = "dglap")]
+#[test]
+fn add_scale_logs() {
+    use pineappl::empty_subgrid::EmptySubgridV1;
+    use pineappl::grid::Grid;
+    use std::fs::File;
+
+    let input = NamedTempFile::new("no_scale_logs.pineappl.lz4").unwrap();
+    let output = NamedTempFile::new("add_scale_logs.pineappl.lz4").unwrap();
+
+    // remove the QCD scale logarithms, which `--add-scale-logs` must regenerate
+    let mut grid = Grid::read(File::open("data/LHCB_WP_7TEV.pineappl.lz4").unwrap()).unwrap();
+    let orders: Vec<_> = grid
+        .orders()
+        .iter()
+        .enumerate()
+        .filter(|(_, order)| order.alphas > 0 && (order.logxir > 0 || order.logxif > 0))
+        .map(|(index, _)| index)
+        .collect();
+
+    assert!(!orders.is_empty());
+
+    for order in orders {
+        for bin in 0..grid.bin_info().bins() {
+            for lumi in 0..grid.lumi().len() {
+                grid.set_subgrid(order, bin, lumi, EmptySubgridV1::default().into());
+            }
+        }
+    }
+
+    grid.write_lz4(File::create(input.path()).unwrap()).unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "ops",
+            "--add-scale-logs=5",
+            input.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    let convolute = |grid: &str| {
+        Command::cargo_bin("pineappl")
+            .unwrap()
+            .args(&[
+                "--silence-lhapdf",
+                "convolute",
+                "--bins=0-6",
+                "--digits-rel=0",
+                grid,
+                "NNPDF31_nlo_as_0118_luxqed",
+            ])
+            .assert()
+            .success()
+    };
+
+    // removing the logarithms must change the scale uncertainties ...
+    assert_ne!(
+        convolute(input.path().to_str().unwrap())
+            .get_output()
+            .stdout,
+        ADD_SCALE_LOGS_STR.as_bytes()
+    );
+
+    // ... and adding them again must restore them
+    convolute(output.path().to_str().unwrap()).stdout(ADD_SCALE_LOGS_STR);
+
+    // adding the logarithms a second time isn't possible
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "ops",
+            "--add-scale-logs=5",
+            output.path().to_str().unwrap(),
+            input.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn rotate_basis() {
+    let output = NamedTempFile::new("evol.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "ops",
+            "--rotate-basis=evol",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn rotate_basis_custom() {
+    use assert_fs::fixture::FileWriteStr;
+
+    let basis = NamedTempFile::new("custom_basis.yaml").unwrap();
+    let output = NamedTempFile::new("custom_basis.pineappl.lz4").unwrap();
+
+    // replaces the down and up quark with their sum and difference
+    let rotation: String = (0..14)
+        .map(|row| {
+            let row: Vec<_> = (0..14)
+                .map(|col| match (row, col) {
+                    (8, 9) | (9, 8) => 1.0,
+                    (9, 9) => -1.0,
+                    _ if row == col => 1.0,
+                    _ => 0.0,
+                })
+                .collect();
+            format!("  - {row:?}\n")
+        })
+        .collect();
+    basis
+        .write_str(&format!(
+            "lumi_id_types: custom\n\
+             pids: [22, -6, -5, -4, -3, -2, -1, 21, 1001, 1002, 3, 4, 5, 6]\n\
+             pdg_mc_ids: [22, -6, -5, -4, -3, -2, -1, 21, 1, 2, 3, 4, 5, 6]\n\
+             rotation:\n{rotation}"
+        ))
+        .unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "ops",
+            &format!("--rotate-basis={}", basis.path().to_str().unwrap()),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "convolute",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: the particle basis `custom` is not supported\n");
+}
+
+#[test]
+fn cc1_unknown_basis() {
+    let input = NamedTempFile::new("custom.pineappl.lz4").unwrap();
+    let output = NamedTempFile::new("cc1_custom.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "set",
+            "--entry",
+            "lumi_id_types",
+            "custom",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            input.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "ops",
+            "--cc1",
+            input.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: can not charge conjugate particles of the basis `custom`\n");
+}
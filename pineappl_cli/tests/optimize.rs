@@ -1,6 +1,8 @@
 use assert_cmd::Command;
 use assert_fs::NamedTempFile;
 
+mod common;
+
 const HELP_STR: &str = "Optimizes the internal data structure to minimize memory usage
 
 Usage: pineappl optimize [OPTIONS] <INPUT> <OUTPUT>
@@ -10,7 +12,8 @@ Arguments:
   <OUTPUT>  Path to the optimized PineAPPL file
 
 Options:
-      --fk-table <ASSUMPTIONS>  [possible values: Nf6Ind, Nf6Sym, Nf5Ind, Nf5Sym, Nf4Ind, Nf4Sym, Nf3Ind, Nf3Sym]
+      --fk-table <ASSUMPTIONS>  Optimize an FK table with the given assumptions, or with the most restrictive assumptions satisfied by the PDF sets given with `--pdfsets` if the value is `auto` [possible values: auto, Nf6Ind, Nf6Sym, Nf5Ind, Nf5Sym, Nf4Ind, Nf4Sym, Nf3Ind, Nf3Sym]
+      --pdfsets <PDFSETS>       LHAPDF ids or names of the PDF sets used to detect the assumptions of `--fk-table auto`
   -h, --help                    Print help information
 ";
 
@@ -40,3 +43,134 @@ fn default() {
         .success()
         .stdout("");
 }
+
+#[test]
+fn fk_table_auto_without_pdfsets() {
+    let output = NamedTempFile::new("optimized-auto.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "optimize",
+            "--fk-table=auto",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            "error: The following required arguments were not provided:
+  --pdfsets <PDFSETS>
+
+Usage: pineappl optimize --fk-table <ASSUMPTIONS> --pdfsets <PDFSETS> <INPUT> <OUTPUT>
+
+For more information try '--help'
+",
+        );
+}
+
+#[test]
+fn pdfsets_without_fk_table() {
+    let output = NamedTempFile::new("optimized-pdfsets.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "optimize",
+            "--pdfsets=NNPDF31_nlo_as_0118_luxqed",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            "error: The following required arguments were not provided:
+  --fk-table <ASSUMPTIONS>
+
+Usage: pineappl optimize --fk-table <ASSUMPTIONS> --pdfsets <PDFSETS> <INPUT> <OUTPUT>
+
+For more information try '--help'
+",
+        );
+}
+
+#[test]
+fn pdfsets_without_fk_table_auto() {
+    let output = NamedTempFile::new("optimized-pdfsets.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "optimize",
+            "--fk-table=Nf4Sym",
+            "--pdfsets=NNPDF31_nlo_as_0118_luxqed",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: `--pdfsets` can only be used together with `--fk-table auto`\n");
+}
+
+#[test]
+fn fk_table_auto() {
+    use pineappl::fk_table::FkTable;
+    use pineappl::grid::Grid;
+    use pineappl::lumi_entry;
+    use std::fs::{self, File};
+
+    let input = NamedTempFile::new("fktable-evol.pineappl.lz4").unwrap();
+    let output = NamedTempFile::new("optimized-auto.pineappl.lz4").unwrap();
+    // an FK table in the evolution basis with a channel for each combination of the gluon with the
+    // singlet, the valence distribution and the non-singlet distributions of the heavy quarks
+    let mut fk_table = common::fk_table(
+        [100, 115, 124, 135, 200, 215, 224, 235]
+            .into_iter()
+            .map(|pid| lumi_entry![pid, 21, 1.0])
+            .collect(),
+        "evol",
+        vec![0.0, 1.0],
+        &[0.01, 0.1, 0.3, 0.6],
+    );
+    fk_table
+        .write_lz4(File::create(input.path()).unwrap())
+        .unwrap();
+
+    let pdf = lhapdf::Pdf::with_setname_and_member("NNPDF31_nlo_as_0118_luxqed", 0).unwrap();
+    let mut xfx = |id, x, q2| pdf.xfx_q2(id, x, q2);
+    let assumptions = fk_table.detect_assumptions(&mut [&mut xfx]);
+
+    // bottom and top quarks vanish at the fitting scale of NNPDF3.1
+    assert!(matches!(
+        assumptions.to_string().as_str(),
+        "Nf4Ind" | "Nf4Sym" | "Nf3Ind" | "Nf3Sym"
+    ));
+
+    fk_table.optimize(assumptions);
+
+    let assert = Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "optimize",
+            "--fk-table=auto",
+            "--pdfsets=NNPDF31_nlo_as_0118_luxqed",
+            input.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    assert.stdout(format!(
+        "optimized with {assumptions}: 8 -> {} channels, {} -> {} bytes\n",
+        fk_table.lumi().len(),
+        fs::metadata(input.path()).unwrap().len(),
+        fs::metadata(output.path()).unwrap().len()
+    ));
+
+    let optimized =
+        FkTable::try_from(Grid::read(File::open(output.path()).unwrap()).unwrap()).unwrap();
+
+    assert_eq!(optimized.assumptions(), Some(assumptions));
+    assert_eq!(optimized.lumi(), fk_table.lumi());
+}
@@ -1,4 +1,5 @@
 use assert_cmd::Command;
+use assert_fs::NamedTempFile;
 use std::num::NonZeroUsize;
 use std::thread;
 
@@ -13,7 +14,12 @@ Arguments:
 Options:
       --cl <CL>            Confidence level in per cent [default: 68.26894921370858]
   -i, --integrated         Show integrated numbers (without bin widths) instead of differential ones
+      --observables        Show the observables defined in the grid instead of its bins
   -o, --orders <ORDERS>    Select orders manually
+  -s, --scales <SCALES>    Additionally show the scale uncertainty using the given prescription: 1, 3, 7, 9, 3-ren, 3-fac or 31-corr, optionally followed by @FACTOR, or a list of xir:xif pairs [default: 1]
+      --covariance         Print the bin-by-bin covariance matrix of the PDF uncertainty instead of the table, readable with `numpy.loadtxt`
+      --scale-covariance   Print the bin-by-bin covariance matrix of the scale uncertainty instead of the table. If given together with `--covariance`, both matrices are added
+      --correlation        Print the correlation matrix instead of the covariance matrix, by default for the PDF uncertainty. Bins without uncertainty are uncorrelated with all other bins
       --threads <THREADS>  Number of threads to utilize [default: {}]
       --digits-abs <ABS>   Set the number of fractional digits shown for absolute numbers [default: 7]
       --digits-rel <REL>   Set the number of fractional digits shown for relative numbers [default: 2]
@@ -85,6 +91,37 @@ const ORDERS_A2_AS1A2_STR: &str = "b   etal    disg/detal  PDF uncertainty
 7    4  4.5 1.3875186e1   -2.77    2.77
 ";
 
+const SCALE_COVARIANCE_STR: &str = "3.48e1 3.26e1 2.89e1 2.37e1 1.81e1 1.27e1 6.35e0 1.73e0
+3.26e1 3.06e1 2.71e1 2.22e1 1.70e1 1.19e1 5.95e0 1.62e0
+2.89e1 2.71e1 2.40e1 1.97e1 1.50e1 1.06e1 5.27e0 1.43e0
+2.37e1 2.22e1 1.97e1 1.61e1 1.23e1 8.66e0 4.32e0 1.17e0
+1.81e1 1.70e1 1.50e1 1.23e1 9.42e0 6.62e0 3.30e0 8.98e-1
+1.27e1 1.19e1 1.06e1 8.66e0 6.62e0 4.66e0 2.32e0 6.32e-1
+6.35e0 5.95e0 5.27e0 4.32e0 3.30e0 2.32e0 1.16e0 3.15e-1
+1.73e0 1.62e0 1.43e0 1.17e0 8.98e-1 6.32e-1 3.15e-1 8.57e-2
+";
+
+const SCALE_CORRELATION_STR: &str =
+    "1.000e0 9.999e-1 9.997e-1 9.994e-1 9.989e-1 9.978e-1 9.945e-1 9.761e-1
+9.999e-1 1.000e0 9.999e-1 9.998e-1 9.993e-1 9.984e-1 9.951e-1 9.769e-1
+9.997e-1 9.999e-1 1.000e0 9.999e-1 9.997e-1 9.989e-1 9.960e-1 9.784e-1
+9.994e-1 9.998e-1 9.999e-1 1.000e0 9.999e-1 9.992e-1 9.966e-1 9.795e-1
+9.989e-1 9.993e-1 9.997e-1 9.999e-1 1.000e0 9.997e-1 9.978e-1 9.824e-1
+9.978e-1 9.984e-1 9.989e-1 9.992e-1 9.997e-1 1.000e0 9.990e-1 9.862e-1
+9.945e-1 9.951e-1 9.960e-1 9.966e-1 9.978e-1 9.990e-1 1.000e0 9.926e-1
+9.761e-1 9.769e-1 9.784e-1 9.795e-1 9.824e-1 9.862e-1 9.926e-1 1.000e0
+";
+
+const CORRELATION_WITHOUT_UNCERTAINTY_STR: &str = "1.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0
+0.0000000e0 1.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0
+0.0000000e0 0.0000000e0 1.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0
+0.0000000e0 0.0000000e0 0.0000000e0 1.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0
+0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 1.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0
+0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 1.0000000e0 0.0000000e0 0.0000000e0
+0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 1.0000000e0 0.0000000e0
+0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 0.0000000e0 1.0000000e0
+";
+
 #[test]
 fn help() {
     Command::cargo_bin("pineappl")
@@ -184,3 +221,131 @@ fn orders_a2_as1a2() {
         .success()
         .stdout(ORDERS_A2_AS1A2_STR);
 }
+
+#[test]
+fn scale_covariance_without_scales() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "pdfunc",
+            "--scale-covariance",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            "Error: the scale covariance needs more than one scale variation, use `--scales`\n",
+        );
+}
+
+#[test]
+fn covariance() {
+    let output = NamedTempFile::new("pdfunc-bin0.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "delete",
+            "--bins=1-7",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    // the variance of the remaining bin is the square of its PDF uncertainty in `DEFAULT_STR`
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "pdfunc",
+            "--covariance",
+            "--threads=1",
+            "--digits-abs=1",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout("1.8e1\n");
+
+    // with `--scale-covariance` the variance of the scale uncertainty is added
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "pdfunc",
+            "--covariance",
+            "--scale-covariance",
+            "--scales=3-ren",
+            "--threads=1",
+            "--digits-abs=1",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout("5.3e1\n");
+}
+
+#[test]
+fn scale_covariance() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "pdfunc",
+            "--scale-covariance",
+            "--scales=3-ren",
+            "--threads=1",
+            "--digits-abs=2",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(SCALE_COVARIANCE_STR);
+}
+
+#[test]
+fn scale_correlation() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "pdfunc",
+            "--scale-covariance",
+            "--correlation",
+            "--scales=7",
+            "--threads=1",
+            "--digits-abs=3",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(SCALE_CORRELATION_STR);
+}
+
+#[test]
+fn correlation_without_uncertainty() {
+    // without varying the scales there's no uncertainty, and the bins must be uncorrelated
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "--silence-lhapdf",
+            "pdfunc",
+            "--scale-covariance",
+            "--correlation",
+            "--scales=3@1",
+            "--threads=1",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(CORRELATION_WITHOUT_UNCERTAINTY_STR);
+}
@@ -11,9 +11,10 @@ Arguments:
   <PDFSETS>...  LHAPDF id(s) or name of the PDF set(s)
 
 Options:
-  -s, --scales <SCALES>                Set the number of scale variations [default: 7] [possible values: 1, 3, 7, 9]
+  -s, --scales <SCALES>                Set the scale-variation prescription: 1, 3, 7, 9, 3-ren, 3-fac or 31-corr, optionally followed by @FACTOR, or a list of xir:xif pairs [default: 7]
       --subgrid-pull <ORDER,BIN,LUMI>  Show the pull for a specific grid three-dimensionally
       --asymmetry                      Plot the asymmetry
+      --observables                    Plot the observables defined in the grid instead of its bins
       --threads <THREADS>              Number of threads to utilize [default: {}]
   -h, --help                           Print help information
 ";
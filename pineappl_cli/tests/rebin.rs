@@ -0,0 +1,205 @@
+use assert_cmd::Command;
+use assert_fs::{fixture::FileWriteStr, NamedTempFile};
+
+const HELP_STR: &str = "Merges the bins of a grid such that they have the given edges
+
+Usage: pineappl rebin <--edges <EDGE1,EDGE2,...>|--hepdata <YAML>> <INPUT> <OUTPUT>
+
+Arguments:
+  <INPUT>   Path to the input grid
+  <OUTPUT>  Path to the modified `PineAPPL` file
+
+Options:
+      --edges <EDGE1,EDGE2,...>  New bin edges of the last dimension
+      --hepdata <YAML>           Read the new bin edges from the last independent variable of a `HEPData` table, which must be the same for all slices of a multi-dimensional table
+  -h, --help                     Print help information
+";
+
+const EDGES_STR: &str = "b  etal   norm
+-+---+---+----
+0   2 2.5  0.5
+1 2.5   3  0.5
+2   3 3.5  0.5
+3 3.5 4.5    1
+";
+
+const HEPDATA_STR: &str = "b   etal   norm
+-+----+---+----
+0 2.25 2.5 0.25
+1  2.5   3  0.5
+2    3   4    1
+";
+
+const HEPDATA_TABLE: &str = "independent_variables:
+- header: {name: '$\\eta_l$'}
+  values:
+  - {low: 2.25, high: 2.5}
+  - {low: 2.5, high: 3.0}
+  - {low: 3.0, high: 4.0}
+dependent_variables:
+- header: {name: '$d\\sigma/d\\eta_l$', units: 'pb'}
+  values:
+  - value: 1.0
+  - value: 2.0
+  - value: 3.0
+";
+
+const HEPDATA_SLICES_TABLE: &str = "independent_variables:
+- header: {name: '$m_{ll}$'}
+  values:
+  - {low: 60.0, high: 120.0}
+  - {low: 60.0, high: 120.0}
+  - {low: 60.0, high: 120.0}
+  - {low: 120.0, high: 200.0}
+  - {low: 120.0, high: 200.0}
+  - {low: 120.0, high: 200.0}
+- header: {name: '$\\eta_l$'}
+  values:
+  - {low: 2.25, high: 2.5}
+  - {low: 2.5, high: 3.0}
+  - {low: 3.0, high: 4.0}
+  - {low: 2.25, high: 2.5}
+  - {low: 2.5, high: SECOND_SLICE_EDGE}
+  - {low: SECOND_SLICE_EDGE, high: 4.0}
+dependent_variables:
+- header: {name: '$d\\sigma/d\\eta_l$', units: 'pb'}
+  values:
+  - value: 1.0
+  - value: 2.0
+  - value: 3.0
+  - value: 4.0
+  - value: 5.0
+  - value: 6.0
+";
+
+#[test]
+fn help() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&["rebin", "--help"])
+        .assert()
+        .success()
+        .stdout(HELP_STR);
+}
+
+#[test]
+fn edges() {
+    let output = NamedTempFile::new("rebinned.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "rebin",
+            "--edges=2,2.5,3,3.5,4.5",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&["obl", "--bins", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(EDGES_STR);
+}
+
+#[test]
+fn edges_misaligned() {
+    let output = NamedTempFile::new("misaligned.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "rebin",
+            "--edges=2,2.4,4.5",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: the bin edges [2.0, 2.4, 4.5] do not align with the bins of the grid\n");
+}
+
+#[test]
+fn hepdata() {
+    let table = NamedTempFile::new("table.yaml").unwrap();
+    table.write_str(HEPDATA_TABLE).unwrap();
+    let output = NamedTempFile::new("rebinned.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "rebin",
+            "--hepdata",
+            table.path().to_str().unwrap(),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&["obl", "--bins", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(HEPDATA_STR);
+}
+
+#[test]
+fn hepdata_slices() {
+    let table = NamedTempFile::new("slices.yaml").unwrap();
+    table
+        .write_str(&HEPDATA_SLICES_TABLE.replace("SECOND_SLICE_EDGE", "3.0"))
+        .unwrap();
+    let output = NamedTempFile::new("rebinned_slices.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "rebin",
+            "--hepdata",
+            table.path().to_str().unwrap(),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&["obl", "--bins", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(HEPDATA_STR);
+}
+
+#[test]
+fn hepdata_slices_different_edges() {
+    let table = NamedTempFile::new("different_slices.yaml").unwrap();
+    table
+        .write_str(&HEPDATA_SLICES_TABLE.replace("SECOND_SLICE_EDGE", "3.5"))
+        .unwrap();
+    let output = NamedTempFile::new("different_slices.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "rebin",
+            "--hepdata",
+            table.path().to_str().unwrap(),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            "Error: the bin edges [2.25, 2.5, 3.5, 4.0] of the HEPData slice [(120.0, 200.0)] \
+             differ from the edges [2.25, 2.5, 3.0, 4.0] of the first slice\n",
+        );
+}
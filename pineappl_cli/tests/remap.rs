@@ -1,16 +1,18 @@
 use assert_cmd::Command;
-use assert_fs::NamedTempFile;
+use assert_fs::{fixture::FileWriteStr, NamedTempFile};
 
 const HELP_STR: &str = "Modifies the bin dimensions, widths and normalizations
 
-Usage: pineappl remap [OPTIONS] <INPUT> <OUTPUT> <REMAPPING>
+Usage: pineappl remap [OPTIONS] <REMAPPING|--from-hepdata <YAML>> <INPUT> <OUTPUT>
 
 Arguments:
   <INPUT>      Path to the input grid
   <OUTPUT>     Path of the modified PineAPPL file
-  <REMAPPING>  Remapping string. See <https://nnpdf.github.io/pineappl/docs/cli-reference.html> for full reference
+  [REMAPPING]  Remapping string. See <https://nnpdf.github.io/pineappl/docs/cli-reference.html> for full reference
 
 Options:
+      --from-hepdata <YAML>
+          Read the bins, labels and units from a `HEPData` table instead of a remapping string
       --ignore-obs-norm <OBS1,OBS2,...>
           Ignore the given observables for differential normalization
       --norm <NORM>
@@ -32,6 +34,66 @@ const DEFAULT_STR: &str = "b etal  x2  x3  disg/detal  scale uncertainty
 7  1  2 2 4 4 5 1.3772029e0    -3.46     2.85
 ";
 
+const FROM_HEPDATA_STR: &str = "b  yll   $\\eta_l$  norm
+-+--+---+----+----+----
+0 60  90    2  2.5   15
+1 60  90  2.5    3   15
+2 60  90    3  3.5   15
+3 60  90  3.5  4.5   30
+4 90 120    2  2.5   15
+5 90 120  2.5    3   15
+6 90 120    3  3.5   15
+7 90 120  3.5  4.5   30
+";
+
+const FROM_HEPDATA_TABLE: &str = "independent_variables:
+- header: {name: 'yll', units: 'GeV'}
+  values:
+  - {low: 60, high: 90}
+  - {low: 60, high: 90}
+  - {low: 60, high: 90}
+  - {low: 60, high: 90}
+  - {low: 90, high: 120}
+  - {low: 90, high: 120}
+  - {low: 90, high: 120}
+  - {low: 90, high: 120}
+- header: {name: '$\\eta_l$'}
+  values:
+  - {low: 2.0, high: 2.5}
+  - {low: 2.5, high: 3.0}
+  - {low: 3.0, high: 3.5}
+  - {low: 3.5, high: 4.5}
+  - {low: 2.0, high: 2.5}
+  - {low: 2.5, high: 3.0}
+  - {low: 3.0, high: 3.5}
+  - {low: 3.5, high: 4.5}
+dependent_variables:
+- header: {name: 'd$\\sigma$/d$\\eta_l$', units: 'pb'}
+  values:
+  - value: 1.0
+  - value: 2.0
+  - value: 3.0
+  - value: 4.0
+  - value: 5.0
+  - value: 6.0
+  - value: 7.0
+  - value: 8.0
+";
+
+const FROM_HEPDATA_MISMATCH_TABLE: &str = "independent_variables:
+- header: {name: 'etal'}
+  values:
+  - {low: 2.0, high: 2.25}
+  - {low: 2.25, high: 2.5}
+  - {low: 2.5, high: 2.75}
+  - {low: 2.75, high: 3.0}
+  - {low: 3.0, high: 3.25}
+  - {low: 3.25, high: 3.5}
+  - {low: 3.5, high: 4.0}
+  - {low: 4.0, high: 5.0}
+dependent_variables: []
+";
+
 #[test]
 fn help() {
     Command::cargo_bin("pineappl")
@@ -72,3 +134,137 @@ fn default() {
         .success()
         .stdout(DEFAULT_STR);
 }
+
+#[test]
+fn from_hepdata() {
+    let table = NamedTempFile::new("table.yaml").unwrap();
+    table.write_str(FROM_HEPDATA_TABLE).unwrap();
+    let numbered = NamedTempFile::new("numbered.pineappl.lz4").unwrap();
+    let output = NamedTempFile::new("remapped.pineappl.lz4").unwrap();
+
+    // the bins of the table have two dimensions, which is only allowed for numbered bins
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "remap",
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            numbered.path().to_str().unwrap(),
+            "0,1,2,3,4,5,6,7,8",
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "remap",
+            "--from-hepdata",
+            table.path().to_str().unwrap(),
+            numbered.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&["obl", "--bins", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(FROM_HEPDATA_STR);
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&["info", "--get=x1_unit", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("GeV\n");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&["info", "--get=y_label_tex", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("d$\\sigma$/d$\\eta_l$\n");
+}
+
+#[test]
+fn from_hepdata_mismatch() {
+    let table = NamedTempFile::new("table.yaml").unwrap();
+    table.write_str(FROM_HEPDATA_MISMATCH_TABLE).unwrap();
+    let output = NamedTempFile::new("remapped.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "remap",
+            "--from-hepdata",
+            table.path().to_str().unwrap(),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: the limits [(4.0, 5.0)] of bin 7 of the HEPData table differ from the limits [(4.0, 4.5)] of the grid\n");
+
+    table
+        .write_str(
+            "independent_variables:
+- header: {name: 'etal'}
+  values:
+  - {low: 2.0, high: 4.5}
+dependent_variables: []
+",
+        )
+        .unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "remap",
+            "--from-hepdata",
+            table.path().to_str().unwrap(),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: the HEPData table has 1 bins, but the grid has 8\n");
+
+    // the limits of grids whose bins aren't numbered must match, including their dimensions
+    table
+        .write_str(&FROM_HEPDATA_MISMATCH_TABLE.replace(
+            "\ndependent_variables",
+            "
+- header: {name: 'ptl'}
+  values:
+  - {low: 0.0, high: 1.0}
+  - {low: 0.0, high: 1.0}
+  - {low: 0.0, high: 1.0}
+  - {low: 0.0, high: 1.0}
+  - {low: 0.0, high: 1.0}
+  - {low: 0.0, high: 1.0}
+  - {low: 0.0, high: 1.0}
+  - {low: 0.0, high: 1.0}
+dependent_variables",
+        ))
+        .unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(&[
+            "remap",
+            "--from-hepdata",
+            table.path().to_str().unwrap(),
+            "data/LHCB_WP_7TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            "Error: the HEPData table has 2 independent variables, but the bins of the grid have \
+             1 dimensions\n",
+        );
+}
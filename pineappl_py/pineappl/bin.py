@@ -18,3 +18,26 @@ class BinRemapper(PyWrapper):
 
     def __init__(self, normalizations, limits):
         self._raw = PyBinRemapper(np.array(normalizations), limits)
+
+    @classmethod
+    def from_str(cls, remapping, ignore_obs_norm=(), norm=1.0):
+        """
+        Create a remapper from a remapping string, using the same syntax as `pineappl remap`.
+
+        Parameters
+        ----------
+            remapping : str
+                remapping string, for instance `0,1,2;0,2,4`
+            ignore_obs_norm : sequence(int)
+                dimensions, starting from one, that are ignored for the normalization
+            norm : float
+                normalization factor in addition to the bin widths
+
+        Returns
+        -------
+            BinRemapper
+                remapper object
+        """
+        remapper = cls.__new__(cls)
+        remapper._raw = PyBinRemapper.from_str(remapping, list(ignore_obs_norm), norm)
+        return remapper
@@ -19,10 +19,12 @@ class Order(PyWrapper):
             power of :math:`\log(\xi_r)`
         logxif : int
             power of :math:`\log(\xi_f)`
+        logxia : int
+            power of :math:`\log(\xi_a)`
     """
 
-    def __init__(self, alphas, alpha, logxir, logxif):
-        self._raw = PyOrder(alphas, alpha, logxir, logxif)
+    def __init__(self, alphas, alpha, logxir, logxif, logxia=0):
+        self._raw = PyOrder(alphas, alpha, logxir, logxif, logxia)
 
     @staticmethod
     def create_mask(orders, max_as, max_al):
@@ -1,6 +1,7 @@
 use pineappl::bin::BinRemapper;
 
 use numpy::PyReadonlyArray1;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
 /// PyO3 wrapper to :rustdoc:`pineappl::bin::BinRemapper <bin/struct.BinRemapper.html>`
@@ -25,4 +26,36 @@ impl PyBinRemapper {
     pub fn new_f64(normalizations: PyReadonlyArray1<f64>, limits: Vec<(f64, f64)>) -> Self {
         Self::new(BinRemapper::new(normalizations.to_vec().unwrap(), limits).unwrap())
     }
+
+    /// Constructor from a remapping string, using the same syntax as `pineappl remap`.
+    ///
+    /// Parameters
+    /// ----------
+    ///     remapping : str
+    ///         remapping string, for instance `0,1,2;0,2,4`
+    ///     ignore_obs_norm : list(int)
+    ///         dimensions, starting from one, that are ignored for the normalization
+    ///     norm : float
+    ///         normalization factor in addition to the bin widths
+    ///
+    /// Returns
+    /// -------
+    ///     PyBinRemapper :
+    ///         remapper
+    #[staticmethod]
+    pub fn from_str(remapping: &str, ignore_obs_norm: Vec<usize>, norm: f64) -> PyResult<Self> {
+        BinRemapper::parse(remapping, &ignore_obs_norm, norm)
+            .map(Self::new)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+
+    /// Return the limits as a remapping string.
+    ///
+    /// Returns
+    /// -------
+    ///     str or None :
+    ///         remapping string or `None` if the bins can not be represented by it
+    pub fn to_remapping_string(&self) -> Option<String> {
+        self.bin_remapper.to_remapping_string()
+    }
 }
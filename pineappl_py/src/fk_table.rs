@@ -2,7 +2,8 @@ use pineappl::fk_table::{FkAssumptions, FkTable};
 use pineappl::grid::Grid;
 use pineappl::lumi::LumiCache;
 
-use numpy::{IntoPyArray, PyArray1, PyArray4};
+use numpy::{IntoPyArray, PyArray1, PyArray4, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
 use std::collections::HashMap;
@@ -163,6 +164,17 @@ impl PyFkTable {
         self.fk_table.muf2()
     }
 
+    /// Get reference (fitting) scales of the first and second initial state.
+    ///
+    /// Returns
+    /// -------
+    ///     tuple(float, float) :
+    ///         reference scales
+    pub fn muf2_per_initial_state(&self) -> (f64, f64) {
+        let [muf2_1, muf2_2] = self.fk_table.muf2_per_initial_state();
+        (muf2_1, muf2_2)
+    }
+
     /// Get (unique) interpolation grid.
     ///
     /// Returns
@@ -219,13 +231,149 @@ impl PyFkTable {
         pdg_id: i32,
         xfx: &PyAny,
         py: Python<'py>,
-    ) -> &'py PyArray1<f64> {
+    ) -> PyResult<&'py PyArray1<f64>> {
         let mut xfx = |id, x, q2| f64::extract(xfx.call1((id, x, q2)).unwrap()).unwrap();
         let mut alphas = |_| 1.0;
         let mut lumi_cache = LumiCache::with_one(pdg_id, &mut xfx, &mut alphas);
-        self.fk_table
+        Ok(self
+            .fk_table
+            .convolute(&mut lumi_cache, &[], &[])
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?
+            .into_pyarray(py))
+    }
+
+    /// Convolute grid with two pdfs.
+    ///
+    /// Parameters
+    /// ----------
+    ///     pdg_id1 : integer
+    ///         PDG Monte Carlo ID of the hadronic particle `xfx1` is the PDF for
+    ///     xfx1 : callable
+    ///         lhapdf like callable with arguments `pid, x, Q2` returning x*pdf for :math:`x`-grid
+    ///     pdg_id2 : integer
+    ///         PDG Monte Carlo ID of the hadronic particle `xfx2` is the PDF for
+    ///     xfx2 : callable
+    ///         lhapdf like callable with arguments `pid, x, Q2` returning x*pdf for :math:`x`-grid
+    ///
+    /// Returns
+    /// -------
+    ///     numpy.ndarray(float) :
+    ///         cross sections for all bins
+    pub fn convolute_with_two<'py>(
+        &self,
+        pdg_id1: i32,
+        xfx1: &PyAny,
+        pdg_id2: i32,
+        xfx2: &PyAny,
+        py: Python<'py>,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        let mut xfx1 = |id, x, q2| f64::extract(xfx1.call1((id, x, q2)).unwrap()).unwrap();
+        let mut xfx2 = |id, x, q2| f64::extract(xfx2.call1((id, x, q2)).unwrap()).unwrap();
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache =
+            LumiCache::with_two(pdg_id1, &mut xfx1, pdg_id2, &mut xfx2, &mut alphas);
+        Ok(self
+            .fk_table
             .convolute(&mut lumi_cache, &[], &[])
-            .into_pyarray(py)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?
+            .into_pyarray(py))
+    }
+
+    /// Convolute grid with a pdf tabulated at :meth:`muf2` and :meth:`x_grid`.
+    ///
+    /// In contrast to :meth:`convolute_with_one` no interpolation is performed.
+    ///
+    /// Parameters
+    /// ----------
+    ///     pdg_id : integer
+    ///         PDG Monte Carlo ID of the hadronic particle `xfx` is the PDF for
+    ///     pids : numpy.ndarray(int)
+    ///         PDG Monte Carlo IDs of the partons, one for each row of `xfx`
+    ///     xfx : numpy.ndarray(float, rank=2)
+    ///         x*pdf with indices: parton, x
+    ///     bin_indices : numpy.ndarray(int)
+    ///         A list with the indices of the corresponding bins that should be calculated. An
+    ///         empty list means that all bins should be calculated.
+    ///     lumi_mask : numpy.ndarray(bool)
+    ///         Mask for selecting specific luminosity channels. The value `True` means the
+    ///         corresponding channel is included. An empty list corresponds to all channels being
+    ///         enabled.
+    ///
+    /// Returns
+    /// -------
+    ///     numpy.ndarray(float) :
+    ///         cross sections for the selected bins
+    pub fn convolute_tabulated_with_one<'py>(
+        &self,
+        pdg_id: i32,
+        pids: PyReadonlyArray1<i32>,
+        xfx: PyReadonlyArray2<f64>,
+        bin_indices: PyReadonlyArray1<usize>,
+        lumi_mask: PyReadonlyArray1<bool>,
+        py: Python<'py>,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self
+            .fk_table
+            .convolute_tabulated_with_one(
+                pdg_id,
+                &pids.to_vec().unwrap(),
+                xfx.as_array(),
+                &bin_indices.to_vec().unwrap(),
+                &lumi_mask.to_vec().unwrap(),
+            )
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?
+            .into_pyarray(py))
+    }
+
+    /// Convolute grid with two pdfs tabulated at :meth:`muf2` and :meth:`x_grid`.
+    ///
+    /// The pdfs are used for the first and second initial state, respectively, and are never
+    /// charge conjugated.
+    ///
+    /// Parameters
+    /// ----------
+    ///     pids1 : numpy.ndarray(int)
+    ///         PDG Monte Carlo IDs of the partons, one for each row of `xfx1`
+    ///     xfx1 : numpy.ndarray(float, rank=2)
+    ///         x*pdf of the first initial state with indices: parton, x
+    ///     pids2 : numpy.ndarray(int)
+    ///         PDG Monte Carlo IDs of the partons, one for each row of `xfx2`
+    ///     xfx2 : numpy.ndarray(float, rank=2)
+    ///         x*pdf of the second initial state with indices: parton, x
+    ///     bin_indices : numpy.ndarray(int)
+    ///         A list with the indices of the corresponding bins that should be calculated. An
+    ///         empty list means that all bins should be calculated.
+    ///     lumi_mask : numpy.ndarray(bool)
+    ///         Mask for selecting specific luminosity channels. The value `True` means the
+    ///         corresponding channel is included. An empty list corresponds to all channels being
+    ///         enabled.
+    ///
+    /// Returns
+    /// -------
+    ///     numpy.ndarray(float) :
+    ///         cross sections for the selected bins
+    pub fn convolute_tabulated_with_two<'py>(
+        &self,
+        pids1: PyReadonlyArray1<i32>,
+        xfx1: PyReadonlyArray2<f64>,
+        pids2: PyReadonlyArray1<i32>,
+        xfx2: PyReadonlyArray2<f64>,
+        bin_indices: PyReadonlyArray1<usize>,
+        lumi_mask: PyReadonlyArray1<bool>,
+        py: Python<'py>,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self
+            .fk_table
+            .convolute_tabulated_with_two(
+                &pids1.to_vec().unwrap(),
+                xfx1.as_array(),
+                &pids2.to_vec().unwrap(),
+                xfx2.as_array(),
+                &bin_indices.to_vec().unwrap(),
+                &lumi_mask.to_vec().unwrap(),
+            )
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?
+            .into_pyarray(py))
     }
 
     /// Optimize FK table storage
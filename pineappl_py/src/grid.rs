@@ -41,8 +41,9 @@ impl PyOrder {
 #[pymethods]
 impl PyOrder {
     #[new]
-    pub fn new_order(alphas: u32, alpha: u32, logxir: u32, logxif: u32) -> Self {
-        Self::new(Order::new(alphas, alpha, logxir, logxif))
+    #[args(logxia = "0")]
+    pub fn new_order(alphas: u32, alpha: u32, logxir: u32, logxif: u32, logxia: u32) -> Self {
+        Self::new(Order::new(alphas, alpha, logxir, logxif, logxia))
     }
 
     /// Tuple representation.
@@ -57,12 +58,15 @@ impl PyOrder {
     ///         power of :math:` \ln(\xi_r)`
     ///     logxif : int
     ///         power of :math:` \ln(\xi_f)`
-    pub fn as_tuple(&self) -> (u32, u32, u32, u32) {
+    ///     logxia : int
+    ///         power of :math:` \ln(\xi_a)`
+    pub fn as_tuple(&self) -> (u32, u32, u32, u32, u32) {
         (
             self.order.alphas,
             self.order.alpha,
             self.order.logxir,
             self.order.logxif,
+            self.order.logxia,
         )
     }
     /// Return a mask suitable to pass as the `order_mask` parameter of [`Grid::convolute`]. The
@@ -161,6 +165,42 @@ impl PyGrid {
         );
     }
 
+    /// Add a point with a multi-dimensional observable to the grid.
+    ///
+    /// Parameters
+    /// ----------
+    ///     x1 : float
+    ///         first momentum fraction
+    ///     x2 : float
+    ///         second momentum fraction
+    ///     q2 : float
+    ///         process scale
+    ///     order : int
+    ///         order index
+    ///     observables : np.array(float)
+    ///         reference point (to be binned), one value for each bin dimension
+    ///     lumi : int
+    ///         luminosity index
+    ///     weight : float
+    ///         cross section weight
+    pub fn fill_nd(
+        &mut self,
+        x1: f64,
+        x2: f64,
+        q2: f64,
+        order: usize,
+        observables: PyReadonlyArray1<f64>,
+        lumi: usize,
+        weight: f64,
+    ) {
+        self.grid.fill_nd(
+            order,
+            &observables.to_vec().unwrap(),
+            lumi,
+            &Ntuple::<f64> { x1, x2, q2, weight },
+        );
+    }
+
     /// Add an array to the grid.
     ///
     /// Useful to avoid multiple python calls, leading to performance improvement.
@@ -376,11 +416,12 @@ impl PyGrid {
         lumi_mask: PyReadonlyArray1<bool>,
         xi: Vec<(f64, f64)>,
         py: Python<'py>,
-    ) -> &'py PyArray1<f64> {
+    ) -> PyResult<&'py PyArray1<f64>> {
         let mut xfx = |id, x, q2| f64::extract(xfx.call1((id, x, q2)).unwrap()).unwrap();
         let mut alphas = |q2| f64::extract(alphas.call1((q2,)).unwrap()).unwrap();
         let mut lumi_cache = LumiCache::with_one(pdg_id, &mut xfx, &mut alphas);
-        self.grid
+        Ok(self
+            .grid
             .convolute(
                 &mut lumi_cache,
                 &order_mask.to_vec().unwrap(),
@@ -388,7 +429,69 @@ impl PyGrid {
                 &lumi_mask.to_vec().unwrap(),
                 &xi,
             )
-            .into_pyarray(py)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?
+            .into_pyarray(py))
+    }
+
+    /// Set the observables, which combine the bins into, for instance, ratios or normalized
+    /// distributions.
+    ///
+    /// Parameters
+    /// ----------
+    ///     observables : str
+    ///         an expression for each observable, separated by semicolons, for instance
+    ///         `b0 / b1; b0 / (b0 + b1 + b2)`
+    pub fn set_observables(&mut self, observables: &str) -> PyResult<()> {
+        observables
+            .parse()
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+            .and_then(|observables| {
+                self.grid
+                    .set_observables(&observables)
+                    .map_err(|err| PyValueError::new_err(format!("{}", err)))
+            })
+    }
+
+    /// Evaluate the observables defined for this grid.
+    ///
+    /// Parameters
+    /// ----------
+    ///     bin_results : numpy.ndarray(float)
+    ///         results for all bins and a single scale, as returned by `convolute_with_one`
+    ///
+    /// Returns
+    /// -------
+    ///     numpy.ndarray(float) :
+    ///         results for all observables
+    ///
+    /// Raises
+    /// ------
+    ///     ValueError :
+    ///         if the grid does not define observables or `bin_results` is not as long as the grid
+    ///         has bins
+    pub fn evaluate_observables<'py>(
+        &self,
+        bin_results: PyReadonlyArray1<f64>,
+        py: Python<'py>,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        let bin_results = bin_results.as_array().to_vec();
+        let bins = self.grid.bin_info().bins();
+
+        if bin_results.len() != bins {
+            return Err(PyValueError::new_err(format!(
+                "expected results for {} bins, but got {}",
+                bins,
+                bin_results.len()
+            )));
+        }
+
+        match self.grid.observables() {
+            Ok(Some(observables)) => Ok(observables.evaluate(&bin_results, 1).into_pyarray(py)),
+            Ok(None) => Err(PyValueError::new_err(
+                "the grid does not define observables",
+            )),
+            Err(err) => Err(PyValueError::new_err(format!("{}", err))),
+        }
     }
 
     /// Convolute with with an evolution operator.
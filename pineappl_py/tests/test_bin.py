@@ -12,3 +12,12 @@ class TestBinRemapper:
 
         with pytest.raises(AttributeError):
             br._bla()
+
+    def test_from_str(self):
+        br = pineappl.bin.BinRemapper.from_str("0,1,2;0,2,4")
+
+        assert isinstance(br.raw, pineappl.pineappl.PyBinRemapper)
+        assert br.to_remapping_string() == "0,1,2;0,2,4"
+
+        with pytest.raises(ValueError):
+            pineappl.bin.BinRemapper.from_str("|0,1,2")
@@ -6,13 +6,18 @@ import pytest
 
 class TestOrder:
     def test_init(self):
-        args = (2, 1, 0, 1)
+        args = (2, 1, 0, 1, 0)
         o = pineappl.grid.Order(*args)
 
         assert isinstance(o, pineappl.grid.Order)
         assert isinstance(o.raw, pineappl.pineappl.PyOrder)
         assert o.as_tuple() == args
 
+    def test_init_default_logxia(self):
+        o = pineappl.grid.Order(2, 1, 0, 1)
+
+        assert o.as_tuple() == (2, 1, 0, 1, 0)
+
 
 class TestGrid:
     def fake_grid(self):
@@ -29,7 +34,7 @@ class TestGrid:
         assert isinstance(g.raw, pineappl.pineappl.PyGrid)
         # orders
         assert len(g.orders()) == 1
-        assert g.orders()[0].as_tuple() == (3, 0, 0, 0)
+        assert g.orders()[0].as_tuple() == (3, 0, 0, 0, 0)
 
     def test_set_subgrid(self):
         g = self.fake_grid()
@@ -165,6 +170,29 @@ class TestGrid:
         res = g.convolute_with_one(2212, lambda pid, x, q2: x, lambda q2: 1.0)
         pytest.approx(res) == 0.0
 
+    def test_fill_nd(self):
+        g = self.fake_grid()
+        g.set_remapper(
+            pineappl.bin.BinRemapper(
+                np.array([1.0, 1.0]), [(0.0, 1.0), (0.0, 1.0), (0.0, 1.0), (1.0, 2.0)]
+            )
+        )
+        g.fill_nd(0.5, 0.5, 1e4, 0, np.array([0.5, 1.5]), 0, 10.0)
+        res = g.convolute_with_one(2212, lambda pid, x, q2: x, lambda q2: 1.0)
+        assert res[0] == 0.0
+        assert res[1] != 0.0
+
+    def test_observables(self):
+        g = self.fake_grid()
+        g.set_observables("b1 / (b0 + b1); b0 - b1")
+        assert g.key_values()["observables"] == "b1 / (b0 + b1); b0 - b1"
+        res = g.evaluate_observables(np.array([1.0, 3.0]))
+        np.testing.assert_allclose(res, [0.75, -2.0])
+        with pytest.raises(ValueError):
+            g.evaluate_observables(np.array([1.0]))
+        with pytest.raises(ValueError):
+            g.set_observables("b2")
+
     def test_fill_array(self):
         g = self.fake_grid()
         g.fill_array(